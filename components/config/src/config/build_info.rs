@@ -0,0 +1,30 @@
+use libs::tera::Context;
+use libs::time::format_description::well_known::Rfc3339;
+use libs::time::OffsetDateTime;
+
+use errors::Result;
+use utils::source_date;
+
+/// Metadata about the current build, available in the context of every template
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// The version of Zola building the site, set by the CLI
+    pub zola_version: String,
+    /// When the site was built, in RFC3339
+    pub build_time: String,
+    /// The `SOURCE_DATE_EPOCH` used instead of the current time, if any
+    pub source_date_epoch: Option<OffsetDateTime>,
+}
+
+impl BuildInfo {
+    /// The build info of a build starting now, or at `source_date_epoch`, without the `zola_version`
+    pub fn new(source_date_epoch: Option<OffsetDateTime>) -> Result<Self> {
+        let build_time = source_date::now(source_date_epoch).format(&Rfc3339)?;
+        Ok(BuildInfo { zola_version: String::new(), build_time, source_date_epoch })
+    }
+
+    pub fn insert_into(&self, context: &mut Context) {
+        context.insert("zola_version", &self.zola_version);
+        context.insert("build_time", &self.build_time);
+    }
+}
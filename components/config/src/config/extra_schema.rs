@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use libs::tera::{Map, Value};
+use serde::{Deserialize, Serialize};
+
+use errors::{bail, Result};
+
+use crate::extra_type::ExtraType;
+
+fn value_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "float",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "table",
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExtraSchema {
+    /// Whether the `extra` of pages is checked against the schema. Defaults to false
+    pub strict: bool,
+    /// The keys allowed in the `extra` of every page, with their type
+    pub keys: HashMap<String, ExtraType>,
+    /// Additional keys allowed in the pages of a section and its subsections,
+    /// by path of the section relative to the content directory, eg `blog/tutorials`
+    pub sections: HashMap<String, HashMap<String, ExtraType>>,
+}
+
+impl ExtraSchema {
+    /// Checks that the `extra` of a page only has known keys with the right types.
+    /// `components` are the folder names of the page from the content directory.
+    /// Does nothing if not in strict mode.
+    pub fn validate(&self, components: &[String], extra: &Map<String, Value>) -> Result<()> {
+        if !self.strict {
+            return Ok(());
+        }
+
+        let mut schema = self.keys.clone();
+        for (section, keys) in &self.sections {
+            let section_components: Vec<_> = section.split('/').filter(|c| !c.is_empty()).collect();
+            if components.len() >= section_components.len()
+                && components.iter().zip(&section_components).all(|(a, b)| a == b)
+            {
+                schema.extend(keys.iter().map(|(k, v)| (k.clone(), *v)));
+            }
+        }
+
+        for (key, value) in extra {
+            match schema.get(key) {
+                Some(ty) if ty.matches(value) => (),
+                Some(ty) => bail!(
+                    "The `extra.{}` value should be of type {} but is of type {}",
+                    key,
+                    ty,
+                    value_type_name(value)
+                ),
+                None => {
+                    let mut known: Vec<_> = schema.keys().map(|k| k.as_str()).collect();
+                    known.sort_unstable();
+                    bail!(
+                        "Unknown key `{}` in `extra`, the known keys are: [{}]",
+                        key,
+                        known.join(", ")
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
-use libs::globset::GlobSet;
+use std::collections::HashMap;
+
+use libs::globset::{Glob, GlobMatcher, GlobSet};
 use serde::{Deserialize, Serialize};
 
-use errors::Result;
+use errors::{Context, Result};
 use utils::globs::build_ignore_glob_set;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -18,7 +20,7 @@ impl Default for LinkCheckerLevel {
     }
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct LinkChecker {
     /// Skip link checking for these URL prefixes
@@ -29,16 +31,60 @@ pub struct LinkChecker {
     pub internal_level: LinkCheckerLevel,
     /// Emit either "error" or "warn" for broken external links (including anchor links).
     pub external_level: LinkCheckerLevel,
+    /// Whether to warn about the pages that no other page or section links to or lists
+    pub report_unlinked_pages: bool,
     /// A list of file glob patterns to skip link checking on
     pub ignored_files: Vec<String>,
     #[serde(skip_serializing, skip_deserializing)] // not a typo, 2 are needed
     pub ignored_files_globset: Option<GlobSet>,
+    /// Status codes to accept for the external links matching a glob pattern, eg
+    /// `"https://www.linkedin.com/*" = [999]`, on top of the success ones
+    pub accepted_status_codes: HashMap<String, Vec<u16>>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub accepted_status_codes_globs: Vec<(GlobMatcher, Vec<u16>)>,
+    /// How many redirects to follow for an external link before giving up
+    pub max_redirects: usize,
+    /// Whether to warn about the external links that redirect somewhere else
+    pub warn_on_redirects: bool,
+}
+
+impl Default for LinkChecker {
+    fn default() -> Self {
+        LinkChecker {
+            skip_prefixes: Vec::new(),
+            skip_anchor_prefixes: Vec::new(),
+            internal_level: LinkCheckerLevel::default(),
+            external_level: LinkCheckerLevel::default(),
+            report_unlinked_pages: false,
+            ignored_files: Vec::new(),
+            ignored_files_globset: None,
+            accepted_status_codes: HashMap::new(),
+            accepted_status_codes_globs: Vec::new(),
+            max_redirects: 10,
+            warn_on_redirects: false,
+        }
+    }
 }
 
 impl LinkChecker {
     pub fn resolve_globset(&mut self) -> Result<()> {
         let glob_set = build_ignore_glob_set(&self.ignored_files, "files")?;
         self.ignored_files_globset = Some(glob_set);
+
+        self.accepted_status_codes_globs.clear();
+        for (pattern, codes) in &self.accepted_status_codes {
+            let glob = Glob::new(pattern).with_context(|| {
+                format!("Invalid glob in `link_checker.accepted_status_codes`: {}", pattern)
+            })?;
+            self.accepted_status_codes_globs.push((glob.compile_matcher(), codes.clone()));
+        }
         Ok(())
     }
+
+    /// Whether that status code is accepted for that URL by `accepted_status_codes`
+    pub fn accepts_status_code(&self, url: &str, code: u16) -> bool {
+        self.accepted_status_codes_globs
+            .iter()
+            .any(|(glob, codes)| codes.contains(&code) && glob.is_match(url))
+    }
 }
@@ -1,4 +1,4 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, iter, path::Path, sync::Arc};
 
 use libs::syntect::{
     highlighting::{Theme, ThemeSet},
@@ -7,12 +7,27 @@ use libs::syntect::{
 };
 use serde::{Deserialize, Serialize};
 
-use errors::{bail, Result};
+use errors::{bail, Context, Result};
 
 use crate::highlighting::{CLASS_STYLE, THEME_SET};
 
 pub const DEFAULT_HIGHLIGHT_THEME: &str = "base16-ocean-dark";
 
+/// The aliases used when a language isn't in `language_aliases`, as (alias, language)
+pub const DEFAULT_LANGUAGE_ALIASES: [(&str, &str); 3] =
+    [("sh", "bash"), ("yml", "yaml"), ("js", "javascript")];
+
+/// Whether a theme name is the path to a `.tmTheme` file from the site root rather than the name
+/// of a theme
+fn is_theme_path(name: &str) -> bool {
+    name.ends_with(".tmTheme")
+}
+
+fn load_theme(path: &Path) -> Result<Theme> {
+    ThemeSet::get_theme(path)
+        .with_context(|| format!("Failed to load the highlight theme {}", path.display()))
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct ThemeCss {
@@ -20,6 +35,56 @@ pub struct ThemeCss {
     pub theme: String,
     /// In which file are we going to output the CSS
     pub filename: String,
+    /// The selector all the rules are scoped to, eg `[data-theme="dark"]`, to switch
+    /// between several themes at runtime
+    pub selector: Option<String>,
+}
+
+/// Prefixes the selectors of every rule of the CSS generated by syntect with `selector`
+fn scope_theme_css(css: &str, selector: &str) -> String {
+    css.lines()
+        .map(|line| match line.strip_suffix(" {") {
+            Some(selectors) if !line.starts_with(' ') => format!(
+                "{} {{",
+                selectors
+                    .split(", ")
+                    .map(|s| format!("{} {}", selector, s))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+/// How to render the TeX math of the markdown at build time
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MathRenderer {
+    /// Converts the math to MathML, which browsers render natively
+    MathMl,
+}
+
+/// The delimiters of the math to keep as is for client-side rendering, eg with KaTeX
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MathDelimiters {
+    /// `$...$` for inline math and `$$...$$` for display math
+    Dollar,
+}
+
+/// Where the footnotes are listed in the style of GitHub
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FootnotesAt {
+    /// At the end of the page
+    #[default]
+    Page,
+    /// At the end of each section of the page, ie before the next `h1` or `h2` heading.
+    /// The content before the first of those headings is a section as well.
+    Section,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -28,10 +93,14 @@ pub struct Markdown {
     /// Whether to highlight all code blocks found in markdown files. Defaults to false
     pub highlight_code: bool,
     /// Which themes to use for code highlighting. See Readme for supported themes
+    /// It can also be the path to a `.tmTheme` file from the site root.
     /// Defaults to "base16-ocean-dark"
     pub highlight_theme: String,
     /// Generate CSS files for Themes out of syntect
     pub highlight_themes_css: Vec<ThemeCss>,
+    /// The languages of the code blocks to render as diagrams, eg `mermaid`: they are not
+    /// highlighted and are output as `<pre class="{language}">` with the source
+    pub diagram_languages: Vec<String>,
     /// Whether to render emoji aliases (e.g.: :smile: => 😄) in the markdown files
     pub render_emoji: bool,
     /// Whether external links are to be opened in a new tab
@@ -41,12 +110,44 @@ pub struct Markdown {
     pub external_links_no_follow: bool,
     /// Whether to set rel="noreferrer" for all external links
     pub external_links_no_referrer: bool,
+    /// A class to add to all external links, eg to style them differently
+    pub external_links_class: Option<String>,
     /// Whether smart punctuation is enabled (changing quotes, dashes, dots etc in their typographic form)
     pub smart_punctuation: bool,
+    /// Whether the single newlines in paragraphs are rendered as `<br>`
+    pub hard_line_breaks: bool,
+    /// Whether `==text==` is rendered as `<mark>text</mark>`
+    pub highlight_mark: bool,
+    /// Whether `~text~` is rendered as `<sub>text</sub>`
+    pub subscript: bool,
+    /// Whether `^text^` is rendered as `<sup>text</sup>`
+    pub superscript: bool,
+    /// Whether attribute lists like `{.class #id key=value}` can be used at the end of
+    /// paragraphs, and `key=value` ones on headings
+    pub attribute_lists: bool,
+    /// If set, `$...$` and `$$...$$` math is rendered at build time
+    pub render_math: Option<MathRenderer>,
+    /// If set, the math is not parsed as markdown and is wrapped in a `<span class="math">`
+    /// to be rendered client-side. `render_math` takes precedence.
+    pub math: Option<MathDelimiters>,
+    /// Whether to remove the potentially dangerous HTML (scripts, event handlers, `javascript:` URLs...)
+    /// from the rendered content. The HTML from shortcodes is not modified.
+    pub sanitize_html: bool,
+    /// Whether to print a warning when headings would get the same anchor and a `-1`, `-2`...
+    /// suffix is added to the id of the next ones
+    pub warn_duplicate_anchors: bool,
     /// Whether footnotes are rendered at the bottom in the style of GitHub.
     pub bottom_footnotes: bool,
+    /// Where the footnotes are listed, `section` implies `bottom_footnotes`
+    pub footnotes_at: FootnotesAt,
     /// A list of directories to search for additional `.sublime-syntax` and `.tmTheme` files in.
     pub extra_syntaxes_and_themes: Vec<String>,
+    /// The languages of the code blocks to highlight as another one, eg `{ jsonc = "json" }`.
+    /// They take precedence over `DEFAULT_LANGUAGE_ALIASES`
+    pub language_aliases: HashMap<String, String>,
+    /// If set, a visually hidden label for screen readers is added before the code blocks with a
+    /// language, `{lang}` being replaced by that language, eg `"Code in {lang}"`
+    pub code_block_label: Option<String>,
     /// The compiled extra syntaxes into a syntax set
     #[serde(skip_serializing, skip_deserializing)] // not a typo, 2 are need
     pub extra_syntax_set: Option<SyntaxSet>,
@@ -68,6 +169,18 @@ impl Markdown {
         }
     }
 
+    /// Returns the language to highlight a code block of the given language with
+    pub fn resolve_language_alias<'a>(&'a self, language: &'a str) -> &'a str {
+        if let Some(lang) = self.language_aliases.get(language) {
+            return lang;
+        }
+        DEFAULT_LANGUAGE_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == language)
+            .map(|(_, lang)| *lang)
+            .unwrap_or(language)
+    }
+
     /// Gets an arbitrary theme from the THEME_SET or the extra_theme_set
     pub fn get_highlight_theme_by_name(&self, theme_name: &str) -> Option<&Theme> {
         (*self.extra_theme_set)
@@ -76,20 +189,36 @@ impl Markdown {
             .or_else(|| THEME_SET.themes.get(theme_name))
     }
 
-    /// Attempt to load any extra syntaxes and themes found in the extra_syntaxes_and_themes folders
+    /// Attempt to load any extra syntaxes and themes found in the extra_syntaxes_and_themes folders,
+    /// as well as the themes given as a path to a `.tmTheme` file
     pub fn load_extra_syntaxes_and_highlight_themes(
         &self,
         base_path: &Path,
     ) -> Result<(Option<SyntaxSet>, Option<ThemeSet>)> {
-        if self.extra_syntaxes_and_themes.is_empty() {
+        let theme_paths: Vec<_> = iter::once(&self.highlight_theme)
+            .chain(self.highlight_themes_css.iter().map(|t| &t.theme))
+            .filter(|name| is_theme_path(name))
+            .collect();
+        if self.extra_syntaxes_and_themes.is_empty() && theme_paths.is_empty() {
             return Ok((None, None));
         }
 
         let mut ss = SyntaxSetBuilder::new();
         let mut ts = ThemeSet::new();
         for dir in &self.extra_syntaxes_and_themes {
-            ss.add_from_folder(base_path.join(dir), true)?;
-            ts.add_from_folder(base_path.join(dir))?;
+            // The syntax parsing errors have the path of the file
+            ss.add_from_folder(base_path.join(dir), true).with_context(|| {
+                format!("Failed to load the syntaxes of `extra_syntaxes_and_themes` in {}", dir)
+            })?;
+            // Same as `ThemeSet::add_from_folder` but with the path of the theme in the errors
+            for path in ThemeSet::discover_theme_paths(base_path.join(dir))? {
+                let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+                ts.themes.insert(name.to_string(), load_theme(&path)?);
+            }
+        }
+        // Those are looked up by their path
+        for name in theme_paths {
+            ts.themes.insert(name.clone(), load_theme(&base_path.join(name))?);
         }
         let ss = ss.build();
 
@@ -99,10 +228,15 @@ impl Markdown {
         ))
     }
 
-    pub fn export_theme_css(&self, theme_name: &str) -> Result<String> {
+    /// The class names don't depend on the theme so the same HTML works with the CSS of any theme
+    pub fn export_theme_css(&self, theme_name: &str, selector: Option<&str>) -> Result<String> {
         if let Some(theme) = self.get_highlight_theme_by_name(theme_name) {
-            Ok(css_for_theme_with_class_style(theme, CLASS_STYLE)
-                .expect("the function can't even error?"))
+            let css = css_for_theme_with_class_style(theme, CLASS_STYLE)
+                .expect("the function can't even error?");
+            Ok(match selector {
+                Some(s) => scope_theme_css(&css, s),
+                None => css,
+            })
         } else {
             bail!("Theme {} not found", theme_name)
         }
@@ -166,12 +300,17 @@ impl Markdown {
         self.external_links_target_blank
             || self.external_links_no_follow
             || self.external_links_no_referrer
+            || self.external_links_class.is_some()
     }
 
     pub fn construct_external_link_tag(&self, url: &str, title: &str) -> String {
         let mut rel_opts = Vec::new();
         let mut target = "".to_owned();
         let title = if title.is_empty() { "".to_owned() } else { format!("title=\"{}\" ", title) };
+        let class = match self.external_links_class {
+            Some(ref class) => format!("class=\"{}\" ", class),
+            None => "".to_owned(),
+        };
 
         if self.external_links_target_blank {
             // Security risk otherwise
@@ -190,7 +329,7 @@ impl Markdown {
             format!("rel=\"{}\" ", rel_opts.join(" "))
         };
 
-        format!("<a {}{}{}href=\"{}\">", rel, target, title, url)
+        format!("<a {}{}{}{}href=\"{}\">", class, rel, target, title, url)
     }
 }
 
@@ -200,13 +339,27 @@ impl Default for Markdown {
             highlight_code: false,
             highlight_theme: DEFAULT_HIGHLIGHT_THEME.to_owned(),
             highlight_themes_css: Vec::new(),
+            diagram_languages: Vec::new(),
             render_emoji: false,
             external_links_target_blank: false,
             external_links_no_follow: false,
             external_links_no_referrer: false,
+            external_links_class: None,
             smart_punctuation: false,
+            hard_line_breaks: false,
+            highlight_mark: false,
+            subscript: false,
+            superscript: false,
+            attribute_lists: false,
+            render_math: None,
+            math: None,
+            sanitize_html: false,
+            warn_duplicate_anchors: false,
             bottom_footnotes: false,
+            footnotes_at: FootnotesAt::Page,
             extra_syntaxes_and_themes: vec![],
+            language_aliases: HashMap::new(),
+            code_block_label: None,
             extra_syntax_set: None,
             extra_theme_set: Arc::new(None),
             lazy_async_image: false,
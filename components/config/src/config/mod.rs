@@ -1,7 +1,11 @@
+pub mod build_info;
+pub mod extra_schema;
 pub mod languages;
 pub mod link_checker;
 pub mod markup;
+pub mod og_image;
 pub mod search;
+pub mod serve;
 pub mod slugify;
 pub mod taxonomies;
 
@@ -9,13 +13,15 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use libs::globset::GlobSet;
+use libs::ignore::gitignore::Gitignore;
+use libs::tera::to_value;
 use libs::toml::Value as Toml;
 use serde::{Deserialize, Serialize};
 
 use crate::theme::Theme;
-use errors::{anyhow, bail, Result};
+use errors::{anyhow, bail, Context, Result};
 use utils::fs::read_file;
-use utils::globs::build_ignore_glob_set;
+use utils::globs::{build_ignore_gitignore, build_ignore_glob_set, NestedGitignore};
 use utils::slugs::slugify_paths;
 
 // We want a default base url for tests
@@ -29,6 +35,28 @@ pub enum Mode {
     Check,
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UrlStyle {
+    /// Pages are written as `about/index.html` and linked as `about/`, the default
+    #[default]
+    Pretty,
+    /// Pages are written as `about.html` and linked as such
+    Ugly,
+}
+
+/// What to do with an array set in both the site `extra` and the theme `extra`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArrayMerge {
+    /// The array of the site replaces the one of the theme, the default
+    #[default]
+    Replace,
+    /// The items of the site array are added after the ones of the theme array, except the
+    /// ones already in the theme array so they are not repeated
+    Concat,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct Config {
@@ -37,6 +65,9 @@ pub struct Config {
 
     /// Theme to use
     pub theme: Option<String>,
+    /// How the arrays in the theme `extra` are merged with the ones in the site `extra`.
+    /// Tables are always merged recursively.
+    pub theme_extra_arrays: ArrayMerge,
     /// Title of the site. Defaults to None
     pub title: Option<String>,
     /// Description of the site
@@ -46,6 +77,9 @@ pub struct Config {
     pub default_language: String,
     /// The list of supported languages outside of the default one
     pub languages: HashMap<String, languages::LanguageOptions>,
+    /// Whether the content of the default language is also rendered in the other languages it
+    /// isn't translated in, with their `translations` for the `{{ trans(key="...") }}` in it
+    pub shared_content: bool,
     /// The translations strings for the default language
     translations: HashMap<String, String>,
 
@@ -56,8 +90,22 @@ pub struct Config {
     /// The filenames to use for feeds. Used to find the templates, too.
     /// Defaults to ["atom.xml"], with "rss.xml" also having a template provided out of the box.
     pub feed_filenames: Vec<String>,
+    /// Whether to sort the feed items by their `updated` date, falling back to their `date`,
+    /// instead of their `date`. Defaults to false.
+    pub feed_sort_by_updated: bool,
+    /// Whether the feed items carry the full content of the pages instead of their summary.
+    /// Defaults to false: the summary is used if the page has one.
+    pub feed_include_content: bool,
     /// If set, files from static/ will be hardlinked instead of copied to the output dir.
     pub hard_link_static: bool,
+    /// The page rendered to `404.html` instead of the `404.html` template, eg `@/404.md`
+    pub not_found_page: Option<String>,
+    /// Whether internal links can omit the `.md` extension, like `@/blog/post` for
+    /// `@/blog/post.md` and `@/blog` for `@/blog/_index.md`
+    pub internal_links_without_extension: bool,
+    /// If set, the drafts are not skipped when building without them but rendered at a path
+    /// derived from this secret, without being listed anywhere, so their URL can be shared
+    pub drafts_secret: Option<String>,
     pub taxonomies: Vec<taxonomies::TaxonomyConfig>,
     /// The default author for pages.
     pub author: Option<String>,
@@ -66,6 +114,9 @@ pub struct Config {
     pub compile_sass: bool,
     /// Whether to minify the html output
     pub minify_html: bool,
+    /// Whether to rewrite the links to the site in the generated HTML to be relative to each page,
+    /// so the output can be browsed without a server
+    pub relative_urls: bool,
     /// Whether to build the search index for the content
     pub build_search_index: bool,
     /// A list of file glob patterns to ignore when processing the content folder. Defaults to none.
@@ -80,20 +131,67 @@ pub struct Config {
     #[serde(skip_serializing, skip_deserializing)] // not a typo, 2 are needed
     pub ignored_static_globset: Option<GlobSet>,
 
+    /// A list of gitignore-style patterns of paths, relative to the site root, whose changes
+    /// don't trigger a rebuild in `zola serve` and `zola build --watch`. Defaults to none.
+    pub ignored_watch: Vec<String>,
+    #[serde(skip_serializing, skip_deserializing)] // not a typo, 2 are needed
+    pub ignored_watch_gitignore: Option<Gitignore>,
+
+    /// Whether the files ignored by the `.gitignore` files of the site are also ignored
+    /// when processing the content and static folders. Defaults to false
+    pub respect_gitignore: bool,
+    /// Set by the site when `respect_gitignore` is enabled since it needs the site path
+    #[serde(skip_serializing, skip_deserializing)] // not a typo, 2 are needed
+    pub gitignore: Option<NestedGitignore>,
+
+    /// Whether markdown files without front matter are loaded as pages, inferring their
+    /// title and date. Defaults to false
+    pub allow_missing_frontmatter: bool,
+    /// Additional formats for the `date` and `updated` of pages, tried after RFC3339 and
+    /// `YYYY-MM-DD`: either a `time` format description like `[year]/[month]/[day]` or `rfc2822`
+    pub date_formats: Vec<String>,
+    /// When set, the pages without a `<!-- more -->` marker get a summary of this many words
+    /// of their content
+    pub summary_word_count: Option<usize>,
+    /// Whether the markdown of the pages is available as `page.raw_content` in the templates.
+    /// Off by default since it is serialized for every page in the template contexts
+    pub include_raw_content: bool,
+
     /// The mode Zola is currently being ran on. Some logging/feature can differ depending on the
     /// command being used.
     #[serde(skip_serializing)]
     pub mode: Mode,
+    /// The version of Zola and the time of the build, set when loading the site
+    #[serde(skip_serializing, skip_deserializing)] // not a typo, 2 are needed
+    pub build_info: build_info::BuildInfo,
 
     pub output_dir: String,
+    /// If set, the build is aborted when it writes more HTML files than that
+    pub max_pages: Option<usize>,
+    /// If set, the build is aborted when the files it renders add up to more bytes than that
+    pub max_output_bytes: Option<u64>,
+    /// Whether pages are output as `about/index.html` (pretty) or `about.html` (ugly)
+    pub url_style: UrlStyle,
+    /// Patterns for the path of the pages of a section, keyed by the section path relative to
+    /// the `content` directory, eg `blog = "{year}/{month}/{slug}"`
+    pub permalinks: HashMap<String, String>,
     /// Whether dotfiles inside the output directory are preserved when rebuilding the site
     pub preserve_dotfiles_in_output: bool,
+    /// The prefixes of the environment variables `get_env` can read, eg `["DEPLOY_"]`.
+    /// Every variable can be read if not set and none if empty.
+    pub allowed_env_prefixes: Option<Vec<String>>,
 
     pub link_checker: link_checker::LinkChecker,
+    /// The keys allowed in the `extra` of pages, checked in strict mode
+    pub extra_schema: extra_schema::ExtraSchema,
+    /// How to generate the social share images of the pages with `og_image = true`
+    pub og_image: og_image::OgImage,
     /// The setup for which slugification strategies to use for paths, taxonomies and anchors
     pub slugify: slugify::Slugify,
     /// The search config, telling what to include in the search index
     pub search: search::Search,
+    /// The config of `zola serve`
+    pub serve: serve::Serve,
     /// The config for the Markdown rendering: syntax highlighting and everything
     pub markdown: markup::Markdown,
     /// All user params set in `[extra]` in the config
@@ -147,6 +245,14 @@ impl Config {
         config.add_default_language()?;
         config.slugify_taxonomies();
         config.link_checker.resolve_globset()?;
+        config.og_image.validate()?;
+
+        for format in &config.date_formats {
+            if format != "rfc2822" {
+                libs::time::format_description::parse(format)
+                    .with_context(|| format!("Invalid format in `date_formats`: {}", format))?;
+            }
+        }
 
         let content_glob_set = build_ignore_glob_set(&config.ignored_content, "content")?;
         config.ignored_content_globset = Some(content_glob_set);
@@ -154,9 +260,20 @@ impl Config {
         let static_glob_set = build_ignore_glob_set(&config.ignored_static, "static")?;
         config.ignored_static_globset = Some(static_glob_set);
 
+        let watch_gitignore = build_ignore_gitignore(&config.ignored_watch, "watch")?;
+        config.ignored_watch_gitignore = Some(watch_gitignore);
+
         Ok(config)
     }
 
+    /// Whether the path is ignored by a `.gitignore` file, if `respect_gitignore` is enabled
+    pub fn is_gitignored(&self, path: &Path) -> bool {
+        match &self.gitignore {
+            Some(gitignore) => gitignore.is_ignored(path, path.is_dir()),
+            None => false,
+        }
+    }
+
     pub fn default_for_test() -> Self {
         let mut config = Config::default();
         config.add_default_language().unwrap();
@@ -192,6 +309,7 @@ impl Config {
     pub fn make_permalink(&self, path: &str) -> String {
         let trailing_bit = if path.ends_with('/')
             || self.feed_filenames.iter().any(|feed_filename| path.ends_with(feed_filename))
+            || (self.url_style == UrlStyle::Ugly && path.ends_with(".html"))
             || path.is_empty()
         {
             ""
@@ -250,7 +368,45 @@ impl Config {
                 self.extra.insert(key.to_string(), val.clone());
                 continue;
             }
-            merge(self.extra.get_mut(key).unwrap(), val)?;
+            merge(self.extra.get_mut(key).unwrap(), val, self.theme_extra_arrays)?;
+        }
+        self.check_theme_required_extra(theme)
+    }
+
+    /// Errors if some `extra` values the theme requires are missing or of the wrong type.
+    /// This is done after merging so the defaults from the theme are taken into account
+    fn check_theme_required_extra(&self, theme: &Theme) -> Result<()> {
+        let mut required: Vec<_> = theme.required_extra.iter().collect();
+        required.sort_by_key(|(key, _)| *key);
+
+        let mut errors = Vec::new();
+        for (key, expected) in required {
+            let mut parts = key.split('.');
+            let mut value = parts.next().and_then(|p| self.extra.get(p));
+            for part in parts {
+                value = value.and_then(|v| v.get(part));
+            }
+            match value {
+                None => errors.push(format!(
+                    "- `extra.{}`: missing, expected type `{}`",
+                    key,
+                    expected.as_str()
+                )),
+                Some(v) if !expected.matches(&to_value(v)?) => errors.push(format!(
+                    "- `extra.{}`: expected type `{}`, found `{}`",
+                    key,
+                    expected.as_str(),
+                    v.type_str()
+                )),
+                _ => (),
+            }
+        }
+
+        if !errors.is_empty() {
+            bail!(
+                "The theme requires some values in the `[extra]` of the config.toml:\n{}",
+                errors.join("\n")
+            );
         }
         Ok(())
     }
@@ -260,6 +416,7 @@ impl Config {
     pub fn merge_with_theme(&mut self, path: PathBuf, theme_name: &str) -> Result<()> {
         let theme = Theme::from_file(&path, theme_name)?;
         self.add_theme_extra(&theme)
+            .with_context(|| format!("Failed to use the theme {} with this config", theme_name))
     }
 
     /// Returns all the languages settings for languages other than the default one
@@ -287,6 +444,15 @@ impl Config {
         self.mode == Mode::Check
     }
 
+    /// The environment the site is built for, used by the `if_env` blocks of the content:
+    /// `development` with `zola serve`, `production` otherwise
+    pub fn environment(&self) -> &'static str {
+        match self.mode {
+            Mode::Serve => "development",
+            Mode::Build | Mode::Check => "production",
+        }
+    }
+
     pub fn enable_serve_mode(&mut self) {
         self.mode = Mode::Serve;
     }
@@ -345,10 +511,17 @@ impl Config {
 }
 
 // merge TOML data that can be a table, or anything else
-pub fn merge(into: &mut Toml, from: &Toml) -> Result<()> {
+pub fn merge(into: &mut Toml, from: &Toml, arrays: ArrayMerge) -> Result<()> {
     match (from.is_table(), into.is_table()) {
         (false, false) => {
-            // These are not tables so we have nothing to merge
+            if let (Some(from), Some(into), ArrayMerge::Concat) =
+                (from.as_array(), into.as_array_mut(), arrays)
+            {
+                let mut merged = from.clone();
+                merged.extend(into.drain(..).filter(|v| !from.contains(v)));
+                *into = merged;
+            }
+            // These are not tables so we have nothing else to merge
             Ok(())
         }
         (true, true) => {
@@ -361,7 +534,7 @@ pub fn merge(into: &mut Toml, from: &Toml) -> Result<()> {
                     continue;
                 }
                 // Two entries to compare, recurse
-                merge(into_table.get_mut(key).unwrap(), val)?;
+                merge(into_table.get_mut(key).unwrap(), val, arrays)?;
             }
             Ok(())
         }
@@ -381,26 +554,51 @@ impl Default for Config {
             theme: None,
             default_language: "en".to_string(),
             languages: HashMap::new(),
+            shared_content: false,
             generate_feeds: false,
             feed_limit: None,
             feed_filenames: vec!["atom.xml".to_string()],
+            feed_sort_by_updated: false,
+            feed_include_content: false,
             hard_link_static: false,
+            not_found_page: None,
+            internal_links_without_extension: false,
+            drafts_secret: None,
             taxonomies: Vec::new(),
             author: None,
             compile_sass: false,
             minify_html: false,
+            relative_urls: false,
             mode: Mode::Build,
+            build_info: build_info::BuildInfo::default(),
             build_search_index: false,
             ignored_content: Vec::new(),
             ignored_content_globset: None,
             ignored_static: Vec::new(),
             ignored_static_globset: None,
+            ignored_watch: Vec::new(),
+            ignored_watch_gitignore: None,
+            respect_gitignore: false,
+            gitignore: None,
+            allow_missing_frontmatter: false,
+            summary_word_count: None,
+            include_raw_content: false,
+            date_formats: Vec::new(),
             translations: HashMap::new(),
             output_dir: "public".to_string(),
+            max_pages: None,
+            max_output_bytes: None,
+            url_style: UrlStyle::Pretty,
+            theme_extra_arrays: ArrayMerge::Replace,
+            permalinks: HashMap::new(),
             preserve_dotfiles_in_output: false,
+            allowed_env_prefixes: None,
             link_checker: link_checker::LinkChecker::default(),
+            extra_schema: extra_schema::ExtraSchema::default(),
+            og_image: og_image::OgImage::default(),
             slugify: slugify::Slugify::default(),
             search: search::Search::default(),
+            serve: serve::Serve::default(),
             markdown: markup::Markdown::default(),
             extra: HashMap::new(),
             generate_sitemap: true,
@@ -412,6 +610,7 @@ impl Default for Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ExtraType;
     use utils::slugs::SlugifyStrategy;
 
     #[test]
@@ -567,6 +766,28 @@ hello = "world"
         assert_eq!(config.make_permalink("atom.xml"), "http://vincent.is/atom.xml");
     }
 
+    #[test]
+    fn doesnt_add_trailing_slash_to_html_file_with_ugly_urls() {
+        let config = Config {
+            base_url: "http://vincent.is".to_string(),
+            url_style: UrlStyle::Ugly,
+            ..Default::default()
+        };
+        assert_eq!(config.make_permalink("/hello.html"), "http://vincent.is/hello.html");
+        assert_eq!(config.make_permalink("/hello"), "http://vincent.is/hello/");
+    }
+
+    #[test]
+    fn can_parse_url_style() {
+        let config = r#"
+base_url = "example.com"
+url_style = "ugly"
+        "#;
+        let config = Config::parse(config).unwrap();
+        assert_eq!(config.url_style, UrlStyle::Ugly);
+        assert_eq!(Config::default().url_style, UrlStyle::Pretty);
+    }
+
     #[test]
     fn can_merge_with_theme_data_and_preserve_config_value() {
         let config_str = r#"
@@ -745,6 +966,50 @@ ignored_content = ["*.{graphml,iso}", "*.py?", "**/{target,temp_folder}"]
         assert!(g.is_match("content/poetry/zen.py2"));
     }
 
+    #[test]
+    fn can_parse_extra_schema() {
+        let config_str = r#"
+title = "My site"
+base_url = "example.com"
+
+[extra_schema]
+strict = true
+keys = { author = "string", rating = "integer" }
+sections = { "blog/tutorials" = { difficulty = "any" } }
+        "#;
+        let config = Config::parse(config_str).unwrap();
+        assert!(config.extra_schema.strict);
+        assert_eq!(config.extra_schema.keys["rating"], ExtraType::Integer);
+        assert_eq!(config.extra_schema.sections["blog/tutorials"]["difficulty"], ExtraType::Any);
+
+        let config_str = r#"
+title = "My site"
+base_url = "example.com"
+
+[extra_schema]
+keys = { author = "text" }
+        "#;
+        assert!(Config::parse(config_str).is_err());
+    }
+
+    #[test]
+    fn ignored_watch_uses_gitignore_patterns() {
+        let config_str = r#"
+title = "My site"
+base_url = "example.com"
+ignored_watch = ["*.swp", "node_modules/", "/content/drafts", "!keep.swp"]
+        "#;
+
+        let config = Config::parse(config_str).unwrap();
+        let gi = config.ignored_watch_gitignore.unwrap();
+        assert!(gi.matched_path_or_any_parents("content/blog/.post.md.swp", false).is_ignore());
+        assert!(gi.matched_path_or_any_parents("static/node_modules/a/b.js", false).is_ignore());
+        assert!(gi.matched_path_or_any_parents("content/drafts/post.md", false).is_ignore());
+        assert!(!gi.matched_path_or_any_parents("content/blog/drafts/post.md", false).is_ignore());
+        assert!(!gi.matched_path_or_any_parents("content/keep.swp", false).is_ignore());
+        assert!(!gi.matched_path_or_any_parents("content/blog/post.md", false).is_ignore());
+    }
+
     #[test]
     fn non_empty_ignored_static_results_in_vector_of_patterns_and_configured_globset() {
         let config_str = r#"
@@ -901,6 +1166,96 @@ bar = "baz"
         assert!(config.add_theme_extra(&theme).is_err());
     }
 
+    #[test]
+    fn can_override_a_nested_theme_value_and_keep_its_siblings() {
+        let config_str = r#"
+base_url = "http://localhost:1312"
+[extra.social.links]
+github = "me"
+        "#;
+        let mut config = Config::parse(config_str).unwrap();
+        let theme_str = r#"
+[extra.social.links]
+github = "theme"
+twitter = "theme"
+        "#;
+        let theme = Theme::parse(theme_str).unwrap();
+        config.add_theme_extra(&theme).unwrap();
+        let links = &config.extra["social"]["links"];
+        assert_eq!(links["github"].as_str(), Some("me"));
+        assert_eq!(links["twitter"].as_str(), Some("theme"));
+    }
+
+    #[test]
+    fn can_choose_how_theme_extra_arrays_are_merged() {
+        let theme_str = r#"
+[extra]
+menu = ["home", "blog"]
+[extra.nested]
+menu = ["home"]
+        "#;
+        let theme = Theme::parse(theme_str).unwrap();
+        let config_str = r#"
+base_url = "http://localhost:1312"
+[extra]
+menu = ["blog", "about"]
+[extra.nested]
+menu = ["about"]
+        "#;
+
+        let mut config = Config::parse(config_str).unwrap();
+        assert_eq!(config.theme_extra_arrays, ArrayMerge::Replace);
+        config.add_theme_extra(&theme).unwrap();
+        assert_eq!(config.extra["menu"], Toml::try_from(["blog", "about"]).unwrap());
+        assert_eq!(config.extra["nested"]["menu"], Toml::try_from(["about"]).unwrap());
+
+        let mut config =
+            Config::parse(&format!("theme_extra_arrays = \"concat\"\n{}", config_str)).unwrap();
+        config.add_theme_extra(&theme).unwrap();
+        assert_eq!(config.extra["menu"], Toml::try_from(["home", "blog", "about"]).unwrap());
+        assert_eq!(config.extra["nested"]["menu"], Toml::try_from(["home", "about"]).unwrap());
+    }
+
+    #[test]
+    fn errors_if_theme_required_extra_is_missing() {
+        let theme_str = r#"
+[extra]
+social.twitter = "zola"
+
+[required_extra]
+author = "string"
+"social.twitter" = "string"
+        "#;
+        let theme = Theme::parse(theme_str).unwrap();
+
+        let mut config = Config::parse("base_url = \"http://localhost:1312\"").unwrap();
+        let err = config.add_theme_extra(&theme).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The theme requires some values in the `[extra]` of the config.toml:\n- `extra.author`: missing, expected type `string`"
+        );
+
+        let config_str = r#"
+base_url = "http://localhost:1312"
+[extra]
+author = 10
+        "#;
+        let mut config = Config::parse(config_str).unwrap();
+        let err = config.add_theme_extra(&theme).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("- `extra.author`: expected type `string`, found `integer`"));
+
+        let config_str = r#"
+base_url = "http://localhost:1312"
+[extra]
+author = "Vincent"
+        "#;
+        let mut config = Config::parse(config_str).unwrap();
+        assert!(config.add_theme_extra(&theme).is_ok());
+        assert_eq!(config.extra["social"]["twitter"].as_str(), Some("zola"));
+    }
+
     #[test]
     fn default_output_dir() {
         let config = r#"
@@ -1066,4 +1421,20 @@ base_url = "example.com"
         let config = Config::parse(config).unwrap();
         assert!(config.generate_robots_txt);
     }
+
+    #[test]
+    fn errors_on_invalid_date_formats() {
+        let config = r#"
+base_url = "example.com"
+date_formats = ["[year]/[month]/[day]", "rfc2822"]
+"#;
+        assert!(Config::parse(config).is_ok());
+
+        let config = r#"
+base_url = "example.com"
+date_formats = ["[year]/[month"]
+"#;
+        let err = Config::parse(config).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid format in `date_formats`: [year]/[month");
+    }
 }
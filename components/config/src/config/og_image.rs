@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use errors::{bail, Result};
+
+/// Parses a `#rrggbb` or `#rrggbbaa` color
+pub fn parse_hex_color(color: &str) -> Result<[u8; 4]> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("`{}` is not a valid color, expected `#rrggbb` or `#rrggbbaa`", color);
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+    let alpha = if hex.len() == 8 { channel(6) } else { 255 };
+    Ok([channel(0), channel(2), channel(4), alpha])
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OgImage {
+    /// The background image the title is drawn on, relative to the site root.
+    /// A plain `background_color` image of `width`x`height` is used if not set
+    pub template: Option<String>,
+    /// The TrueType/OpenType font used for the title, relative to the site root.
+    /// Required to generate images
+    pub font: Option<String>,
+    /// The size of the title, in pixels
+    pub font_size: f32,
+    /// The color of the title
+    pub text_color: String,
+    /// The color of the image when there is no template
+    pub background_color: String,
+    /// The size of the image when there is no template
+    pub width: u32,
+    pub height: u32,
+    /// The space between the title and the edges of the image, in pixels
+    pub padding: u32,
+}
+
+impl OgImage {
+    pub fn validate(&self) -> Result<()> {
+        for (name, color) in
+            [("text_color", &self.text_color), ("background_color", &self.background_color)]
+        {
+            if let Err(e) = parse_hex_color(color) {
+                bail!("Invalid `og_image.{}`: {}", name, e);
+            }
+        }
+        if self.font_size <= 0.0 {
+            bail!("`og_image.font_size` needs to be positive");
+        }
+        Ok(())
+    }
+}
+
+impl Default for OgImage {
+    fn default() -> Self {
+        OgImage {
+            template: None,
+            font: None,
+            font_size: 64.0,
+            text_color: "#ffffff".to_string(),
+            background_color: "#1f2937".to_string(),
+            width: 1200,
+            height: 630,
+            padding: 80,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_hex_color;
+
+    #[test]
+    fn can_parse_hex_colors() {
+        assert_eq!(parse_hex_color("#ff8000").unwrap(), [255, 128, 0, 255]);
+        assert_eq!(parse_hex_color("#FF800080").unwrap(), [255, 128, 0, 128]);
+        assert!(parse_hex_color("#fff").is_err());
+        assert!(parse_hex_color("#gg0000").is_err());
+    }
+}
@@ -0,0 +1,21 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Serve {
+    /// The content types of the files served by `zola serve`, by extension without the dot,
+    /// eg `{ "bin" = "application/x-custom" }`, overriding the built-in ones
+    pub mime_types: HashMap<String, String>,
+}
+
+impl Serve {
+    /// The content type set for the extension, whatever its case
+    pub fn mime_type(&self, extension: &str) -> Option<&str> {
+        self.mime_types
+            .iter()
+            .find(|(ext, _)| ext.eq_ignore_ascii_case(extension))
+            .map(|(_, mime_type)| mime_type.as_str())
+    }
+}
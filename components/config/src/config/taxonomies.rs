@@ -1,4 +1,27 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
+use utils::slugs::{slugify_paths, SlugifyStrategy};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaxonomySortBy {
+    /// Alphabetical order of the term slugs
+    #[default]
+    Name,
+    /// Terms with the most pages first
+    Count,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaxonomyPagesSortBy {
+    /// Most recent to oldest
+    #[default]
+    Date,
+    /// Lower weight comes first
+    Weight,
+}
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
@@ -15,6 +38,16 @@ pub struct TaxonomyConfig {
     pub render: bool,
     /// Whether to generate a feed only for each taxonomy term, defaults to `false`
     pub feed: bool,
+    /// Whether `/` in terms is used for nested terms, eg `programming/rust` being a child of
+    /// `programming`. A page in a child term is also in all its parent terms. Defaults to `false`
+    pub hierarchical: bool,
+    /// How to sort the terms of the taxonomy, defaults to `name`
+    pub sort_by: TaxonomySortBy,
+    /// How to sort the pages of each term, defaults to `date`
+    pub sort_pages_by: TaxonomyPagesSortBy,
+    /// Custom slugs for some terms, eg `c++` -> `cpp`. The other terms are slugified
+    /// with the `slugify.taxonomies` strategy
+    pub slugs: HashMap<String, String>,
 }
 
 impl Default for TaxonomyConfig {
@@ -26,6 +59,10 @@ impl Default for TaxonomyConfig {
             paginate_path: None,
             render: true,
             feed: false,
+            hierarchical: false,
+            sort_by: TaxonomySortBy::Name,
+            sort_pages_by: TaxonomyPagesSortBy::Date,
+            slugs: HashMap::new(),
         }
     }
 }
@@ -38,4 +75,28 @@ impl TaxonomyConfig {
     pub fn paginate_path(&self) -> &str {
         self.paginate_path.as_deref().unwrap_or("page")
     }
+
+    /// The slug of a term of this taxonomy: its custom one if there is one.
+    /// For hierarchical taxonomies, each part of `a/b` is slugified separately to `a/b` so
+    /// it doesn't end up with the same slug as `a-b`. The custom slugs can be for a part or
+    /// for a parent term, eg `a/b`, in which case its children use it as well.
+    pub fn term_slug(&self, term: &str, strategy: SlugifyStrategy) -> String {
+        if !self.hierarchical {
+            return self.part_slug(term, strategy);
+        }
+        let parts: Vec<_> = term.split('/').collect();
+        let mut slug = String::new();
+        for i in 0..parts.len() {
+            slug = match self.slugs.get(&parts[..=i].join("/")) {
+                Some(custom) => custom.clone(),
+                None if i == 0 => slugify_paths(parts[0], strategy),
+                None => format!("{}/{}", slug, self.part_slug(parts[i], strategy)),
+            };
+        }
+        slug
+    }
+
+    fn part_slug(&self, term: &str, strategy: SlugifyStrategy) -> String {
+        self.slugs.get(term).cloned().unwrap_or_else(|| slugify_paths(term, strategy))
+    }
 }
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use libs::tera::Value;
+use libs::toml::value::Datetime;
+use serde::{Deserialize, Serialize};
+
+/// The type of an `extra` value, for the `[required_extra]` of themes and the `[extra_schema]`
+/// of the config
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtraType {
+    String,
+    Integer,
+    /// Integers are accepted as well
+    Float,
+    Boolean,
+    /// A TOML datetime, or a string that is one since the dates of the front matter are
+    /// converted to strings
+    Datetime,
+    Array,
+    Table,
+    /// Any value, only checking that the key is set
+    Any,
+}
+
+/// How TOML datetimes look like once serialized to a `Value`
+fn is_toml_datetime(value: &Value) -> bool {
+    value.as_object().is_some_and(|o| o.len() == 1 && o.contains_key("$__toml_private_datetime"))
+}
+
+impl ExtraType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExtraType::String => "string",
+            ExtraType::Integer => "integer",
+            ExtraType::Float => "float",
+            ExtraType::Boolean => "boolean",
+            ExtraType::Datetime => "datetime",
+            ExtraType::Array => "array",
+            ExtraType::Table => "table",
+            ExtraType::Any => "any",
+        }
+    }
+
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            ExtraType::String => value.is_string(),
+            ExtraType::Integer => value.is_i64() || value.is_u64(),
+            ExtraType::Float => value.is_number(),
+            ExtraType::Boolean => value.is_boolean(),
+            ExtraType::Datetime => {
+                is_toml_datetime(value)
+                    || value.as_str().is_some_and(|s| s.parse::<Datetime>().is_ok())
+            }
+            ExtraType::Array => value.is_array(),
+            ExtraType::Table => value.is_object() && !is_toml_datetime(value),
+            ExtraType::Any => true,
+        }
+    }
+}
+
+impl fmt::Display for ExtraType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use libs::tera::to_value;
+    use libs::toml::Value as Toml;
+
+    use super::ExtraType;
+
+    #[test]
+    fn can_match_datetimes_from_toml_or_the_front_matter() {
+        let toml: Toml = "date = 2024-01-02".parse::<Toml>().unwrap();
+        let toml_date = to_value(&toml["date"]).unwrap();
+        assert!(ExtraType::Datetime.matches(&toml_date));
+        assert!(!ExtraType::Table.matches(&toml_date));
+        assert!(!ExtraType::String.matches(&toml_date));
+
+        let front_matter_date = to_value("2024-01-02T10:00:00Z").unwrap();
+        assert!(ExtraType::Datetime.matches(&front_matter_date));
+        assert!(ExtraType::String.matches(&front_matter_date));
+        assert!(!ExtraType::Datetime.matches(&to_value("tomorrow").unwrap()));
+    }
+
+    #[test]
+    fn floats_accept_integers() {
+        assert!(ExtraType::Float.matches(&to_value(1).unwrap()));
+        assert!(ExtraType::Float.matches(&to_value(1.5).unwrap()));
+        assert!(!ExtraType::Integer.matches(&to_value(1.5).unwrap()));
+    }
+}
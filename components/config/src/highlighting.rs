@@ -40,7 +40,8 @@ pub fn resolve_syntax_and_theme<'config>(
 ) -> SyntaxAndTheme<'config> {
     let theme = config.markdown.get_highlight_theme();
 
-    if let Some(ref lang) = language {
+    if let Some(lang) = language {
+        let lang = &config.markdown.resolve_language_alias(lang);
         if let Some(ref extra_syntaxes) = config.markdown.extra_syntax_set {
             if let Some(syntax) = extra_syntaxes.find_syntax_by_token(lang) {
                 return SyntaxAndTheme {
@@ -80,3 +81,8 @@ pub fn resolve_syntax_and_theme<'config>(
         }
     }
 }
+
+/// Whether a code block in that language can be highlighted, once its alias is resolved
+pub fn is_known_language(language: &str, config: &Config) -> bool {
+    resolve_syntax_and_theme(Some(language), config).source != HighlightSource::NotFound
+}
@@ -1,18 +1,25 @@
 mod config;
+mod extra_type;
 pub mod highlighting;
 mod theme;
 
 use std::path::Path;
 
 pub use crate::config::{
+    build_info::BuildInfo,
+    extra_schema::ExtraSchema,
     languages::LanguageOptions,
     link_checker::LinkChecker,
     link_checker::LinkCheckerLevel,
+    markup::{FootnotesAt, MathDelimiters, MathRenderer},
+    og_image::{parse_hex_color, OgImage},
     search::{IndexFormat, Search},
+    serve::Serve,
     slugify::Slugify,
-    taxonomies::TaxonomyConfig,
-    Config,
+    taxonomies::{TaxonomyConfig, TaxonomyPagesSortBy, TaxonomySortBy},
+    Config, UrlStyle,
 };
+pub use crate::extra_type::ExtraType;
 use errors::Result;
 
 /// Get and parse the config.
@@ -7,13 +7,18 @@ use serde::{Deserialize, Serialize};
 use errors::{bail, Context, Result};
 use utils::fs::read_file;
 
+use crate::extra_type::ExtraType;
+
 /// Holds the data from a `theme.toml` file.
-/// There are other fields than `extra` in it but Zola
+/// There are other fields than `extra` and `required_extra` in it but Zola
 /// itself doesn't care about them.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Theme {
     /// All user params set in [extra] in the theme.toml
     pub extra: HashMap<String, Toml>,
+    /// The keys of `extra`, possibly dotted like `social.github`, that need to be set
+    /// with their type, set in [required_extra] in the theme.toml
+    pub required_extra: HashMap<String, ExtraType>,
 }
 
 impl Theme {
@@ -25,17 +30,24 @@ impl Theme {
         };
 
         let mut extra = HashMap::new();
+        let mut required_extra = HashMap::new();
         if let Some(theme_table) = theme.as_table() {
             if let Some(ex) = theme_table.get("extra") {
                 if ex.is_table() {
                     extra = ex.clone().try_into().unwrap();
                 }
             }
+            if let Some(req) = theme_table.get("required_extra") {
+                required_extra = req
+                    .clone()
+                    .try_into()
+                    .context("Invalid `required_extra` in the `theme.toml`")?;
+            }
         } else {
             bail!("Expected the `theme.toml` to be a TOML table")
         }
 
-        Ok(Theme { extra })
+        Ok(Theme { extra, required_extra })
     }
 
     /// Parses a theme file from the given path
@@ -123,6 +123,12 @@ impl FileInfo {
         }
     }
 
+    /// The path of the content directory the file is in
+    pub fn content_path(&self) -> PathBuf {
+        let depth = self.relative.split('/').count();
+        self.path.ancestors().nth(depth).map(Path::to_path_buf).unwrap_or_default()
+    }
+
     /// Look for a language in the filename.
     /// If a language has been found, update the name of the file in this struct to
     /// remove it and return the language code
@@ -161,6 +167,12 @@ impl FileInfo {
 
         Ok(lang)
     }
+
+    /// The path of the translation of this file in `lang`, eg `blog/hello.fr.md` for
+    /// `blog/hello.md`
+    pub fn translation_path(&self, lang: &str) -> PathBuf {
+        self.path.with_file_name(format!("{}.{}.md", self.name, lang))
+    }
 }
 
 #[cfg(test)]
@@ -184,6 +196,7 @@ mod tests {
         );
         assert_eq!(file.components, ["posts".to_string(), "tutorials".to_string()]);
         assert_eq!(file.colocated_path, Some("posts/tutorials/python/".to_string()));
+        assert_eq!(file.content_path(), PathBuf::from("/home/vincent/code/site/content"));
     }
 
     #[test]
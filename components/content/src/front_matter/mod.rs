@@ -2,6 +2,8 @@ mod page;
 mod section;
 mod split;
 
-pub use page::PageFrontMatter;
+pub use page::{Author, PageFrontMatter};
 pub use section::SectionFrontMatter;
-pub use split::{split_page_content, split_section_content};
+pub use split::{
+    has_front_matter, split_content, split_page_content, split_section_content, RawFrontMatter,
+};
@@ -1,8 +1,9 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use libs::tera::{Map, Value};
-use serde::Deserialize;
-use time::format_description::well_known::Rfc3339;
+use serde::{Deserialize, Deserializer, Serialize};
+use time::format_description::well_known::{Rfc2822, Rfc3339};
 use time::macros::{format_description, time};
 use time::{Date, OffsetDateTime, PrimitiveDateTime};
 
@@ -11,6 +12,36 @@ use utils::de::{fix_toml_dates, from_unknown_datetime};
 
 use crate::front_matter::split::RawFrontMatter;
 
+/// An author of a page, written either as a string or as a table with at least a `name`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Author {
+    pub name: String,
+    pub email: Option<String>,
+    pub url: Option<String>,
+    pub avatar: Option<String>,
+}
+
+fn from_authors<'de, D>(deserializer: D) -> std::result::Result<Vec<Author>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MaybeAuthor {
+        Name(String),
+        Details(Author),
+    }
+
+    Ok(Vec::<MaybeAuthor>::deserialize(deserializer)?
+        .into_iter()
+        .map(|a| match a {
+            MaybeAuthor::Name(name) => Author { name, email: None, url: None, avatar: None },
+            MaybeAuthor::Details(author) => author,
+        })
+        .collect())
+}
+
 /// The front matter of every page
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(default)]
@@ -53,8 +84,12 @@ pub struct PageFrontMatter {
     pub taxonomies: HashMap<String, Vec<String>>,
     /// Integer to use to order content. Highest is at the bottom, lowest first
     pub weight: Option<usize>,
-    /// The authors of the page.
+    /// The names of the authors of the page, or the strings as written.
+    #[serde(skip_deserializing)]
     pub authors: Vec<String>,
+    /// The authors of the page with all their details
+    #[serde(rename = "authors", deserialize_with = "from_authors")]
+    pub author_details: Vec<Author>,
     /// All aliases for that page. Zola will create HTML templates that will
     /// redirect to this
     #[serde(skip_serializing)]
@@ -66,6 +101,10 @@ pub struct PageFrontMatter {
     /// Defaults to `true` but is only used if search if explicitly enabled in the config.
     #[serde(skip_serializing)]
     pub in_search_index: bool,
+    /// Whether to generate a social share image with the title of the page,
+    /// following the `og_image` config
+    #[serde(skip_serializing)]
+    pub og_image: bool,
     /// Any extra parameter present in the front matter
     pub extra: Map<String, Value>,
 }
@@ -86,8 +125,45 @@ fn parse_datetime(d: &str) -> Option<OffsetDateTime> {
         .ok()
 }
 
+/// Parses a date with one of the `date_formats` of the config, which might only have a date
+/// or no offset, in which case UTC is assumed
+fn parse_datetime_with_format(d: &str, format: &str) -> Option<OffsetDateTime> {
+    if format == "rfc2822" {
+        return OffsetDateTime::parse(d, &Rfc2822).ok();
+    }
+    // The formats are validated when loading the config
+    let format = time::format_description::parse(format).ok()?;
+    OffsetDateTime::parse(d, &format)
+        .or_else(|_| PrimitiveDateTime::parse(d, &format).map(|dt| dt.assume_utc()))
+        .or_else(|_| {
+            Date::parse(d, &format)
+                .map(|date| PrimitiveDateTime::new(date, time!(0:00)).assume_utc())
+        })
+        .ok()
+}
+
+/// Parses the date, trying the `date_formats` if it is not in one of the default formats.
+/// The dates in those formats are then rewritten in RFC3339 so the Tera `date` filter can use them.
+fn parse_date_field(date: &mut Option<String>, date_formats: &[String]) -> Option<OffsetDateTime> {
+    let d = date.as_deref()?;
+    if let Some(dt) = parse_datetime(d) {
+        return Some(dt);
+    }
+    let dt = date_formats.iter().find_map(|format| parse_datetime_with_format(d, format))?;
+    *date = dt.format(&Rfc3339).ok();
+    Some(dt)
+}
+
 impl PageFrontMatter {
     pub fn parse(raw: &RawFrontMatter) -> Result<PageFrontMatter> {
+        Self::parse_with_date_formats(raw, &[])
+    }
+
+    /// Same as `parse` but also tries the given formats for the dates, see `config.date_formats`
+    pub fn parse_with_date_formats(
+        raw: &RawFrontMatter,
+        date_formats: &[String],
+    ) -> Result<PageFrontMatter> {
         let mut f: PageFrontMatter = raw.deserialize()?;
 
         if let Some(ref slug) = f.slug {
@@ -107,7 +183,8 @@ impl PageFrontMatter {
             _ => unreachable!("Got something other than a table in page extra"),
         };
 
-        f.date_to_datetime();
+        f.parse_dates(date_formats);
+        f.authors = f.author_details.iter().map(|a| a.name.clone()).collect();
 
         for terms in f.taxonomies.values() {
             for term in terms {
@@ -122,17 +199,66 @@ impl PageFrontMatter {
                 bail!("`date` could not be parsed: {}.", date);
             }
         }
+        if let Some(ref updated) = f.updated {
+            if f.updated_datetime.is_none() {
+                bail!("`updated` could not be parsed: {}.", updated);
+            }
+        }
 
         Ok(f)
     }
 
+    /// Creates the front matter of a file that doesn't have one.
+    /// The title is taken from the first `# heading` of the content, or from the filename
+    /// if there isn't one.
+    pub fn infer_from_content(file_path: &Path, content: &str) -> PageFrontMatter {
+        let mut in_code_block = false;
+        let mut title = None;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with("```") || line.starts_with("~~~") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+            if in_code_block {
+                continue;
+            }
+            if let Some(heading) = line.strip_prefix("# ") {
+                let heading = heading.trim().trim_end_matches('#').trim_end();
+                if !heading.is_empty() {
+                    title = Some(heading.to_string());
+                    break;
+                }
+            }
+        }
+
+        let title = title.or_else(|| {
+            let name = file_path.file_stem().map(|s| s.to_string_lossy().to_string());
+            // Colocated pages take the name of their folder, like their slug
+            if name.as_deref() == Some("index") {
+                file_path
+                    .parent()
+                    .and_then(|p| p.file_name())
+                    .map(|s| s.to_string_lossy().to_string())
+            } else {
+                name
+            }
+        });
+
+        PageFrontMatter { title, ..Default::default() }
+    }
+
     /// Converts the TOML datetime to a time::OffsetDateTime
     /// Also grabs the year/month/day tuple that will be used in serialization
     pub fn date_to_datetime(&mut self) {
-        self.datetime = self.date.as_ref().map(|s| s.as_ref()).and_then(parse_datetime);
+        self.parse_dates(&[]);
+    }
+
+    fn parse_dates(&mut self, date_formats: &[String]) {
+        self.datetime = parse_date_field(&mut self.date, date_formats);
         self.datetime_tuple = self.datetime.map(|dt| (dt.year(), dt.month().into(), dt.day()));
 
-        self.updated_datetime = self.updated.as_ref().map(|s| s.as_ref()).and_then(parse_datetime);
+        self.updated_datetime = parse_date_field(&mut self.updated, date_formats);
         self.updated_datetime_tuple =
             self.updated_datetime.map(|dt| (dt.year(), dt.month().into(), dt.day()));
     }
@@ -161,8 +287,10 @@ impl Default for PageFrontMatter {
             taxonomies: HashMap::new(),
             weight: None,
             authors: Vec::new(),
+            author_details: Vec::new(),
             aliases: Vec::new(),
             template: None,
+            og_image: false,
             extra: Map::new(),
         }
     }
@@ -170,11 +298,29 @@ impl Default for PageFrontMatter {
 
 #[cfg(test)]
 mod tests {
-    use crate::front_matter::page::PageFrontMatter;
+    use crate::front_matter::page::{Author, PageFrontMatter};
     use crate::front_matter::split::RawFrontMatter;
     use libs::tera::to_value;
+    use std::path::Path;
     use test_case::test_case;
+    use time::format_description::well_known::Rfc3339;
     use time::macros::datetime;
+    use time::OffsetDateTime;
+
+    #[test]
+    fn can_infer_title_from_heading() {
+        let content = "Some intro\n```\n# not a heading\n```\n# The *title* #\n\n# Another one";
+        let res = PageFrontMatter::infer_from_content(Path::new("content/hello.md"), content);
+        assert_eq!(res.title.unwrap(), "The *title*");
+    }
+
+    #[test]
+    fn can_infer_title_from_filename() {
+        let res = PageFrontMatter::infer_from_content(Path::new("content/hello.md"), "## Hello");
+        assert_eq!(res.title.unwrap(), "hello");
+        let res = PageFrontMatter::infer_from_content(Path::new("content/world/index.md"), "");
+        assert_eq!(res.title.unwrap(), "world");
+    }
 
     #[test_case(&RawFrontMatter::Toml(r#"  "#); "toml")]
     #[test_case(&RawFrontMatter::Toml(r#"  "#); "yaml")]
@@ -380,6 +526,38 @@ date: 2002/10/12
 
     #[test_case(&RawFrontMatter::Toml(r#"
 title = "Hello"
+date = "2023/06/01"
+"#), datetime!(2023 - 06 - 01 0:00 UTC); "slashes toml")]
+    #[test_case(&RawFrontMatter::Yaml(r#"
+title: Hello
+date: 2023/06/01
+"#), datetime!(2023 - 06 - 01 0:00 UTC); "slashes yaml")]
+    #[test_case(&RawFrontMatter::Toml(r#"
+title = "Hello"
+date = "Thu, 01 Jun 2023 10:30:00 +0200"
+"#), datetime!(2023 - 06 - 01 10:30 +2); "rfc2822")]
+    #[test_case(&RawFrontMatter::Toml(r#"
+title = "Hello"
+date = "01.06.2023 10:30"
+"#), datetime!(2023 - 06 - 01 10:30 UTC); "without offset")]
+    fn can_parse_date_with_additional_formats(content: &RawFrontMatter, expected: OffsetDateTime) {
+        let date_formats = vec![
+            "[year]/[month]/[day]".to_string(),
+            "rfc2822".to_string(),
+            "[day].[month].[year] [hour]:[minute]".to_string(),
+        ];
+        let res = PageFrontMatter::parse_with_date_formats(content, &date_formats).unwrap();
+        assert_eq!(res.datetime, Some(expected));
+        assert_eq!(res.datetime_tuple, Some((2023, 6, 1)));
+        // Rewritten so the Tera `date` filter can use it
+        assert_eq!(res.date, expected.format(&Rfc3339).ok());
+
+        // The default is strict
+        assert!(PageFrontMatter::parse(content).is_err());
+    }
+
+    #[test_case(&RawFrontMatter::Toml(r#"
+title = "Hello"
 description = "hey there"
 date = 2002-14-01
 "#); "toml")]
@@ -393,6 +571,13 @@ date: 2002-14-01
         assert!(res.is_err());
     }
 
+    #[test]
+    fn cannot_parse_invalid_updated_date() {
+        let content = &RawFrontMatter::Toml(r#"updated = "2002/10/12""#);
+        let res = PageFrontMatter::parse(content);
+        assert_eq!(res.unwrap_err().to_string(), "`updated` could not be parsed: 2002/10/12.");
+    }
+
     #[test_case(&RawFrontMatter::Toml(r#"
 title = "Hello"
 description = "hey there"
@@ -563,4 +748,43 @@ authors:
             res2.authors
         );
     }
+
+    #[test_case(&RawFrontMatter::Toml(r#"
+authors = [
+    "person1@example.com (Person One)",
+    { name = "Person Two", url = "https://two.example.com", avatar = "/two.png" },
+]
+"#); "toml")]
+    #[test_case(&RawFrontMatter::Yaml(r#"
+title: Hello World
+authors:
+    - person1@example.com (Person One)
+    - name: Person Two
+      url: https://two.example.com
+      avatar: /two.png
+"#); "yaml")]
+    fn can_parse_authors_tables(content: &RawFrontMatter) {
+        let res = PageFrontMatter::parse(content).unwrap();
+        assert_eq!(
+            res.authors,
+            vec!["person1@example.com (Person One)".to_owned(), "Person Two".to_owned()]
+        );
+        assert_eq!(res.author_details[0].name, "person1@example.com (Person One)");
+        assert_eq!(res.author_details[0].url, None);
+        assert_eq!(
+            res.author_details[1],
+            Author {
+                name: "Person Two".to_owned(),
+                email: None,
+                url: Some("https://two.example.com".to_owned()),
+                avatar: Some("/two.png".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn errors_on_unknown_author_fields() {
+        let content = RawFrontMatter::Toml(r#"authors = [{ name = "Bob", avtar = "/bob.png" }]"#);
+        assert!(PageFrontMatter::parse(&content).is_err());
+    }
 }
@@ -1,8 +1,9 @@
 use libs::tera::{Map, Value};
 use serde::{Deserialize, Serialize};
 
-use errors::Result;
+use errors::{bail, Result};
 use utils::de::fix_toml_dates;
+use utils::templates::is_template_extension;
 use utils::types::InsertAnchor;
 
 use crate::front_matter::split::RawFrontMatter;
@@ -60,9 +61,18 @@ pub struct SectionFrontMatter {
     /// Useful when the section shouldn't split up the parent section, like
     /// sections for each year under a posts section.
     pub transparent: bool,
+    /// Whether the `lower`/`higher` pages of the pages of this section and of all its subsections
+    /// are found in a single order, using the `sort_by` of this section, so the navigation doesn't
+    /// stop at the end of a subsection. Defaults to `false`.
+    #[serde(skip_serializing)]
+    pub siblings_across_subsections: bool,
     /// Optional template for all pages in this section (including the pages of children section)
     #[serde(skip_serializing)]
     pub page_template: Option<String>,
+    /// Extensions of additional files the pages of this section (including the pages of children
+    /// section) are rendered to, eg `txt` renders them with `page.txt` to `index.txt` as well
+    #[serde(skip_serializing)]
+    pub page_output_formats: Vec<String>,
     /// All aliases for that page. Zola will create HTML templates that will
     /// redirect to this
     #[serde(skip_serializing)]
@@ -70,6 +80,14 @@ pub struct SectionFrontMatter {
     /// Whether to generate a feed for the current section
     #[serde(skip_serializing)]
     pub generate_feeds: bool,
+    /// The filenames of the feeds of the section, eg `["podcast.xml"]`, instead of the
+    /// `feed_filenames` of the config
+    #[serde(skip_serializing)]
+    pub feed_filenames: Vec<String>,
+    /// Whether the feeds of the section carry the full content of the pages instead of their
+    /// summary, overriding `feed_include_content` of the config
+    #[serde(skip_serializing)]
+    pub feed_include_content: Option<bool>,
     /// Any extra parameter present in the front matter
     pub extra: Map<String, Value>,
 }
@@ -78,6 +96,18 @@ impl SectionFrontMatter {
     pub fn parse(raw: &RawFrontMatter) -> Result<SectionFrontMatter> {
         let mut f: SectionFrontMatter = raw.deserialize()?;
 
+        for format in &f.page_output_formats {
+            if format == "html"
+                || !format.chars().all(|c| c.is_ascii_alphanumeric())
+                || !is_template_extension(format)
+            {
+                bail!(
+                    "`page_output_formats` should only contain template extensions other than `html`, like `txt` or `json`, got `{}`",
+                    format
+                );
+            }
+        }
+
         f.extra = match fix_toml_dates(f.extra) {
             Value::Object(o) => o,
             _ => unreachable!("Got something other than a table in section extra"),
@@ -111,9 +141,13 @@ impl Default for SectionFrontMatter {
             insert_anchor_links: InsertAnchor::None,
             in_search_index: true,
             transparent: false,
+            siblings_across_subsections: false,
             page_template: None,
+            page_output_formats: Vec::new(),
             aliases: Vec::new(),
             generate_feeds: false,
+            feed_filenames: Vec::new(),
+            feed_include_content: None,
             extra: Map::new(),
             draft: false,
         }
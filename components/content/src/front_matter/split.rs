@@ -26,7 +26,7 @@ pub enum RawFrontMatter<'a> {
 }
 
 impl RawFrontMatter<'_> {
-    pub(crate) fn deserialize<T>(&self) -> Result<T>
+    pub fn deserialize<T>(&self) -> Result<T>
     where
         T: serde::de::DeserializeOwned,
     {
@@ -41,9 +41,17 @@ impl RawFrontMatter<'_> {
     }
 }
 
+/// Whether the file starts with a TOML or YAML front matter
+pub fn has_front_matter(content: &str) -> bool {
+    TOML_RE.is_match(content) || YAML_RE.is_match(content)
+}
+
 /// Split a file between the front matter and its content
 /// Will return an error if the front matter wasn't found
-fn split_content<'c>(file_path: &Path, content: &'c str) -> Result<(RawFrontMatter<'c>, &'c str)> {
+pub fn split_content<'c>(
+    file_path: &Path,
+    content: &'c str,
+) -> Result<(RawFrontMatter<'c>, &'c str)> {
     let (re, is_toml) = if TOML_RE.is_match(content) {
         (&TOML_RE as &Regex, true)
     } else if YAML_RE.is_match(content) {
@@ -86,14 +94,16 @@ pub fn split_section_content<'c>(
 
 /// Split a file between the front matter and its content
 /// Returns a parsed `PageFrontMatter` and the rest of the content
+/// `date_formats` are the additional formats to parse the dates with, see `config.date_formats`
 pub fn split_page_content<'c>(
     file_path: &Path,
     content: &'c str,
+    date_formats: &[String],
 ) -> Result<(PageFrontMatter, &'c str)> {
     let (front_matter, content) = split_content(file_path, content)?;
-    let meta = PageFrontMatter::parse(&front_matter).with_context(|| {
-        format!("Error when parsing front matter of section `{}`", file_path.to_string_lossy())
-    })?;
+    let meta = PageFrontMatter::parse_with_date_formats(&front_matter, date_formats).with_context(
+        || format!("Error when parsing front matter of section `{}`", file_path.to_string_lossy()),
+    )?;
     Ok((meta, content))
 }
 
@@ -121,7 +131,7 @@ date: 2002-10-12
 Hello
 "#; "yaml")]
     fn can_split_page_content_valid(content: &str) {
-        let (front_matter, content) = split_page_content(Path::new(""), content).unwrap();
+        let (front_matter, content) = split_page_content(Path::new(""), content, &[]).unwrap();
         assert_eq!(content, "Hello\n");
         assert_eq!(front_matter.title.unwrap(), "Title");
     }
@@ -171,7 +181,7 @@ description: hey there
 date: 2002-10-12
 ---"#; "yaml no newline")]
     fn can_split_content_with_only_frontmatter_valid(content: &str) {
-        let (front_matter, content) = split_page_content(Path::new(""), content).unwrap();
+        let (front_matter, content) = split_page_content(Path::new(""), content, &[]).unwrap();
         assert_eq!(content, "");
         assert_eq!(front_matter.title.unwrap(), "Title");
     }
@@ -205,7 +215,7 @@ date: 2002-10-02T15:00:00Z
 ---
 ---"#, "---"; "yaml with minuses in content")]
     fn can_split_content_lazily(content: &str, expected: &str) {
-        let (front_matter, content) = split_page_content(Path::new(""), content).unwrap();
+        let (front_matter, content) = split_page_content(Path::new(""), content, &[]).unwrap();
         assert_eq!(content, expected);
         assert_eq!(front_matter.title.unwrap(), "Title");
     }
@@ -245,7 +255,7 @@ description: hey there
 date: 2002-10-12
 ----"#; "yaml too many dashes")]
     fn errors_if_cannot_locate_frontmatter(content: &str) {
-        let res = split_page_content(Path::new(""), content);
+        let res = split_page_content(Path::new(""), content, &[]);
         assert!(res.is_err());
     }
 }
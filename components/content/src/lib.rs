@@ -12,7 +12,9 @@ mod types;
 mod utils;
 
 pub use file_info::FileInfo;
-pub use front_matter::{PageFrontMatter, SectionFrontMatter};
+pub use front_matter::{
+    has_front_matter, split_content, Author, PageFrontMatter, RawFrontMatter, SectionFrontMatter,
+};
 pub use library::Library;
 pub use page::Page;
 pub use pagination::Paginator;
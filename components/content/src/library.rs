@@ -88,7 +88,8 @@ impl Library {
             self.insert_reverse_aliases(&file_path, entries);
         }
 
-        for (taxa_name, terms) in &page.meta.taxonomies {
+        let taxonomies = if page.unlisted { None } else { Some(&page.meta.taxonomies) };
+        for (taxa_name, terms) in taxonomies.into_iter().flatten() {
             for term in terms {
                 // Safe unwraps as we create all lang/taxa and we validated that they are correct
                 // before getting there
@@ -134,7 +135,7 @@ impl Library {
                 .or_insert(set! {source.to_path_buf()});
         };
 
-        for (_, page) in &self.pages {
+        for (_, page) in self.pages.iter().filter(|(_, p)| !p.unlisted) {
             for (internal_link, _) in &page.internal_links {
                 add_backlink(internal_link, &page.file.path);
             }
@@ -164,6 +165,20 @@ impl Library {
                         .insert(term, page_path.iter().map(|p| &self.pages[p]).collect());
                 }
 
+                if taxo_config.hierarchical {
+                    // A page in `a/b` is also in `a`, even if no page is directly in `a`
+                    for (term, page_path) in terms_pages {
+                        for (idx, _) in term.match_indices('/') {
+                            let parent_pages = taxo_found.terms.entry(&term[..idx]).or_default();
+                            for p in page_path {
+                                if !parent_pages.iter().any(|pp| &pp.file.path == p) {
+                                    parent_pages.push(&self.pages[p]);
+                                }
+                            }
+                        }
+                    }
+                }
+
                 taxonomies.push(Taxonomy::new(taxo_found, config));
             }
         }
@@ -208,6 +223,44 @@ impl Library {
                 s.ignored_pages = unsortable;
             }
         }
+
+        self.fill_siblings_across_subsections();
+    }
+
+    /// Fills the siblings of the pages of the sections with `siblings_across_subsections` set,
+    /// ordering the pages of the section and of all its subsections together
+    fn fill_siblings_across_subsections(&mut self) {
+        let mut sections: Vec<_> = self
+            .sections
+            .values()
+            .filter(|s| s.meta.siblings_across_subsections && s.meta.sort_by != SortBy::None)
+            .collect();
+        // The outermost sections are done last so their order wins
+        sections.sort_by_key(|s| std::cmp::Reverse(s.ancestors.len()));
+
+        let mut updates = Vec::new();
+        for section in sections {
+            let mut paths = Vec::new();
+            let mut to_visit = vec![&section.file.path];
+            while let Some(path) = to_visit.pop() {
+                let s = &self.sections[path];
+                paths.extend(&s.pages);
+                to_visit.extend(&s.subsections);
+            }
+            // The pages of transparent subsections are also in their parent section
+            paths.sort();
+            paths.dedup();
+            let pages: Vec<_> = paths.into_iter().map(|p| &self.pages[p]).collect();
+            updates.push(sort_pages(&pages, section.meta.sort_by).0);
+        }
+
+        for sorted in updates {
+            for (i, page_path) in sorted.iter().enumerate() {
+                let p = self.pages.get_mut(page_path).unwrap();
+                p.lower = if i > 0 { Some(sorted[i - 1].clone()) } else { None };
+                p.higher = sorted.get(i + 1).cloned();
+            }
+        }
     }
 
     /// Find out the direct subsections of each subsection if there are some
@@ -299,12 +352,17 @@ impl Library {
                 continue;
             }
             let parent_filename = &index_filename_by_lang[&page.lang];
-            add_translation(&page.file.canonical, path);
+            if !page.unlisted {
+                add_translation(&page.file.canonical, path);
+            }
             let mut parent_section_path = page.file.parent.join(parent_filename);
 
             while let Some(parent_section) = self.sections.get_mut(&parent_section_path) {
                 let is_transparent = parent_section.meta.transparent;
-                parent_section.pages.push(path.clone());
+                // Unlisted pages still get their ancestors and templates, they are only not listed
+                if !page.unlisted {
+                    parent_section.pages.push(path.clone());
+                }
                 page.ancestors = ancestors.get(&parent_section_path).cloned().unwrap_or_default();
                 // Don't forget to push the actual parent
                 page.ancestors.push(parent_section.file.relative.clone());
@@ -321,6 +379,18 @@ impl Library {
                         }
                     }
                 }
+                // Same for the output formats
+                if page.output_formats.is_empty() {
+                    page.output_formats = page
+                        .ancestors
+                        .iter()
+                        .rev()
+                        .filter_map(|a| self.sections.get(&content_path.join(a)))
+                        .map(|s| &s.meta.page_output_formats)
+                        .find(|formats| !formats.is_empty())
+                        .cloned()
+                        .unwrap_or_default();
+                }
 
                 if !is_transparent {
                     break;
@@ -373,13 +443,69 @@ impl Library {
     pub fn find_sections_by_path(&self, paths: &[PathBuf]) -> Vec<&Section> {
         paths.iter().map(|p| &self.sections[p]).collect()
     }
+
+    /// Finds the pages in the same language as that page sharing the most taxonomy terms with it,
+    /// only counting the given taxonomies if there are some.
+    /// Pages sharing as many terms are sorted by date, the most recent first.
+    pub fn find_related_pages(
+        &self,
+        path: &Path,
+        taxonomies: &[String],
+        count: usize,
+    ) -> Vec<&Page> {
+        let Some(page) = self.pages.get(path) else {
+            return Vec::new();
+        };
+        let terms: AHashSet<(&str, &str)> = page
+            .meta
+            .taxonomies
+            .iter()
+            .filter(|(name, _)| taxonomies.is_empty() || taxonomies.contains(name))
+            .flat_map(|(name, terms)| terms.iter().map(move |t| (name.as_str(), t.as_str())))
+            .collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut related: Vec<(usize, &Page)> = self
+            .pages
+            .values()
+            .filter(|p| {
+                p.file.path != page.file.path && p.lang == page.lang && p.meta.render && !p.unlisted
+            })
+            .filter_map(|p| {
+                let shared = p
+                    .meta
+                    .taxonomies
+                    .iter()
+                    .flat_map(|(name, terms)| {
+                        terms.iter().map(move |t| (name.as_str(), t.as_str()))
+                    })
+                    .filter(|t| terms.contains(t))
+                    .count();
+                if shared == 0 {
+                    None
+                } else {
+                    Some((shared, p))
+                }
+            })
+            .collect();
+        related.sort_by(|(a_shared, a), (b_shared, b)| {
+            b_shared
+                .cmp(a_shared)
+                .then_with(|| b.meta.datetime.cmp(&a.meta.datetime))
+                .then_with(|| a.permalink.cmp(&b.permalink))
+        });
+
+        related.into_iter().take(count).map(|(_, p)| p).collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::FileInfo;
-    use config::{LanguageOptions, TaxonomyConfig};
+    use config::{LanguageOptions, TaxonomyConfig, TaxonomyPagesSortBy, TaxonomySortBy};
     use std::collections::HashMap;
     use utils::slugs::SlugifyStrategy;
 
@@ -685,6 +811,194 @@ mod tests {
         assert_eq!(authors.items[0].permalink, "http://a-website.com/authors/vincent-prouillet/");
     }
 
+    #[test]
+    fn can_make_hierarchical_taxonomies() {
+        let mut config = Config::default_for_test();
+        config.languages.get_mut("en").unwrap().taxonomies = vec![
+            TaxonomyConfig {
+                name: "categories".to_string(),
+                hierarchical: true,
+                ..TaxonomyConfig::default()
+            },
+            TaxonomyConfig { name: "tags".to_string(), ..TaxonomyConfig::default() },
+        ];
+        config.slugify_taxonomies();
+
+        let page1 = create_page_w_taxa(
+            "a.md",
+            "en",
+            vec![("categories", vec!["Programming/Rust"]), ("tags", vec!["a/b"])],
+        );
+        let page2 = create_page_w_taxa(
+            "b.md",
+            "en",
+            vec![("categories", vec!["Programming", "Programming/Rust/Async"])],
+        );
+        let page3 = create_page_w_taxa("c.md", "en", vec![("categories", vec!["Programming/JS"])]);
+        let taxonomies = taxonomies!(config, [page1, page2, page3]);
+
+        let categories = taxonomies.iter().find(|t| t.kind.name == "categories").unwrap();
+        let terms: Vec<_> = categories
+            .items
+            .iter()
+            .map(|t| (t.name.as_str(), t.permalink.as_str(), t.pages.len()))
+            .collect();
+        assert_eq!(
+            terms,
+            vec![
+                ("Programming", "http://a-website.com/categories/programming/", 3),
+                ("Programming/JS", "http://a-website.com/categories/programming/js/", 1),
+                ("Programming/Rust", "http://a-website.com/categories/programming/rust/", 2),
+                (
+                    "Programming/Rust/Async",
+                    "http://a-website.com/categories/programming/rust/async/",
+                    1
+                ),
+            ]
+        );
+        assert_eq!(
+            categories.items[3].ancestors,
+            vec!["Programming".to_string(), "Programming/Rust".to_string()]
+        );
+        assert!(categories.items[0].ancestors.is_empty());
+
+        // Other taxonomies are not affected
+        let tags = taxonomies.iter().find(|t| t.kind.name == "tags").unwrap();
+        assert_eq!(tags.items.len(), 1);
+        assert_eq!(tags.items[0].permalink, "http://a-website.com/tags/a-b/");
+    }
+
+    #[test]
+    fn can_use_custom_slugs_for_parent_hierarchical_terms() {
+        let mut config = Config::default_for_test();
+        config.languages.get_mut("en").unwrap().taxonomies = vec![TaxonomyConfig {
+            name: "categories".to_string(),
+            hierarchical: true,
+            slugs: HashMap::from([
+                ("Programming/Rust".to_string(), "rust".to_string()),
+                ("C++".to_string(), "cpp".to_string()),
+            ]),
+            ..TaxonomyConfig::default()
+        }];
+        config.slugify_taxonomies();
+
+        let page = create_page_w_taxa(
+            "a.md",
+            "en",
+            vec![("categories", vec!["Programming/Rust/Async", "Programming/C++"])],
+        );
+        let taxonomies = taxonomies!(config, [page]);
+
+        let paths: Vec<_> =
+            taxonomies[0].items.iter().map(|t| (t.name.as_str(), t.path.as_str())).collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("Programming", "/categories/programming/"),
+                ("Programming/C++", "/categories/programming/cpp/"),
+                ("Programming/Rust", "/categories/rust/"),
+                ("Programming/Rust/Async", "/categories/rust/async/"),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_use_custom_slugs_for_taxonomy_terms() {
+        let mut config = Config::default_for_test();
+        config.languages.get_mut("en").unwrap().taxonomies = vec![TaxonomyConfig {
+            name: "tags".to_string(),
+            slugs: HashMap::from([("c++".to_string(), "cpp".to_string())]),
+            ..TaxonomyConfig::default()
+        }];
+        config.slugify_taxonomies();
+
+        let page1 = create_page_w_taxa("a.md", "en", vec![("tags", vec!["c++", "rust"])]);
+        let page2 = create_page_w_taxa("b.md", "en", vec![("tags", vec!["c"])]);
+        let taxonomies = taxonomies!(config, [page1, page2]);
+
+        let tags = &taxonomies[0];
+        let paths: Vec<_> = tags.items.iter().map(|t| (t.name.as_str(), t.path.as_str())).collect();
+        // Unmapped terms are slugified as usual and `c` doesn't collide with `c++`
+        assert_eq!(paths, vec![("c", "/tags/c/"), ("c++", "/tags/cpp/"), ("rust", "/tags/rust/")]);
+    }
+
+    #[test]
+    fn can_sort_taxonomy_term_pages_by_weight() {
+        let mut config = Config::default_for_test();
+        config.languages.get_mut("en").unwrap().taxonomies = vec![TaxonomyConfig {
+            name: "tags".to_string(),
+            sort_pages_by: TaxonomyPagesSortBy::Weight,
+            ..TaxonomyConfig::default()
+        }];
+        config.slugify_taxonomies();
+
+        let page = |path: &str, weight: Option<usize>| {
+            let mut page = create_page_w_taxa(path, "en", vec![("tags", vec!["rust"])]);
+            page.meta.weight = weight;
+            page.permalink = format!("https://example.com/{}/", path.trim_end_matches(".md"));
+            page
+        };
+        let taxonomies = taxonomies!(
+            config,
+            [
+                page("a.md", Some(3)),
+                page("c.md", Some(1)),
+                page("d.md", None),
+                page("b.md", Some(1))
+            ]
+        );
+
+        let rust = &taxonomies[0].items[0];
+        // Same weights are sorted by permalink and pages without a weight are at the end
+        assert_eq!(
+            rust.pages,
+            vec![
+                PathBuf::from("b.md"),
+                PathBuf::from("c.md"),
+                PathBuf::from("a.md"),
+                PathBuf::from("d.md")
+            ]
+        );
+    }
+
+    #[test]
+    fn can_sort_taxonomy_terms_by_count() {
+        let mut config = Config::default_for_test();
+        config.languages.get_mut("en").unwrap().taxonomies = vec![
+            TaxonomyConfig {
+                name: "tags".to_string(),
+                sort_by: TaxonomySortBy::Count,
+                ..TaxonomyConfig::default()
+            },
+            TaxonomyConfig { name: "categories".to_string(), ..TaxonomyConfig::default() },
+        ];
+        config.slugify_taxonomies();
+
+        let page1 = create_page_w_taxa(
+            "a.md",
+            "en",
+            vec![("tags", vec!["rust", "db", "js"]), ("categories", vec!["b", "a"])],
+        );
+        let page2 = create_page_w_taxa("b.md", "en", vec![("tags", vec!["rust", "js", "c"])]);
+        let page3 = create_page_w_taxa(
+            "c.md",
+            "en",
+            vec![("tags", vec!["rust", "html"]), ("categories", vec!["b"])],
+        );
+        let taxonomies = taxonomies!(config, [page1, page2, page3]);
+
+        let tags = taxonomies.iter().find(|t| t.kind.name == "tags").unwrap();
+        let terms: Vec<_> = tags.items.iter().map(|t| (t.name.as_str(), t.pages.len())).collect();
+        // Terms with the same count are still sorted by name
+        assert_eq!(terms, vec![("rust", 3), ("js", 2), ("c", 1), ("db", 1), ("html", 1)]);
+
+        // Sorted by name by default
+        let categories = taxonomies.iter().find(|t| t.kind.name == "categories").unwrap();
+        let terms: Vec<_> =
+            categories.items.iter().map(|t| (t.name.as_str(), t.pages.len())).collect();
+        assert_eq!(terms, vec![("a", 1), ("b", 2)]);
+    }
+
     #[test]
     fn can_make_multiple_language_taxonomies() {
         let mut config = Config::default_for_test();
@@ -784,4 +1098,173 @@ mod tests {
         );
         assert_eq!(library.backlinks["_index.md"], set! {PathBuf::from("page2.md")});
     }
+
+    #[test]
+    fn links_with_anchors_are_backlinks_to_the_page() {
+        let mut page1 = create_page("a.md", "en", PageSort::Title("A"));
+        page1.permalink = "https://example.com/a/".to_owned();
+        page1.internal_links.push(("b.md".to_owned(), Some("usage".to_owned())));
+        page1.internal_links.push(("b.md".to_owned(), None));
+        let page2 = create_page("b.md", "en", PageSort::Title("B"));
+        let mut library = Library::default();
+        library.insert_page(page1);
+        library.insert_page(page2);
+        library.fill_backlinks();
+
+        assert_eq!(library.backlinks["b.md"], set! {PathBuf::from("a.md")});
+        let serialized =
+            libs::serde_json::to_value(library.pages[Path::new("b.md")].serialize(&library))
+                .unwrap();
+        assert_eq!(
+            serialized["backlinks"],
+            libs::serde_json::json!([{"permalink": "https://example.com/a/", "title": "A"}])
+        );
+    }
+
+    #[test]
+    fn can_serialize_page_refs_without_the_pages() {
+        let config = Config::default_for_test();
+        let mut library = Library::default();
+        library.insert_section(create_section(
+            "content/blog/_index.md",
+            "en",
+            0,
+            false,
+            SortBy::Title,
+        ));
+        for i in 0..50 {
+            let mut page = create_page(&format!("content/blog/{}.md", i), "en", PageSort::None);
+            page.meta.title = Some(format!("Post {:02}", i));
+            page.permalink = format!("https://example.com/blog/{}/", i);
+            page.content = "<p>Lorem ipsum dolor sit amet</p>".repeat(100);
+            library.insert_page(page);
+        }
+        library.populate_sections(&config, Path::new("content"));
+
+        let section = &library.sections[Path::new("content/blog/_index.md")];
+        let full = libs::serde_json::to_value(section.serialize(&library)).unwrap();
+        let basic = libs::serde_json::to_value(section.serialize_basic(&library)).unwrap();
+
+        assert_eq!(full["pages"].as_array().unwrap().len(), 50);
+        assert!(basic["pages"].as_array().unwrap().is_empty());
+        let page_refs = basic["page_refs"].as_array().unwrap();
+        assert_eq!(page_refs.len(), 50);
+        assert_eq!(page_refs, full["page_refs"].as_array().unwrap());
+        assert_eq!(
+            page_refs[0],
+            libs::serde_json::json!({
+                "relative_path": "blog/0.md",
+                "permalink": "https://example.com/blog/0/",
+                "path": "",
+                "title": "Post 00",
+                "date": null,
+            })
+        );
+        // Only the metadata of the pages is allocated when serializing the page refs
+        let full_size = libs::serde_json::to_string(&full).unwrap().len();
+        let basic_size = libs::serde_json::to_string(&basic).unwrap().len();
+        assert!(basic_size * 20 < full_size);
+    }
+
+    #[test]
+    fn can_find_related_pages_by_shared_terms() {
+        let mut config = Config::default_for_test();
+        let taxonomies = vec![
+            TaxonomyConfig { name: "tags".to_string(), ..TaxonomyConfig::default() },
+            TaxonomyConfig { name: "authors".to_string(), ..TaxonomyConfig::default() },
+        ];
+        config.languages.get_mut("en").unwrap().taxonomies = taxonomies.clone();
+        config
+            .languages
+            .insert("fr".to_owned(), LanguageOptions { taxonomies, ..LanguageOptions::default() });
+        config.slugify_taxonomies();
+        let mut library = Library::new(&config);
+        let mut pages = vec![
+            create_page_w_taxa(
+                "a.md",
+                "en",
+                vec![("tags", vec!["rust", "web"]), ("authors", vec!["bob"])],
+            ),
+            create_page_w_taxa("one-tag.md", "en", vec![("tags", vec!["rust"])]),
+            create_page_w_taxa("two-tags.md", "en", vec![("tags", vec!["rust", "web"])]),
+            create_page_w_taxa("two-tags-older.md", "en", vec![("tags", vec!["web", "rust"])]),
+            create_page_w_taxa("author.md", "en", vec![("authors", vec!["bob"])]),
+            create_page_w_taxa("unrelated.md", "en", vec![("tags", vec!["python"])]),
+            create_page_w_taxa("fr.md", "fr", vec![("tags", vec!["rust", "web"])]),
+        ];
+        for (i, date) in ["2022-01-01", "2022-03-01", "2022-02-01", "2021-01-01"].iter().enumerate()
+        {
+            pages[i].meta.date = Some(date.to_string());
+            pages[i].meta.date_to_datetime();
+        }
+        for page in pages {
+            library.insert_page(page);
+        }
+
+        let related: Vec<_> = library
+            .find_related_pages(Path::new("a.md"), &[], 5)
+            .iter()
+            .map(|p| p.file.path.clone())
+            .collect();
+        assert_eq!(
+            related,
+            vec![
+                PathBuf::from("two-tags.md"),
+                PathBuf::from("two-tags-older.md"),
+                PathBuf::from("one-tag.md"),
+                PathBuf::from("author.md"),
+            ]
+        );
+
+        let related: Vec<_> = library
+            .find_related_pages(Path::new("a.md"), &["authors".to_string()], 5)
+            .iter()
+            .map(|p| p.file.path.clone())
+            .collect();
+        assert_eq!(related, vec![PathBuf::from("author.md")]);
+
+        assert_eq!(library.find_related_pages(Path::new("a.md"), &[], 2).len(), 2);
+        assert!(library.find_related_pages(Path::new("missing.md"), &[], 5).is_empty());
+    }
+
+    #[test]
+    fn can_find_siblings_across_subsections() {
+        let config = Config::default_for_test();
+        let mut library = Library::default();
+        let mut blog = create_section("content/blog/_index.md", "en", 0, false, SortBy::Date);
+        blog.meta.siblings_across_subsections = true;
+        library.insert_section(blog);
+        for path in
+            ["content/_index.md", "content/blog/2023/_index.md", "content/blog/2024/_index.md"]
+        {
+            library.insert_section(create_section(path, "en", 0, false, SortBy::Date));
+        }
+        for (path, date) in [
+            ("content/blog/2023/a.md", "2023-06-01"),
+            ("content/blog/2023/b.md", "2023-12-01"),
+            ("content/blog/2024/c.md", "2024-02-01"),
+        ] {
+            library.insert_page(create_page(path, "en", PageSort::Date(date)));
+        }
+        library.populate_sections(&config, Path::new("content"));
+
+        let page = |path: &str| &library.pages[&PathBuf::from(path)];
+        // The most recent page of 2023 is followed by the oldest one of 2024
+        assert_eq!(
+            page("content/blog/2023/b.md").lower,
+            Some(PathBuf::from("content/blog/2024/c.md"))
+        );
+        assert_eq!(
+            page("content/blog/2024/c.md").higher,
+            Some(PathBuf::from("content/blog/2023/b.md"))
+        );
+        assert_eq!(
+            page("content/blog/2023/b.md").higher,
+            Some(PathBuf::from("content/blog/2023/a.md"))
+        );
+        assert_eq!(page("content/blog/2024/c.md").lower, None);
+        assert_eq!(page("content/blog/2023/a.md").higher, None);
+        // The subsections keep their own pages
+        assert_eq!(library.sections[&PathBuf::from("content/blog/2024/_index.md")].pages.len(), 1);
+    }
 }
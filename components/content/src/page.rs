@@ -4,22 +4,23 @@ use std::path::{Path, PathBuf};
 
 use libs::once_cell::sync::Lazy;
 use libs::regex::Regex;
+use libs::sha2::{Digest, Sha256};
 use libs::tera::{Context as TeraContext, Tera};
 
-use config::Config;
-use errors::{Context, Result};
+use config::{Config, UrlStyle};
+use errors::{bail, Context, Result};
 use markdown::{render_content, RenderContext};
 use utils::slugs::slugify_paths;
-use utils::table_of_contents::Heading;
+use utils::table_of_contents::{FlatHeading, Heading};
 use utils::templates::{render_template, ShortcodeDefinition};
 use utils::types::InsertAnchor;
 
 use crate::file_info::FileInfo;
-use crate::front_matter::{split_page_content, PageFrontMatter};
+use crate::front_matter::{has_front_matter, split_page_content, PageFrontMatter};
 use crate::library::Library;
 use crate::ser::SerializingPage;
 use crate::utils::get_reading_analytics;
-use crate::utils::{find_related_assets, has_anchor};
+use crate::utils::{find_related_assets, get_file_modified_date, has_anchor, truncate_html_words};
 use utils::anchors::has_anchor_id;
 use utils::fs::read_file;
 
@@ -66,6 +67,8 @@ pub struct Page {
     pub higher: Option<PathBuf>,
     /// Toc made from the headings of the markdown file
     pub toc: Vec<Heading>,
+    /// The headings of the markdown file in order, without nesting
+    pub toc_flat: Vec<FlatHeading>,
     /// How many words in the raw content
     pub word_count: Option<usize>,
     /// How long would it take to read the raw content.
@@ -82,6 +85,16 @@ pub struct Page {
     pub internal_links: Vec<(String, Option<String>)>,
     /// The list of all links to external webpages. They can be validated by the `link_checker`.
     pub external_links: Vec<String>,
+    /// The URL of the generated social share image, if `og_image` is set in the front matter
+    pub og_image: Option<String>,
+    /// The extensions of the additional files the page is rendered to, from the
+    /// `page_output_formats` of its sections
+    pub output_formats: Vec<String>,
+    /// Whether `raw_content` is serialized for the templates, from `include_raw_content` in the config
+    pub include_raw_content: bool,
+    /// Whether the page is not in the pages of its sections, the taxonomies, the feeds or the
+    /// sitemap: the drafts published under a secret path and the `not_found_page`
+    pub unlisted: bool,
 }
 
 impl Page {
@@ -93,20 +106,31 @@ impl Page {
 
     /// Parse a page given the content of the .md file
     /// Files without front matter or with invalid front matter are considered
-    /// erroneous
+    /// erroneous, unless `allow_missing_frontmatter` is set in the config
     pub fn parse(
         file_path: &Path,
         content: &str,
         config: &Config,
         base_path: &Path,
     ) -> Result<Page> {
-        let (meta, content) = split_page_content(file_path, content)?;
+        let is_missing_front_matter =
+            config.allow_missing_frontmatter && !has_front_matter(content);
+        let (meta, content) = if is_missing_front_matter {
+            (PageFrontMatter::infer_from_content(file_path, content), content)
+        } else {
+            split_page_content(file_path, content, &config.date_formats)?
+        };
         let mut page = Page::new(file_path, meta, base_path);
+        config
+            .extra_schema
+            .validate(&page.file.components, &page.meta.extra)
+            .with_context(|| format!("Invalid `extra` in {}", file_path.display()))?;
 
         page.lang =
             page.file.find_language(&config.default_language, &config.other_languages_codes())?;
 
         page.raw_content = content.to_string();
+        page.include_raw_content = config.include_raw_content;
         let (word_count, reading_time) = get_reading_analytics(&page.raw_content);
         page.word_count = Some(word_count);
         page.reading_time = Some(reading_time);
@@ -133,9 +157,19 @@ impl Page {
             }
         }
 
+        if is_missing_front_matter && page.meta.date.is_none() {
+            page.meta.date = get_file_modified_date(file_path, config.build_info.source_date_epoch);
+            page.meta.date_to_datetime();
+        }
+
         page.slug = {
             if let Some(ref slug) = page.meta.slug {
-                slugify_paths(slug, config.slugify.paths)
+                // A slug like `guides/intro` is allowed for nested URLs, each part is slugified
+                slug.split('/')
+                    .filter(|part| !part.trim().is_empty())
+                    .map(|part| slugify_paths(part, config.slugify.paths))
+                    .collect::<Vec<_>>()
+                    .join("/")
             } else if let Some(slug) = slug_from_dated_filename {
                 slugify_paths(&slug, config.slugify.paths)
             } else {
@@ -152,7 +186,10 @@ impl Page {
                 format!("/{}", path)
             }
         } else {
-            let mut path = if page.file.components.is_empty() {
+            let section_path = page.file.components.join("/");
+            let mut path = if let Some(pattern) = config.permalinks.get(&section_path) {
+                page.path_from_pattern(pattern)?
+            } else if page.file.components.is_empty() {
                 if page.file.name == "index" && page.file.colocated_path.is_none() {
                     String::new()
                 } else {
@@ -172,6 +209,10 @@ impl Page {
         if !page.path.ends_with('/') {
             page.path = format!("{}/", page.path);
         }
+        // A `path = "about.html"` is already the file of an ugly URL
+        if config.url_style == UrlStyle::Ugly && page.path.ends_with(".html/") {
+            page.path = format!("{}/", page.path.trim_end_matches(".html/"));
+        }
 
         page.components = page
             .path
@@ -179,6 +220,12 @@ impl Page {
             .map(|p| p.to_string())
             .filter(|p| !p.is_empty())
             .collect::<Vec<_>>();
+
+        // With ugly URLs, `/posts/hello/` is output as `/posts/hello.html` instead.
+        // The root index page still needs to be an `index.html`
+        if config.url_style == UrlStyle::Ugly && page.path != "/" {
+            page.path = format!("{}.html", page.path.trim_end_matches('/'));
+        }
         page.permalink = config.make_permalink(&page.path);
 
         Ok(page)
@@ -186,6 +233,56 @@ impl Page {
 
     pub fn find_language(&mut self) {}
 
+    /// Moves the page to a path derived from the `secret` and its file so it can be shared
+    /// without being guessable, and marks it as unlisted. Its aliases are removed as they
+    /// would redirect to that path.
+    pub fn make_unlisted(&mut self, secret: &str, config: &Config) {
+        let mut hasher = Sha256::new();
+        hasher.update(secret.as_bytes());
+        hasher.update(self.file.relative.as_bytes());
+        let hash = format!("{:x}", hasher.finalize())[..32].to_string();
+
+        self.path = if config.url_style == UrlStyle::Ugly {
+            format!("/{}.html", hash)
+        } else {
+            format!("/{}/", hash)
+        };
+        self.components = vec![hash];
+        self.permalink = config.make_permalink(&self.path);
+        self.meta.aliases.clear();
+        self.unlisted = true;
+    }
+
+    /// Builds the path of the page from a `permalinks` pattern of the config, eg
+    /// `{year}/{month}/{slug}`
+    fn path_from_pattern(&self, pattern: &str) -> Result<String> {
+        let mut path = pattern.trim_matches('/').replace("{slug}", &self.slug);
+
+        if path.contains("{year}") || path.contains("{month}") || path.contains("{day}") {
+            let (year, month, day) = match self.meta.datetime_tuple {
+                Some(d) => d,
+                None => bail!(
+                    "Page `{}` has no date but the permalink pattern `{}` of its section requires one",
+                    self.file.path.display(),
+                    pattern
+                ),
+            };
+            path = path
+                .replace("{year}", &year.to_string())
+                .replace("{month}", &format!("{:02}", month))
+                .replace("{day}", &format!("{:02}", day));
+        }
+
+        if path.contains('{') {
+            bail!(
+                "Unknown token in the permalink pattern `{}`, the available ones are `{{year}}`, `{{month}}`, `{{day}}` and `{{slug}}`",
+                pattern
+            );
+        }
+
+        Ok(path)
+    }
+
     /// Read and parse a .md file into a Page struct
     pub fn from_file<P: AsRef<Path>>(path: P, config: &Config, base_path: &Path) -> Result<Page> {
         let path = path.as_ref();
@@ -203,6 +300,17 @@ impl Page {
         Ok(page)
     }
 
+    /// Parses the file of this page again as its translation in `lang`, for the `shared_content`
+    /// of the config. The copy has the path of the `{name}.{lang}.md` file it replaces
+    pub fn translated_copy(&self, lang: &str, config: &Config, base_path: &Path) -> Result<Page> {
+        let content = read_file(&self.file.path)?;
+        let path = self.file.translation_path(lang);
+        let mut page = Page::parse(&path, &content, config, base_path)?;
+        page.assets = self.assets.clone();
+        page.serialized_assets = self.serialized_assets.clone();
+        Ok(page)
+    }
+
     /// We need access to all pages url to render links relative to content
     /// so that can't happen at the same time as parsing
     pub fn render_markdown(
@@ -213,6 +321,7 @@ impl Page {
         anchor_insert: InsertAnchor,
         shortcode_definitions: &HashMap<String, ShortcodeDefinition>,
     ) -> Result<()> {
+        let content_path = self.file.content_path();
         let mut context = RenderContext::new(
             tera,
             config,
@@ -223,14 +332,20 @@ impl Page {
         );
         context.set_shortcode_definitions(shortcode_definitions);
         context.set_current_page_path(&self.file.relative);
+        context.set_content_path(&content_path);
         context.tera_context.insert("page", &SerializingPage::new(self, None, false));
 
         let res = render_content(&self.raw_content, &context)
             .with_context(|| format!("Failed to render content of {}", self.file.path.display()))?;
 
-        self.summary = res.summary;
+        // An explicit `<!-- more -->` always wins over the automatic summary
+        self.summary = match (res.summary, config.summary_word_count) {
+            (None, Some(count)) => truncate_html_words(&res.body, count),
+            (summary, _) => summary,
+        };
         self.content = res.body;
         self.toc = res.toc;
+        self.toc_flat = res.toc_flat;
         self.external_links = res.external_links;
         self.internal_links = res.internal_links;
 
@@ -243,9 +358,33 @@ impl Page {
             Some(ref l) => l,
             None => "page.html",
         };
+        self.render_template(tpl_name, tera, config, library)
+    }
 
+    /// Renders the page to one of its `output_formats`, with the template of the same name as
+    /// the HTML one but with the extension of the format, eg `page.txt`
+    pub fn render_output_format(
+        &self,
+        format: &str,
+        tera: &Tera,
+        config: &Config,
+        library: &Library,
+    ) -> Result<String> {
+        let html_tpl = self.meta.template.as_deref().unwrap_or("page.html");
+        let tpl_name = format!("{}.{}", html_tpl.strip_suffix(".html").unwrap_or(html_tpl), format);
+        self.render_template(&tpl_name, tera, config, library)
+    }
+
+    fn render_template(
+        &self,
+        tpl_name: &str,
+        tera: &Tera,
+        config: &Config,
+        library: &Library,
+    ) -> Result<String> {
         let mut context = TeraContext::new();
         context.insert("config", &config.serialize(&self.lang));
+        config.build_info.insert_into(&mut context);
         context.insert("current_url", &self.permalink);
         context.insert("current_path", &self.path);
         context.insert("page", &self.serialize(library));
@@ -292,12 +431,17 @@ impl Page {
     pub fn serialize_without_siblings<'a>(&'a self, library: &'a Library) -> SerializingPage<'a> {
         SerializingPage::new(self, Some(library), false)
     }
+
+    /// Only the metadata of the page: no content, summary, toc or siblings
+    pub fn serialize_metadata_only<'a>(&'a self, library: &'a Library) -> SerializingPage<'a> {
+        SerializingPage::new(self, Some(library), false).without_content()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
-    use std::fs::{create_dir, File};
+    use std::fs::{create_dir, create_dir_all, File};
     use std::io::Write;
     use std::path::{Path, PathBuf};
 
@@ -306,10 +450,88 @@ mod tests {
     use tempfile::tempdir;
 
     use crate::Page;
-    use config::{Config, LanguageOptions};
+    use config::{Config, ExtraType, LanguageOptions, UrlStyle};
+    use utils::site::resolve_internal_link;
     use utils::slugs::SlugifyStrategy;
     use utils::types::InsertAnchor;
 
+    fn extra_schema_config() -> Config {
+        let mut config = Config::default_for_test();
+        config.extra_schema.strict = true;
+        config.extra_schema.keys.insert("author".to_string(), ExtraType::String);
+        config.extra_schema.keys.insert("rating".to_string(), ExtraType::Integer);
+        config
+            .extra_schema
+            .sections
+            .insert("blog".to_string(), HashMap::from([("series".to_string(), ExtraType::Array)]));
+        config
+    }
+
+    #[test]
+    fn can_validate_extra_against_schema() {
+        let config = extra_schema_config();
+        let content = r#"
++++
+title = "Hello"
+[extra]
+author = "Vincent"
+rating = 5
+series = ["zola"]
++++
+Hello world"#;
+        let res =
+            Page::parse(&PathBuf::from("content/blog/post.md"), content, &config, &PathBuf::new());
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap().meta.extra["author"], "Vincent");
+    }
+
+    #[test]
+    fn errors_on_unknown_extra_key_in_strict_mode() {
+        let config = extra_schema_config();
+        let content = r#"
++++
+title = "Hello"
+[extra]
+athor = "Vincent"
++++
+Hello world"#;
+        let res = Page::parse(Path::new("content/post.md"), content, &config, &PathBuf::new());
+        let err = format!("{:#}", res.unwrap_err());
+        assert!(err.starts_with("Invalid `extra` in content/post.md"));
+        assert!(
+            err.ends_with("Unknown key `athor` in `extra`, the known keys are: [author, rating]")
+        );
+
+        // Sections keys only apply to their pages
+        let content = "+++\n[extra]\nseries = []\n+++\n";
+        assert!(
+            Page::parse(Path::new("content/post.md"), content, &config, &PathBuf::new()).is_err()
+        );
+
+        // Nothing is checked outside of strict mode
+        let mut config = extra_schema_config();
+        config.extra_schema.strict = false;
+        assert!(
+            Page::parse(Path::new("content/post.md"), content, &config, &PathBuf::new()).is_ok()
+        );
+    }
+
+    #[test]
+    fn errors_on_mistyped_extra_key_in_strict_mode() {
+        let config = extra_schema_config();
+        let content = r#"
++++
+title = "Hello"
+[extra]
+rating = "5"
++++
+Hello world"#;
+        let res = Page::parse(Path::new("content/post.md"), content, &config, &PathBuf::new());
+        let err = format!("{:#}", res.unwrap_err());
+        assert!(err
+            .ends_with("The `extra.rating` value should be of type integer but is of type string"));
+    }
+
     #[test]
     fn can_parse_a_valid_page() {
         let config = Config::default_for_test();
@@ -398,6 +620,43 @@ Hello world"#;
         assert_eq!(page.permalink, config.make_permalink("hello-world"));
     }
 
+    #[test]
+    fn can_make_ugly_url_from_sections_and_slug() {
+        let content = r#"
+    +++
+    slug = "hello-world"
+    +++
+    Hello world"#;
+        let mut conf = Config::default();
+        conf.base_url = "http://hello.com/".to_string();
+        conf.url_style = UrlStyle::Ugly;
+        let res =
+            Page::parse(Path::new("content/posts/intro/start.md"), content, &conf, &PathBuf::new());
+        assert!(res.is_ok());
+        let page = res.unwrap();
+        assert_eq!(page.path, "/posts/intro/hello-world.html");
+        assert_eq!(page.components, vec!["posts", "intro", "hello-world"]);
+        assert_eq!(page.permalink, "http://hello.com/posts/intro/hello-world.html");
+    }
+
+    #[test]
+    fn doesnt_add_the_html_extension_twice_to_ugly_urls() {
+        let content = r#"
+    +++
+    path = "about/team.html"
+    +++
+    Hello world"#;
+        let mut conf = Config::default();
+        conf.base_url = "http://hello.com/".to_string();
+        conf.url_style = UrlStyle::Ugly;
+        let res = Page::parse(Path::new("content/team.md"), content, &conf, &PathBuf::new());
+        assert!(res.is_ok());
+        let page = res.unwrap();
+        assert_eq!(page.path, "/about/team.html");
+        assert_eq!(page.components, vec!["about", "team"]);
+        assert_eq!(page.permalink, "http://hello.com/about/team.html");
+    }
+
     #[test]
     fn can_make_url_from_slug_only_with_no_special_chars() {
         let content = r#"
@@ -415,6 +674,29 @@ Hello world"#;
         assert_eq!(page.permalink, config.make_permalink("hello-world"));
     }
 
+    #[test]
+    fn can_make_nested_url_from_slug_with_slashes() {
+        let content = r#"
+    +++
+    slug = "/Guides/Getting Started/"
+    +++
+    Hello world"#;
+        let config = Config::default();
+        let page =
+            Page::parse(Path::new("content/docs/intro.md"), content, &config, &PathBuf::new())
+                .unwrap();
+        assert_eq!(page.slug, "guides/getting-started");
+        assert_eq!(page.path, "/docs/guides/getting-started/");
+        assert_eq!(page.components, vec!["docs", "guides", "getting-started"]);
+        assert_eq!(page.permalink, config.make_permalink("/docs/guides/getting-started/"));
+
+        // Internal links are still using the file path
+        let mut permalinks = HashMap::new();
+        permalinks.insert(page.file.relative.clone(), page.permalink.clone());
+        let link = resolve_internal_link("@/docs/intro.md", &permalinks, false).unwrap();
+        assert_eq!(link.permalink, page.permalink);
+    }
+
     #[test]
     fn can_make_url_from_utf8_slug_frontmatter() {
         let content = r#"
@@ -432,6 +714,104 @@ Hello world"#;
         assert_eq!(page.permalink, config.make_permalink("日本"));
     }
 
+    #[test]
+    fn can_make_unicode_url_from_filename() {
+        let mut config = Config::default();
+        config.slugify.paths = SlugifyStrategy::Unicode;
+        let tests = vec![
+            ("content/posts/記事タイトル.md", "/posts/記事タイトル/"),
+            ("content/posts/Привет Мир.md", "/posts/привет-мир/"),
+        ];
+        for (file_path, expected) in tests {
+            let res =
+                Page::parse(Path::new(file_path), "+++\n+++\nHello", &config, &PathBuf::new());
+            let page = res.unwrap();
+            assert_eq!(page.path, expected);
+            assert_eq!(page.permalink, config.make_permalink(expected));
+        }
+    }
+
+    #[test]
+    fn can_make_url_from_permalink_pattern() {
+        let mut config = Config::default();
+        config.permalinks.insert("posts".to_string(), "{year}/{month}/{slug}".to_string());
+        config
+            .permalinks
+            .insert("news".to_string(), "/news/{year}/{month}/{day}/{slug}/".to_string());
+
+        let content = "+++\ndate = 2023-06-04\n+++\nHello";
+        let page =
+            Page::parse(Path::new("content/posts/my-post.md"), content, &config, &PathBuf::new())
+                .unwrap();
+        assert_eq!(page.path, "/2023/06/my-post/");
+        assert_eq!(page.components, vec!["2023", "06", "my-post"]);
+        assert_eq!(page.permalink, config.make_permalink("/2023/06/my-post/"));
+
+        // The date can come from the filename
+        let page = Page::parse(
+            Path::new("content/news/2021-12-25_christmas.md"),
+            "+++\n+++\nHello",
+            &config,
+            &PathBuf::new(),
+        )
+        .unwrap();
+        assert_eq!(page.path, "/news/2021/12/25/christmas/");
+
+        // Other sections are not affected
+        let page =
+            Page::parse(Path::new("content/docs/my-post.md"), content, &config, &PathBuf::new())
+                .unwrap();
+        assert_eq!(page.path, "/docs/my-post/");
+    }
+
+    #[test]
+    fn can_make_url_from_permalink_pattern_in_other_language() {
+        let mut config = Config::default_for_test();
+        config.languages.insert("fr".to_owned(), LanguageOptions::default());
+        config.permalinks.insert("posts".to_string(), "{year}/{slug}".to_string());
+        let page = Page::parse(
+            Path::new("content/posts/my-post.fr.md"),
+            "+++\ndate = 2023-06-04\n+++\nHello",
+            &config,
+            &PathBuf::new(),
+        )
+        .unwrap();
+        assert_eq!(page.path, "/fr/2023/my-post/");
+    }
+
+    #[test]
+    fn errors_on_permalink_pattern_without_date() {
+        let mut config = Config::default();
+        config.permalinks.insert("posts".to_string(), "{year}/{month}/{slug}".to_string());
+        let res = Page::parse(
+            Path::new("content/posts/my-post.md"),
+            "+++\n+++\nHello",
+            &config,
+            &PathBuf::new(),
+        );
+        assert!(res.unwrap_err().to_string().contains("has no date"));
+
+        // No date needed if the pattern doesn't use it
+        config.permalinks.insert("posts".to_string(), "articles/{slug}".to_string());
+        let page = Page::parse(
+            Path::new("content/posts/my-post.md"),
+            "+++\n+++\nHello",
+            &config,
+            &PathBuf::new(),
+        )
+        .unwrap();
+        assert_eq!(page.path, "/articles/my-post/");
+
+        config.permalinks.insert("posts".to_string(), "{hour}/{slug}".to_string());
+        let res = Page::parse(
+            Path::new("content/posts/my-post.md"),
+            "+++\n+++\nHello",
+            &config,
+            &PathBuf::new(),
+        );
+        assert!(res.unwrap_err().to_string().contains("Unknown token"));
+    }
+
     #[test]
     fn can_make_url_from_path() {
         let content = r#"
@@ -532,6 +912,53 @@ Hello world
         assert_eq!(page.summary, Some("<p>Hello world</p>\n".to_string()));
     }
 
+    #[test]
+    fn can_generate_summary_from_word_count() {
+        let mut config = Config::default_for_test();
+        config.summary_word_count = Some(3);
+        let render = |content: &str| {
+            let mut page =
+                Page::parse(Path::new("hello.md"), content, &config, &PathBuf::new()).unwrap();
+            page.render_markdown(
+                &HashMap::default(),
+                &Tera::default(),
+                &config,
+                InsertAnchor::None,
+                &HashMap::new(),
+            )
+            .unwrap();
+            page.summary
+        };
+
+        assert_eq!(
+            render("+++\n+++\nHello *big wide* world\n\nBye"),
+            Some("<p>Hello <em>big wide</em></p>".to_string())
+        );
+        // The marker takes precedence
+        assert_eq!(
+            render("+++\n+++\nHello\n<!-- more -->\nbig wide world"),
+            Some("<p>Hello</p>\n".to_string())
+        );
+        // Nothing to summarize
+        assert_eq!(render("+++\n+++\nHello world"), None);
+    }
+
+    #[test]
+    fn can_include_raw_content_in_serialized_page() {
+        let mut config = Config::default_for_test();
+        let library = crate::Library::default();
+        let content = "+++\ntitle = \"Hello\"\n+++\nHello *world*\n";
+        let raw_content = |config: &Config| {
+            let page =
+                Page::parse(Path::new("hello.md"), content, config, &PathBuf::new()).unwrap();
+            libs::serde_json::to_value(page.serialize(&library)).unwrap()["raw_content"].clone()
+        };
+
+        assert_eq!(raw_content(&config), "");
+        config.include_raw_content = true;
+        assert_eq!(raw_content(&config), "Hello *world*\n");
+    }
+
     #[test]
     fn strips_footnotes_in_summary() {
         let config = Config::default_for_test();
@@ -569,6 +996,41 @@ And here's another. [^3]
         );
     }
 
+    #[test]
+    fn can_parse_page_without_front_matter_with_heading() {
+        let mut config = Config::default_for_test();
+        config.allow_missing_frontmatter = true;
+        let content = "# Hello world\n\nSome content";
+        let page = Page::parse(Path::new("post.md"), content, &config, &PathBuf::new()).unwrap();
+        assert_eq!(page.meta.title.unwrap(), "Hello world");
+        assert_eq!(page.raw_content, content);
+        assert_eq!(page.slug, "post");
+    }
+
+    #[test]
+    fn can_parse_page_without_front_matter_without_heading() {
+        let tmp_dir = tempdir().expect("create temp dir");
+        let path = tmp_dir.path();
+        create_dir(path.join("content")).expect("create content temp dir");
+        let file_path = path.join("content").join("my-notes.md");
+        let mut f = File::create(&file_path).unwrap();
+        f.write_all(b"Just some text").unwrap();
+
+        let mut config = Config::default_for_test();
+        config.allow_missing_frontmatter = true;
+        let page = Page::from_file(&file_path, &config, path).unwrap();
+        assert_eq!(page.meta.title.unwrap(), "my-notes");
+        // The date comes from the file modification time
+        assert!(page.meta.datetime.is_some());
+    }
+
+    #[test]
+    fn errors_on_page_without_front_matter_by_default() {
+        let config = Config::default_for_test();
+        let res = Page::parse(Path::new("post.md"), "# Hello", &config, &PathBuf::new());
+        assert!(res.is_err());
+    }
+
     #[test]
     fn page_with_assets_gets_right_info() {
         let tmp_dir = tempdir().expect("create temp dir");
@@ -593,6 +1055,32 @@ And here's another. [^3]
         assert_eq!(page.permalink, "http://a-website.com/posts/with-assets/");
     }
 
+    #[test]
+    fn page_assets_are_grouped_by_extension() {
+        let tmp_dir = tempdir().expect("create temp dir");
+        let path = tmp_dir.path();
+        let nested_path = path.join("content").join("gallery");
+        create_dir_all(&nested_path).expect("create nested temp dir");
+        let mut f = File::create(nested_path.join("index.md")).unwrap();
+        f.write_all(b"+++\n+++\n").unwrap();
+        for asset in ["a.png", "b.PNG", "c.jpg", "manual.pdf"] {
+            File::create(nested_path.join(asset)).unwrap();
+        }
+
+        let page = Page::from_file(nested_path.join("index.md"), &Config::default(), path).unwrap();
+        let library = crate::Library::default();
+        let serialized = libs::serde_json::to_value(page.serialize(&library)).unwrap();
+        assert_eq!(serialized["assets"].as_array().unwrap().len(), 4);
+        assert_eq!(
+            serialized["assets_by_extension"],
+            libs::serde_json::json!({
+                "jpg": ["/gallery/c.jpg"],
+                "pdf": ["/gallery/manual.pdf"],
+                "png": ["/gallery/a.png", "/gallery/b.PNG"],
+            })
+        );
+    }
+
     #[test]
     fn page_with_assets_and_slug_overrides_path() {
         let tmp_dir = tempdir().expect("create temp dir");
@@ -863,6 +1351,41 @@ Hello world
         assert_eq!(page.slug, "hello");
     }
 
+    #[test]
+    fn can_serialize_date_components_with_localized_month_name() {
+        let mut config = Config::default_for_test();
+        config.languages.insert("fr".to_owned(), LanguageOptions::default());
+        config.languages.insert("pt-BR".to_owned(), LanguageOptions::default());
+        config.languages.insert("tlh".to_owned(), LanguageOptions::default());
+        let library = crate::Library::default();
+
+        for (path, month_name) in [
+            ("hello.md", "June"),
+            ("hello.fr.md", "juin"),
+            ("hello.pt-BR.md", "junho"),
+            // No locale data for Klingon
+            ("hello.tlh.md", "June"),
+        ] {
+            let page = Page::parse(
+                Path::new(path),
+                "+++\ndate = 2023-06-01\n+++\n",
+                &config,
+                &PathBuf::new(),
+            )
+            .unwrap();
+            let serialized = libs::serde_json::to_value(page.serialize(&library)).unwrap();
+            assert_eq!(serialized["year"], 2023);
+            assert_eq!(serialized["month"], 6);
+            assert_eq!(serialized["day"], 1);
+            assert_eq!(serialized["month_name"], month_name);
+        }
+
+        let page =
+            Page::parse(Path::new("undated.md"), "+++\n+++\n", &config, &PathBuf::new()).unwrap();
+        let serialized = libs::serde_json::to_value(page.serialize(&library)).unwrap();
+        assert!(serialized["month_name"].is_null());
+    }
+
     #[test]
     fn can_specify_language_in_filename() {
         let mut config = Config::default();
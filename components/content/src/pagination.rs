@@ -242,6 +242,7 @@ impl<'a> Paginator<'a> {
                 context.insert("config", &config.serialize(&t.lang));
             }
         };
+        config.build_info.insert_into(&mut context);
         context.insert("current_url", &pager.permalink);
         context.insert("current_path", &pager.path);
         context.insert("paginator", &self.build_paginator_context(pager));
@@ -398,6 +399,7 @@ mod tests {
             path: "/some-tags/something/".to_string(),
             permalink: "https://vincent.is/some-tags/something/".to_string(),
             pages: library.pages.keys().cloned().collect(),
+            ancestors: Vec::new(),
         };
         let taxonomy = Taxonomy {
             kind: taxonomy_def,
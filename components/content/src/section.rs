@@ -8,7 +8,7 @@ use errors::{Context, Result};
 use markdown::{render_content, RenderContext};
 use utils::fs::read_file;
 use utils::net::is_external_link;
-use utils::table_of_contents::Heading;
+use utils::table_of_contents::{FlatHeading, Heading};
 use utils::templates::{render_template, ShortcodeDefinition};
 
 use crate::file_info::FileInfo;
@@ -48,6 +48,8 @@ pub struct Section {
     pub subsections: Vec<PathBuf>,
     /// Toc made from the headings of the markdown file
     pub toc: Vec<Heading>,
+    /// The headings of the markdown file in order, without nesting
+    pub toc_flat: Vec<FlatHeading>,
     /// How many words in the raw content
     pub word_count: Option<usize>,
     /// How long would it take to read the raw content.
@@ -62,6 +64,8 @@ pub struct Section {
     pub internal_links: Vec<(String, Option<String>)>,
     /// The list of all links to external webpages. They can be validated by the `link_checker`.
     pub external_links: Vec<String>,
+    /// The filenames of the feeds of the section, from its front matter or the config
+    pub feed_filenames: Vec<String>,
 }
 
 impl Section {
@@ -110,6 +114,15 @@ impl Section {
             .filter(|p| !p.is_empty())
             .collect::<Vec<_>>();
         section.permalink = config.make_permalink(&section.path);
+        section.feed_filenames = if section.meta.feed_filenames.is_empty() {
+            config
+                .languages
+                .get(&section.lang)
+                .map_or(&config.feed_filenames, |l| &l.feed_filenames)
+                .clone()
+        } else {
+            section.meta.feed_filenames.clone()
+        };
         Ok(section)
     }
 
@@ -130,6 +143,23 @@ impl Section {
         Ok(section)
     }
 
+    /// Parses the file of this section again as its translation in `lang`, for the
+    /// `shared_content` of the config. The copy has the path of the `_index.{lang}.md` file it
+    /// replaces
+    pub fn translated_copy(
+        &self,
+        lang: &str,
+        config: &Config,
+        base_path: &Path,
+    ) -> Result<Section> {
+        let content = read_file(&self.file.path)?;
+        let path = self.file.translation_path(lang);
+        let mut section = Section::parse(&path, &content, config, base_path)?;
+        section.assets = self.assets.clone();
+        section.serialized_assets = section.serialize_assets();
+        Ok(section)
+    }
+
     pub fn get_template_name(&self) -> &str {
         match self.meta.template {
             Some(ref l) => l,
@@ -151,6 +181,7 @@ impl Section {
         config: &Config,
         shortcode_definitions: &HashMap<String, ShortcodeDefinition>,
     ) -> Result<()> {
+        let content_path = self.file.content_path();
         let mut context = RenderContext::new(
             tera,
             config,
@@ -161,6 +192,7 @@ impl Section {
         );
         context.set_shortcode_definitions(shortcode_definitions);
         context.set_current_page_path(&self.file.relative);
+        context.set_content_path(&content_path);
         context
             .tera_context
             .insert("section", &SerializingSection::new(self, SectionSerMode::ForMarkdown));
@@ -169,6 +201,7 @@ impl Section {
             .with_context(|| format!("Failed to render content of {}", self.file.path.display()))?;
         self.content = res.body;
         self.toc = res.toc;
+        self.toc_flat = res.toc_flat;
 
         self.external_links = res.external_links;
         if let Some(ref redirect_to) = self.meta.redirect_to {
@@ -188,6 +221,7 @@ impl Section {
 
         let mut context = TeraContext::new();
         context.insert("config", &config.serialize(&self.lang));
+        config.build_info.insert_into(&mut context);
         context.insert("current_url", &self.permalink);
         context.insert("current_path", &self.path);
         context.insert("section", &SerializingSection::new(self, SectionSerMode::Full(library)));
@@ -230,8 +264,9 @@ impl Section {
         SerializingSection::new(self, SectionSerMode::Full(library))
     }
 
+    /// Only the metadata of the section: no content, toc or pages
     pub fn serialize_basic<'a>(&'a self, library: &'a Library) -> SerializingSection<'a> {
-        SerializingSection::new(self, SectionSerMode::MetadataOnly(library))
+        SerializingSection::new(self, SectionSerMode::MetadataOnly(library)).without_content()
     }
 }
 
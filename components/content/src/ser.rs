@@ -1,12 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
 use serde::Serialize;
 
 use crate::library::Library;
-use crate::{Page, Section};
+use crate::{Author, Page, Section};
+use libs::pure_rust_locales::{locale_match, Locale};
 use libs::tera::{Map, Value};
-use utils::table_of_contents::Heading;
+use utils::locales::find_locale;
+use utils::table_of_contents::{FlatHeading, Heading};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct BackLink<'a> {
@@ -23,6 +25,35 @@ pub struct TranslatedContent<'a> {
     pub path: &'a Path,
 }
 
+/// A lightweight reference to a page of a section, for the templates that only need to link
+/// to the pages without their content, like a navigation menu
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PageRef<'a> {
+    pub relative_path: &'a str,
+    pub permalink: &'a str,
+    pub path: &'a str,
+    pub title: &'a Option<String>,
+    pub date: &'a Option<String>,
+}
+
+impl<'a> PageRef<'a> {
+    pub fn new(page: &'a Page) -> Self {
+        Self {
+            relative_path: &page.file.relative,
+            permalink: &page.permalink,
+            path: &page.path,
+            title: &page.meta.title,
+            date: &page.meta.date,
+        }
+    }
+}
+
+/// The name of the month in that language, or in English if we don't have the locale data for it
+fn month_name(lang: &str, month: u8) -> &'static str {
+    let locale = find_locale(lang).unwrap_or(Locale::POSIX);
+    locale_match!(locale => LC_TIME::MON)[usize::from(month) - 1]
+}
+
 fn find_backlinks<'a>(relative_path: &str, library: &'a Library) -> Vec<BackLink<'a>> {
     let mut backlinks = Vec::new();
     if let Some(b) = library.backlinks.get(relative_path) {
@@ -44,6 +75,7 @@ pub struct SerializingPage<'a> {
     relative_path: &'a str,
     colocated_path: &'a Option<String>,
     content: &'a str,
+    raw_content: &'a str,
     permalink: &'a str,
     slug: &'a str,
     ancestors: &'a [String],
@@ -53,17 +85,22 @@ pub struct SerializingPage<'a> {
     date: &'a Option<String>,
     year: Option<i32>,
     month: Option<u8>,
+    month_name: Option<&'static str>,
     day: Option<u8>,
     taxonomies: &'a HashMap<String, Vec<String>>,
     authors: &'a [String],
+    author_details: &'a [Author],
     extra: &'a Map<String, Value>,
     path: &'a str,
     components: &'a [String],
     summary: &'a Option<String>,
     toc: &'a [Heading],
+    toc_flat: &'a [FlatHeading],
     word_count: Option<usize>,
     reading_time: Option<usize>,
     assets: &'a [String],
+    assets_by_extension: BTreeMap<String, Vec<&'a str>>,
+    og_image: &'a Option<String>,
     draft: bool,
     lang: &'a str,
     lower: Option<Box<SerializingPage<'a>>>,
@@ -109,6 +146,7 @@ impl<'a> SerializingPage<'a> {
             colocated_path: &page.file.colocated_path,
             ancestors: &page.ancestors,
             content: &page.content,
+            raw_content: if page.include_raw_content { &page.raw_content } else { "" },
             permalink: &page.permalink,
             slug: &page.slug,
             title: &page.meta.title,
@@ -118,16 +156,21 @@ impl<'a> SerializingPage<'a> {
             date: &page.meta.date,
             year,
             month,
+            month_name: month.map(|m| month_name(&page.lang, m)),
             day,
             taxonomies: &page.meta.taxonomies,
             authors: &page.meta.authors,
+            author_details: &page.meta.author_details,
             path: &page.path,
             components: &page.components,
             summary: &page.summary,
             toc: &page.toc,
+            toc_flat: &page.toc_flat,
             word_count: page.word_count,
             reading_time: page.reading_time,
             assets: &page.serialized_assets,
+            assets_by_extension: group_assets_by_extension(&page.serialized_assets),
+            og_image: &page.og_image,
             draft: page.meta.draft,
             lang: &page.lang,
             lower,
@@ -136,6 +179,27 @@ impl<'a> SerializingPage<'a> {
             backlinks,
         }
     }
+
+    /// Removes the rendered content, for when only the metadata is needed
+    pub(crate) fn without_content(mut self) -> Self {
+        self.content = "";
+        self.raw_content = "";
+        self.summary = &None;
+        self.toc = &[];
+        self.toc_flat = &[];
+        self
+    }
+}
+
+/// Groups the assets by their lowercased extension
+fn group_assets_by_extension(assets: &[String]) -> BTreeMap<String, Vec<&str>> {
+    let mut grouped: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for asset in assets {
+        if let Some(ext) = Path::new(asset).extension().and_then(|e| e.to_str()) {
+            grouped.entry(ext.to_lowercase()).or_default().push(asset);
+        }
+    }
+    grouped
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
@@ -152,15 +216,18 @@ pub struct SerializingSection<'a> {
     path: &'a str,
     components: &'a [String],
     toc: &'a [Heading],
+    toc_flat: &'a [FlatHeading],
     word_count: Option<usize>,
     reading_time: Option<usize>,
     lang: &'a str,
     assets: &'a [String],
     pages: Vec<SerializingPage<'a>>,
+    page_refs: Vec<PageRef<'a>>,
     subsections: Vec<&'a str>,
     translations: Vec<TranslatedContent<'a>>,
     backlinks: Vec<BackLink<'a>>,
     generate_feeds: bool,
+    feed_filenames: &'a [String],
     transparent: bool,
 }
 
@@ -169,7 +236,7 @@ pub enum SectionSerMode<'a> {
     /// Just itself, no pages or subsections
     /// TODO: I believe we can get rid of it?
     ForMarkdown,
-    /// Fetches subsections/ancestors/translations but not the pages
+    /// Fetches subsections/ancestors/translations and the `page_refs` but not the pages
     MetadataOnly(&'a Library),
     /// Fetches everything
     Full(&'a Library),
@@ -178,6 +245,7 @@ pub enum SectionSerMode<'a> {
 impl<'a> SerializingSection<'a> {
     pub fn new(section: &'a Section, mode: SectionSerMode<'a>) -> Self {
         let mut pages = Vec::with_capacity(section.pages.len());
+        let mut page_refs = Vec::with_capacity(section.pages.len());
         let mut subsections = Vec::with_capacity(section.subsections.len());
         let mut translations = Vec::new();
         let mut backlinks = Vec::new();
@@ -191,6 +259,7 @@ impl<'a> SerializingSection<'a> {
                     .iter()
                     .map(|p| lib.sections[p].file.relative.as_str())
                     .collect();
+                page_refs = section.pages.iter().map(|p| PageRef::new(&lib.pages[p])).collect();
 
                 // Fetching pages on top
                 if let SectionSerMode::Full(_) = mode {
@@ -216,16 +285,27 @@ impl<'a> SerializingSection<'a> {
             path: &section.path,
             components: &section.components,
             toc: &section.toc,
+            toc_flat: &section.toc_flat,
             word_count: section.word_count,
             reading_time: section.reading_time,
             assets: &section.serialized_assets,
             lang: &section.lang,
             generate_feeds: section.meta.generate_feeds,
+            feed_filenames: &section.feed_filenames,
             transparent: section.meta.transparent,
             pages,
+            page_refs,
             subsections,
             translations,
             backlinks,
         }
     }
+
+    /// Removes the rendered content, for when only the metadata is needed
+    pub(crate) fn without_content(mut self) -> Self {
+        self.content = "";
+        self.toc = &[];
+        self.toc_flat = &[];
+        self
+    }
 }
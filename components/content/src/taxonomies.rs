@@ -1,9 +1,9 @@
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::path::PathBuf;
 
 use serde::Serialize;
 
-use config::{Config, TaxonomyConfig};
+use config::{Config, TaxonomyConfig, TaxonomyPagesSortBy, TaxonomySortBy};
 use errors::{Context as ErrorContext, Result};
 use libs::ahash::AHashMap;
 use libs::tera::{Context, Tera};
@@ -24,6 +24,7 @@ pub struct SerializedTaxonomyTerm<'a> {
     permalink: &'a str,
     pages: Vec<SerializingPage<'a>>,
     page_count: usize,
+    ancestors: &'a [String],
 }
 
 impl<'a> SerializedTaxonomyTerm<'a> {
@@ -43,10 +44,23 @@ impl<'a> SerializedTaxonomyTerm<'a> {
             permalink: &item.permalink,
             pages,
             page_count: item.pages.len(),
+            ancestors: &item.ancestors,
         }
     }
 }
 
+/// Slugifies the term, unless it has a custom slug in the config of its taxonomy
+fn term_slug(name: &str, lang: &str, taxo_slug: &str, config: &Config) -> String {
+    match config
+        .languages
+        .get(lang)
+        .and_then(|options| options.taxonomies.iter().find(|t| t.slug == taxo_slug))
+    {
+        Some(taxo_config) => taxo_config.term_slug(name, config.slugify.taxonomies),
+        None => slugify_paths(name, config.slugify.taxonomies),
+    }
+}
+
 /// A taxonomy with all its pages
 #[derive(Debug, Clone)]
 pub struct TaxonomyTerm {
@@ -55,6 +69,8 @@ pub struct TaxonomyTerm {
     pub path: String,
     pub permalink: String,
     pub pages: Vec<PathBuf>,
+    /// The names of the parent terms, from the top one, for hierarchical taxonomies
+    pub ancestors: Vec<String>,
 }
 
 impl TaxonomyTerm {
@@ -63,9 +79,39 @@ impl TaxonomyTerm {
         lang: &str,
         taxo_slug: &str,
         taxo_pages: &[&Page],
+        sort_by: SortBy,
+        config: &Config,
+    ) -> Self {
+        let item_slug = term_slug(name, lang, taxo_slug, config);
+        Self::new_with_slug(name, item_slug, lang, taxo_slug, taxo_pages, sort_by, config)
+    }
+
+    /// A term of a hierarchical taxonomy: `a/b` is a child of `a` and ends up at
+    /// `/{taxo_slug}/a/b/`, see `TaxonomyConfig::term_slug`
+    pub fn new_hierarchical(
+        name: &str,
+        lang: &str,
+        taxo_slug: &str,
+        taxo_pages: &[&Page],
+        sort_by: SortBy,
+        config: &Config,
+    ) -> Self {
+        let item_slug = term_slug(name, lang, taxo_slug, config);
+        let mut term =
+            Self::new_with_slug(name, item_slug, lang, taxo_slug, taxo_pages, sort_by, config);
+        term.ancestors = name.match_indices('/').map(|(i, _)| name[..i].to_string()).collect();
+        term
+    }
+
+    fn new_with_slug(
+        name: &str,
+        item_slug: String,
+        lang: &str,
+        taxo_slug: &str,
+        taxo_pages: &[&Page],
+        sort_by: SortBy,
         config: &Config,
     ) -> Self {
-        let item_slug = slugify_paths(name, config.slugify.taxonomies);
         let path = if lang != config.default_language {
             format!("/{}/{}/{}/", lang, taxo_slug, item_slug)
         } else {
@@ -73,13 +119,20 @@ impl TaxonomyTerm {
         };
         let permalink = config.make_permalink(&path);
 
-        // Taxonomy are almost always used for blogs so we filter by dates
+        // Taxonomy are almost always used for blogs so we sort by dates by default
         // and it's not like we can sort things across sections by anything other
-        // than dates
-        let (mut pages, ignored_pages) = sort_pages(taxo_pages, SortBy::Date);
-        // We still append pages without dates at the end
+        // than dates or weights
+        let (mut pages, ignored_pages) = sort_pages(taxo_pages, sort_by);
+        // We still append pages without dates/weights at the end
         pages.extend(ignored_pages);
-        TaxonomyTerm { name: name.to_string(), permalink, path, slug: item_slug, pages }
+        TaxonomyTerm {
+            name: name.to_string(),
+            permalink,
+            path,
+            slug: item_slug,
+            pages,
+            ancestors: Vec::new(),
+        }
     }
 
     pub fn serialize<'a>(&'a self, library: &'a Library) -> SerializedTaxonomyTerm<'a> {
@@ -137,7 +190,7 @@ pub struct Taxonomy {
     pub slug: String,
     pub path: String,
     pub permalink: String,
-    // this vec is sorted by the count of item
+    // this vec is sorted according to the `sort_by` of the taxonomy config
     pub items: Vec<TaxonomyTerm>,
 }
 
@@ -145,8 +198,16 @@ impl Taxonomy {
     pub(crate) fn new(tax_found: TaxonomyFound, config: &Config) -> Self {
         let mut sorted_items = vec![];
         let slug = tax_found.slug;
+        let sort_by = match tax_found.config.sort_pages_by {
+            TaxonomyPagesSortBy::Date => SortBy::Date,
+            TaxonomyPagesSortBy::Weight => SortBy::Weight,
+        };
         for (name, pages) in tax_found.terms {
-            sorted_items.push(TaxonomyTerm::new(name, tax_found.lang, &slug, &pages, config));
+            sorted_items.push(if tax_found.config.hierarchical {
+                TaxonomyTerm::new_hierarchical(name, tax_found.lang, &slug, &pages, sort_by, config)
+            } else {
+                TaxonomyTerm::new(name, tax_found.lang, &slug, &pages, sort_by, config)
+            });
         }
 
         sorted_items.sort_by(|a, b| match a.slug.cmp(&b.slug) {
@@ -165,6 +226,10 @@ impl Taxonomy {
                 false
             }
         });
+        if tax_found.config.sort_by == TaxonomySortBy::Count {
+            // Stable sort so terms with the same number of pages stay sorted by name
+            sorted_items.sort_by_key(|item| Reverse(item.pages.len()));
+        }
         let path = if tax_found.lang != config.default_language {
             format!("/{}/{}/", tax_found.lang, slug)
         } else {
@@ -208,6 +273,7 @@ impl Taxonomy {
     ) -> Context {
         let mut context = Context::new();
         context.insert("config", &config.serialize(&self.lang));
+        config.build_info.insert_into(&mut context);
         context.insert("lang", &self.lang);
         context.insert("term", &SerializedTaxonomyTerm::from_item(item, library, true));
         context.insert("taxonomy", &self.kind);
@@ -224,6 +290,7 @@ impl Taxonomy {
     ) -> Result<String> {
         let mut context = Context::new();
         context.insert("config", &config.serialize(&self.lang));
+        config.build_info.insert_into(&mut context);
         let terms: Vec<SerializedTaxonomyTerm> = self
             .items
             .iter()
@@ -276,7 +343,7 @@ impl<'a> TaxonomyFound<'a> {
 mod tests {
     use config::{Config, TaxonomyConfig};
 
-    use crate::{Library, Taxonomy, TaxonomyTerm};
+    use crate::{Library, SortBy, Taxonomy, TaxonomyTerm};
 
     use super::TaxonomyFound;
 
@@ -287,7 +354,8 @@ mod tests {
         let tax_found = TaxonomyFound::new("tag".into(), &conf.default_language, &tax_conf);
         let tax = Taxonomy::new(tax_found, &conf);
         let pages = &[];
-        let term = TaxonomyTerm::new("rust", &conf.default_language, "tags", pages, &conf);
+        let term =
+            TaxonomyTerm::new("rust", &conf.default_language, "tags", pages, SortBy::Date, &conf);
         let lib = Library::default();
 
         let ctx = tax.build_term_context(&term, &conf, &lib);
@@ -2,9 +2,12 @@ use std::path::{Path, PathBuf};
 
 use libs::unicode_segmentation::UnicodeSegmentation;
 use libs::walkdir::WalkDir;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
 use config::Config;
 use utils::fs::is_temp_file;
+use utils::source_date;
 use utils::table_of_contents::Heading;
 
 pub fn has_anchor(headings: &[Heading], anchor: &str) -> bool {
@@ -50,6 +53,7 @@ pub fn find_related_assets(path: &Path, config: &Config, recursive: bool) -> Vec
     if let Some(ref globset) = config.ignored_content_globset {
         assets.retain(|p| !globset.is_match(p));
     }
+    assets.retain(|p| !config.is_gitignored(p));
 
     assets.sort_by(|a, b| {
         a.to_str().unwrap().to_ascii_lowercase().cmp(&b.to_str().unwrap().to_ascii_lowercase())
@@ -58,6 +62,16 @@ pub fn find_related_assets(path: &Path, config: &Config, recursive: bool) -> Vec
     assets
 }
 
+/// Get the last modification time of a file as a RFC3339 datetime, if available,
+/// clamped to the `SOURCE_DATE_EPOCH` of the build
+pub fn get_file_modified_date(
+    path: &Path,
+    source_date_epoch: Option<OffsetDateTime>,
+) -> Option<String> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    source_date::clamp(OffsetDateTime::from(modified), source_date_epoch).format(&Rfc3339).ok()
+}
+
 /// Get word count and estimated reading time
 pub fn get_reading_analytics(content: &str) -> (usize, usize) {
     // code fences "toggle" the state from non-code to code and back, so anything inbetween the
@@ -70,6 +84,76 @@ pub fn get_reading_analytics(content: &str) -> (usize, usize) {
     (word_count, ((word_count + 199) / 200))
 }
 
+/// Elements without a closing tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Cuts the HTML after `word_count` words of text, closing the elements still open at that point.
+/// Returns `None` if the HTML has no more than `word_count` words, since there is nothing to cut
+pub fn truncate_html_words(html: &str, word_count: usize) -> Option<String> {
+    let mut truncated = String::with_capacity(html.len());
+    let mut open_elements: Vec<&str> = Vec::new();
+    let mut words = 0;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(rest.len(), |i| i + 3);
+            truncated.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        if rest.starts_with('<') {
+            let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+            let tag = &rest[..end];
+            let name = tag
+                .trim_start_matches('<')
+                .trim_start_matches('/')
+                .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .next()
+                .unwrap_or_default();
+            if tag.starts_with("</") {
+                if let Some(pos) = open_elements.iter().rposition(|e| e.eq_ignore_ascii_case(name))
+                {
+                    open_elements.truncate(pos);
+                }
+            } else if !tag.ends_with("/>")
+                && !VOID_ELEMENTS.contains(&name.to_ascii_lowercase().as_str())
+            {
+                open_elements.push(name);
+            }
+            truncated.push_str(tag);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let end = rest.find('<').unwrap_or(rest.len());
+        let text = &rest[..end];
+        let mut in_word = false;
+        for (i, c) in text.char_indices() {
+            if c.is_whitespace() {
+                in_word = false;
+            } else if !in_word {
+                in_word = true;
+                words += 1;
+                if words > word_count {
+                    truncated.push_str(text[..i].trim_end());
+                    for element in open_elements.iter().rev() {
+                        truncated.push_str(&format!("</{}>", element));
+                    }
+                    return Some(truncated);
+                }
+            }
+        }
+        truncated.push_str(text);
+        rest = &rest[end..];
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -258,4 +342,19 @@ mod tests {
         assert_eq!(word_count, 4);
         assert_eq!(reading_time, 1);
     }
+
+    #[test]
+    fn can_truncate_html_words() {
+        let html =
+            "<p>Hello <em>big <strong>wide</strong> world</em> of<br> HTML</p>\n<p>Bye</p>\n";
+        assert_eq!(
+            truncate_html_words(html, 3),
+            Some("<p>Hello <em>big <strong>wide</strong></em></p>".to_string())
+        );
+        assert_eq!(
+            truncate_html_words(html, 5),
+            Some("<p>Hello <em>big <strong>wide</strong> world</em> of<br></p>".to_string())
+        );
+        assert_eq!(truncate_html_words(html, 7), None);
+    }
 }
@@ -0,0 +1,59 @@
+use std::cmp::Reverse;
+use std::path::Path;
+
+use errors::{Context, Result};
+use libs::image::{self, GenericImageView};
+
+/// Images are downscaled to fit in that size before looking at their pixels
+const SAMPLE_SIZE: u32 = 128;
+/// Each channel is quantized to 4 bits to group similar colors together
+const QUANTIZATION_SHIFT: u8 = 4;
+
+/// Returns the `count` most common colors of an image as `#rrggbb`, most common first.
+/// Fully transparent pixels are ignored.
+pub fn get_image_colors(path: &Path, count: usize) -> Result<Vec<String>> {
+    let img =
+        image::open(path).with_context(|| format!("Failed to read image: {}", path.display()))?;
+    let (width, height) = img.dimensions();
+    // Downscaling first is much faster and doesn't change the dominant colors much
+    let img = if width > SAMPLE_SIZE || height > SAMPLE_SIZE {
+        img.thumbnail(SAMPLE_SIZE, SAMPLE_SIZE)
+    } else {
+        img
+    };
+
+    // (quantized color, sum of each channel, number of pixels)
+    let mut buckets: Vec<([u8; 3], [u64; 3], u64)> = Vec::new();
+    for pixel in img.to_rgba8().pixels() {
+        if pixel[3] == 0 {
+            continue;
+        }
+        let key = [
+            pixel[0] >> QUANTIZATION_SHIFT,
+            pixel[1] >> QUANTIZATION_SHIFT,
+            pixel[2] >> QUANTIZATION_SHIFT,
+        ];
+        let index = match buckets.binary_search_by(|(k, _, _)| k.cmp(&key)) {
+            Ok(i) => i,
+            Err(i) => {
+                buckets.insert(i, (key, [0; 3], 0));
+                i
+            }
+        };
+        let (_, sums, pixels) = &mut buckets[index];
+        for c in 0..3 {
+            sums[c] += pixel[c] as u64;
+        }
+        *pixels += 1;
+    }
+
+    // The sort is stable and buckets are sorted by color so ties are deterministic
+    buckets.sort_by_key(|(_, _, pixels)| Reverse(*pixels));
+    Ok(buckets
+        .into_iter()
+        .take(count)
+        .map(|(_, sums, pixels)| {
+            format!("#{:02x}{:02x}{:02x}", sums[0] / pixels, sums[1] / pixels, sums[2] / pixels)
+        })
+        .collect())
+}
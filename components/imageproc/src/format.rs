@@ -2,6 +2,7 @@ use errors::{anyhow, Result};
 use std::hash::{Hash, Hasher};
 
 const DEFAULT_Q_JPG: u8 = 75;
+const DEFAULT_Q_AVIF: u8 = 80;
 
 /// Thumbnail image format
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -12,6 +13,8 @@ pub enum Format {
     Png,
     /// WebP, The `u8` argument is WebP quality (in percent), None meaning lossless.
     WebP(Option<u8>),
+    /// AVIF, The `u8` argument is AVIF quality (in percent).
+    Avif(u8),
 }
 
 impl Format {
@@ -32,6 +35,7 @@ impl Format {
             "jpeg" | "jpg" => Ok(Jpeg(jpg_quality)),
             "png" => Ok(Png),
             "webp" => Ok(WebP(quality)),
+            "avif" => Ok(Avif(quality.unwrap_or(DEFAULT_Q_AVIF))),
             _ => Err(anyhow!("Invalid image format: {}", format)),
         }
     }
@@ -44,6 +48,7 @@ impl Format {
             Png => "png",
             Jpeg(_) => "jpg",
             WebP(_) => "webp",
+            Avif(_) => "avif",
         }
     }
 }
@@ -58,6 +63,7 @@ impl Hash for Format {
             Jpeg(q) => 1001 + q as u16,
             WebP(None) => 2000,
             WebP(Some(q)) => 2001 + q as u16,
+            Avif(q) => 3001 + q as u16,
         };
 
         hasher.write_u16(q);
@@ -1,11 +1,57 @@
-use std::borrow::Cow;
-use std::collections::hash_map::DefaultHasher;
+use std::fs;
 use std::hash::{Hash, Hasher};
 use std::path::Path;
 
 use crate::format::Format;
-use crate::ResizeOperation;
+use crate::{PostProcessing, ResizeInstructions, ResizeOperation};
+use errors::{Context, Result};
 use libs::image::DynamicImage;
+use libs::sha2::{Digest, Sha256};
+
+/// A `Hasher` backed by SHA-256 that writes integers as little-endian bytes.
+/// Unlike `DefaultHasher`, the output is the same on every machine and Rust version
+/// so the generated filenames don't change between deploys.
+#[derive(Default)]
+pub(crate) struct StableHasher(Sha256);
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+        u64::from_le_bytes(digest[..8].try_into().unwrap())
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    // usize is hashed as a u64 so 32 and 64 bit platforms agree
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+/// Hashes the content of a file, to be fed to `get_processed_filename`
+pub fn hash_file_content(path: &Path) -> Result<[u8; 32]> {
+    let content =
+        fs::read(path).with_context(|| format!("Failed to read image: {}", path.display()))?;
+    Ok(Sha256::digest(content).into())
+}
 
 /// Apply image rotation based on EXIF data
 /// Returns `None` if no transformation is needed
@@ -32,24 +78,23 @@ pub fn fix_orientation(img: &DynamicImage, path: &Path) -> Option<DynamicImage>
     }
 }
 
-/// We only use the input_path to get the file stem.
-/// Hashing the resolved `input_path` would include the absolute path to the image
-/// with all filesystem components.
+/// The filename only depends on the content of the image, see `hash_file_content`, and not on
+/// its path so identical images at different paths processed the same way share one file.
+/// The crop/resize instructions are hashed as well since they depend on the size of the input.
 pub fn get_processed_filename(
-    input_path: &Path,
-    input_src: &str,
+    content_hash: &[u8; 32],
     op: &ResizeOperation,
+    instr: &ResizeInstructions,
+    post: &PostProcessing,
     format: &Format,
 ) -> String {
-    let mut hasher = DefaultHasher::new();
-    hasher.write(input_src.as_ref());
+    let mut hasher = StableHasher::default();
+    hasher.write(content_hash);
     op.hash(&mut hasher);
+    instr.hash(&mut hasher);
+    post.hash(&mut hasher);
     format.hash(&mut hasher);
     let hash = hasher.finish();
-    let filename = input_path
-        .file_stem()
-        .map(|s| s.to_string_lossy())
-        .unwrap_or_else(|| Cow::Borrowed("unknown"));
 
-    format!("{}.{:016x}.{}", filename, hash, format.extension())
+    format!("{:016x}.{}", hash, format.extension())
 }
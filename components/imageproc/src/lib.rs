@@ -1,10 +1,15 @@
+mod colors;
 mod format;
 mod helpers;
+mod lqip;
 mod meta;
+mod og_image;
 mod ops;
 mod processor;
 
+pub use colors::get_image_colors;
 pub use helpers::fix_orientation;
+pub use lqip::{get_image_lqip, MAX_LQIP_SIZE};
 pub use meta::{read_image_metadata, ImageMeta, ImageMetaResponse};
-pub use ops::{ResizeInstructions, ResizeOperation};
+pub use ops::{PostProcessing, ResizeInstructions, ResizeOperation};
 pub use processor::{EnqueueResponse, Processor, RESIZED_SUBDIR};
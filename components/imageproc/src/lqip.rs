@@ -0,0 +1,36 @@
+use std::path::Path;
+
+use errors::{anyhow, Context, Result};
+use libs::base64::engine::{general_purpose::STANDARD as standard_b64, Engine};
+use libs::image::{self, DynamicImage};
+use libs::webp;
+
+/// The maximum size of the data URI returned by `get_image_lqip`, in bytes
+pub const MAX_LQIP_SIZE: usize = 1024;
+/// The placeholder is downscaled to fit one of those sizes, the first one fitting in `MAX_LQIP_SIZE`
+const LQIP_SIZES: [u32; 4] = [20, 16, 12, 8];
+const LQIP_QUALITY: f32 = 30.0;
+const LQIP_BLUR: f32 = 1.0;
+
+fn encode(img: &DynamicImage, size: u32) -> Result<String> {
+    let thumbnail = DynamicImage::ImageRgba8(img.thumbnail(size, size).blur(LQIP_BLUR).to_rgba8());
+    let encoder = webp::Encoder::from_image(&thumbnail)
+        .map_err(|_| anyhow!("Unable to load this kind of image with webp"))?;
+    let bytes = encoder.encode(LQIP_QUALITY);
+    Ok(format!("data:image/webp;base64,{}", standard_b64.encode(&*bytes)))
+}
+
+/// Returns a tiny blurred WebP version of the image as a base64 data URI, to use as a
+/// placeholder while the real image loads. It is never bigger than `MAX_LQIP_SIZE`.
+pub fn get_image_lqip(path: &Path) -> Result<String> {
+    let img =
+        image::open(path).with_context(|| format!("Failed to read image: {}", path.display()))?;
+
+    for size in LQIP_SIZES {
+        let uri = encode(&img, size)?;
+        if uri.len() <= MAX_LQIP_SIZE {
+            return Ok(uri);
+        }
+    }
+    Err(anyhow!("The placeholder of {} doesn't fit in {} bytes", path.display(), MAX_LQIP_SIZE))
+}
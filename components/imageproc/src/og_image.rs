@@ -0,0 +1,176 @@
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use config::{parse_hex_color, OgImage};
+use errors::{anyhow, Context, Result};
+use libs::ab_glyph::{point, Font, FontVec, GlyphId, PxScale, ScaleFont};
+use libs::image::{self, ImageFormat, Rgba, RgbaImage};
+use utils::fs as ufs;
+
+use crate::helpers::StableHasher;
+
+/// Holds all data needed to generate a social share image with the title of a page
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct OgImageOp {
+    title: String,
+    template: Option<PathBuf>,
+    font: PathBuf,
+    /// The `og_image` settings, as `text_color, background_color, width, height, padding`
+    /// and the font size bits, since a f32 isn't hashable
+    settings: (String, String, u32, u32, u32, u32),
+    pub(crate) output_path: PathBuf,
+    /// Same as `ImageOp::ignore`
+    ignore: bool,
+}
+
+impl OgImageOp {
+    pub fn new(title: &str, config: &OgImage, base_path: &Path, output_dir: &Path) -> Result<Self> {
+        let font = match &config.font {
+            Some(f) => base_path.join(f),
+            None => {
+                return Err(anyhow!(
+                    "`og_image.font` needs to be set in the config to generate the images of pages with `og_image = true`"
+                ))
+            }
+        };
+        let template = config.template.as_ref().map(|t| base_path.join(t));
+        let settings = (
+            config.text_color.clone(),
+            config.background_color.clone(),
+            config.width,
+            config.height,
+            config.padding,
+            config.font_size.to_bits(),
+        );
+
+        // The paths are hashed as written in the config, the absolute ones vary between machines
+        let mut hasher = StableHasher::default();
+        title.hash(&mut hasher);
+        config.template.hash(&mut hasher);
+        config.font.hash(&mut hasher);
+        settings.hash(&mut hasher);
+        let output_path = output_dir.join(format!("og-image.{:016x}.png", hasher.finish()));
+
+        let inputs_changed = |output: &Path| {
+            ufs::file_stale(&font, output)
+                || template.as_ref().map(|t| ufs::file_stale(t, output)).unwrap_or(false)
+        };
+        let ignore = output_path.exists() && !inputs_changed(&output_path);
+
+        Ok(OgImageOp { title: title.to_string(), template, font, settings, output_path, ignore })
+    }
+
+    pub fn perform(&self) -> Result<()> {
+        if self.ignore {
+            return Ok(());
+        }
+        let (text_color, background_color, width, height, padding, font_size) = &self.settings;
+
+        let mut img = match &self.template {
+            Some(t) => image::open(t)
+                .with_context(|| format!("Failed to read og_image template: {}", t.display()))?
+                .to_rgba8(),
+            None => {
+                RgbaImage::from_pixel(*width, *height, Rgba(parse_hex_color(background_color)?))
+            }
+        };
+        let font_data = fs::read(&self.font)
+            .with_context(|| format!("Failed to read og_image font: {}", self.font.display()))?;
+        let font = FontVec::try_from_vec(font_data)
+            .map_err(|_| anyhow!("Invalid og_image font: {}", self.font.display()))?;
+
+        draw_title(
+            &mut img,
+            &self.title,
+            &font,
+            PxScale::from(f32::from_bits(*font_size)),
+            Rgba(parse_hex_color(text_color)?),
+            *padding as f32,
+        );
+        img.save_with_format(&self.output_path, ImageFormat::Png)?;
+        Ok(())
+    }
+}
+
+fn line_width<F: ScaleFont<G>, G: Font>(font: &F, text: &str) -> f32 {
+    let mut width = 0.0;
+    let mut previous: Option<GlyphId> = None;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let Some(prev) = previous {
+            width += font.kern(prev, id);
+        }
+        width += font.h_advance(id);
+        previous = Some(id);
+    }
+    width
+}
+
+/// Splits the text into lines fitting in `max_width`.
+/// Words longer than a line are kept whole.
+fn wrap_text<F: ScaleFont<G>, G: Font>(font: &F, text: &str, max_width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let candidate =
+            if current.is_empty() { word.to_string() } else { format!("{} {}", current, word) };
+        if line_width(font, &candidate) <= max_width || current.is_empty() {
+            current = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Draws the title left-aligned and vertically centered, wrapping it to fit within the padding
+fn draw_title(
+    img: &mut RgbaImage,
+    title: &str,
+    font: &FontVec,
+    scale: PxScale,
+    color: Rgba<u8>,
+    padding: f32,
+) {
+    let scaled = font.as_scaled(scale);
+    let max_width = img.width() as f32 - 2.0 * padding;
+    let lines = wrap_text(&scaled, title, max_width);
+    let line_height = scaled.height() + scaled.line_gap();
+    let text_height = line_height * lines.len() as f32;
+    let mut y = ((img.height() as f32 - text_height) / 2.0).max(padding);
+
+    for line in lines {
+        let mut caret = point(padding, y + scaled.ascent());
+        let mut previous: Option<GlyphId> = None;
+        for c in line.chars() {
+            let id = scaled.glyph_id(c);
+            if let Some(prev) = previous {
+                caret.x += scaled.kern(prev, id);
+            }
+            let glyph = id.with_scale_and_position(scale, caret);
+            caret.x += scaled.h_advance(id);
+            previous = Some(id);
+
+            let Some(outlined) = font.outline_glyph(glyph) else { continue };
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let x = bounds.min.x as i32 + gx as i32;
+                let y = bounds.min.y as i32 + gy as i32;
+                if x < 0 || y < 0 || x >= img.width() as i32 || y >= img.height() as i32 {
+                    return;
+                }
+                let pixel = img.get_pixel_mut(x as u32, y as u32);
+                let alpha = coverage * (color[3] as f32 / 255.0);
+                for i in 0..3 {
+                    pixel[i] = (pixel[i] as f32 * (1.0 - alpha) + color[i] as f32 * alpha) as u8;
+                }
+                pixel[3] = pixel[3].max((alpha * 255.0) as u8);
+            });
+        }
+        y += line_height;
+    }
+}
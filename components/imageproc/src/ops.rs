@@ -1,4 +1,7 @@
+use std::hash::{Hash, Hasher};
+
 use errors::{anyhow, Result};
+use libs::image::DynamicImage;
 
 /// De-serialized & sanitized arguments of `resize_image`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -139,3 +142,61 @@ impl ResizeInstructions {
         self
     }
 }
+
+/// Effects applied by `Processor` after the crop/resize, always in that order:
+/// rotation, then grayscale, then blur.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct PostProcessing {
+    /// Clockwise rotation in degrees, one of 90, 180 or 270
+    pub rotate: Option<u32>,
+    pub grayscale: bool,
+    /// The sigma of the gaussian blur
+    pub blur: Option<f32>,
+}
+
+impl PostProcessing {
+    pub fn from_args(rotate: Option<u32>, grayscale: bool, blur: Option<f32>) -> Result<Self> {
+        let rotate = match rotate {
+            None | Some(0) => None,
+            Some(r @ (90 | 180 | 270)) => Some(r),
+            Some(r) => return Err(anyhow!("Invalid rotation: {}, expected 90, 180 or 270", r)),
+        };
+        let blur = match blur {
+            None | Some(0.0) => None,
+            Some(b) if b.is_finite() && b > 0.0 => Some(b),
+            Some(b) => return Err(anyhow!("Invalid blur: {}, expected a positive number", b)),
+        };
+
+        Ok(PostProcessing { rotate, grayscale, blur })
+    }
+
+    /// Whether the width and height of the image are swapped by the rotation
+    pub fn swaps_dimensions(&self) -> bool {
+        matches!(self.rotate, Some(90 | 270))
+    }
+
+    pub fn apply(&self, img: DynamicImage) -> DynamicImage {
+        let img = match self.rotate {
+            Some(90) => img.rotate90(),
+            Some(180) => img.rotate180(),
+            Some(270) => img.rotate270(),
+            _ => img,
+        };
+        let img = if self.grayscale { img.grayscale() } else { img };
+        match self.blur {
+            Some(sigma) => img.blur(sigma),
+            None => img,
+        }
+    }
+}
+
+// The blur is validated in `from_args` so it's never NaN
+impl Eq for PostProcessing {}
+
+impl Hash for PostProcessing {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.rotate.hash(hasher);
+        self.grayscale.hash(hasher);
+        self.blur.map(f32::to_bits).hash(hasher);
+    }
+}
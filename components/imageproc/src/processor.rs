@@ -3,9 +3,10 @@ use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 
-use config::Config;
+use config::{Config, OgImage};
 use errors::{anyhow, Context, Result};
 use libs::ahash::{HashMap, HashSet};
+use libs::image::codecs::avif::AvifEncoder;
 use libs::image::codecs::jpeg::JpegEncoder;
 use libs::image::imageops::FilterType;
 use libs::image::{EncodableLayout, ImageFormat};
@@ -15,17 +16,23 @@ use serde::{Deserialize, Serialize};
 use utils::fs as ufs;
 
 use crate::format::Format;
-use crate::helpers::get_processed_filename;
-use crate::{fix_orientation, ImageMeta, ResizeInstructions, ResizeOperation};
+use crate::helpers::{get_processed_filename, hash_file_content};
+use crate::og_image::OgImageOp;
+use crate::{fix_orientation, ImageMeta, PostProcessing, ResizeInstructions, ResizeOperation};
 
 pub static RESIZED_SUBDIR: &str = "processed_images";
+/// The AVIF encoder speed, from 1 (slowest, smallest files) to 10
+const AVIF_SPEED: u8 = 6;
 
-/// Holds all data needed to perform a resize operation
-#[derive(Debug, PartialEq, Eq, Hash)]
+/// Holds all data needed to perform a resize operation.
+/// The ops are identified by their output path: the ops on identical images at different paths
+/// write the same output file, which only needs to be done once.
+#[derive(Debug)]
 pub struct ImageOp {
     input_path: PathBuf,
     output_path: PathBuf,
     instr: ResizeInstructions,
+    post: PostProcessing,
     format: Format,
     /// Whether we actually want to perform that op.
     /// In practice we set it to true if the output file already
@@ -33,6 +40,20 @@ pub struct ImageOp {
     ignore: bool,
 }
 
+impl PartialEq for ImageOp {
+    fn eq(&self, other: &Self) -> bool {
+        self.output_path == other.output_path
+    }
+}
+
+impl Eq for ImageOp {}
+
+impl std::hash::Hash for ImageOp {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.output_path.hash(state);
+    }
+}
+
 impl ImageOp {
     fn perform(&self) -> Result<()> {
         if self.ignore {
@@ -50,6 +71,7 @@ impl ImageOp {
             Some((w, h)) => img.resize_exact(w, h, FilterType::Lanczos3),
             None => img,
         };
+        let img = self.post.apply(img);
 
         let f = File::create(&self.output_path)?;
         let mut buffered_f = BufWriter::new(f);
@@ -71,6 +93,10 @@ impl ImageOp {
                 };
                 buffered_f.write_all(memory.as_bytes())?;
             }
+            Format::Avif(q) => {
+                let encoder = AvifEncoder::new_with_speed_quality(&mut buffered_f, AVIF_SPEED, q);
+                img.write_with_encoder(encoder)?;
+            }
         }
 
         Ok(())
@@ -99,9 +125,12 @@ impl EnqueueResponse {
         static_path: PathBuf,
         meta: &ImageMeta,
         instr: &ResizeInstructions,
+        post: &PostProcessing,
     ) -> Self {
         let static_path = static_path.to_string_lossy().into_owned();
         let (width, height) = instr.resize_instruction.unwrap_or(meta.size);
+        let (width, height) =
+            if post.swaps_dimensions() { (height, width) } else { (width, height) };
         let (orig_width, orig_height) = meta.size;
 
         Self { url, static_path, width, height, orig_width, orig_height }
@@ -114,10 +143,15 @@ impl EnqueueResponse {
 #[derive(Debug)]
 pub struct Processor {
     base_url: String,
+    base_path: PathBuf,
     output_dir: PathBuf,
     img_ops: HashSet<ImageOp>,
+    og_image: OgImage,
+    og_ops: HashSet<OgImageOp>,
     /// We want to make sure we only ever get metadata for an image once
     meta_cache: HashMap<PathBuf, ImageMeta>,
+    /// Same for the hash of their content
+    content_hash_cache: HashMap<PathBuf, [u8; 32]>,
 }
 
 impl Processor {
@@ -125,8 +159,12 @@ impl Processor {
         Processor {
             output_dir: base_path.join("static").join(RESIZED_SUBDIR),
             base_url: config.make_permalink(RESIZED_SUBDIR),
+            base_path,
             img_ops: HashSet::default(),
+            og_image: config.og_image.clone(),
+            og_ops: HashSet::default(),
             meta_cache: HashMap::default(),
+            content_hash_cache: HashMap::default(),
         }
     }
 
@@ -135,13 +173,23 @@ impl Processor {
     }
 
     pub fn num_img_ops(&self) -> usize {
-        self.img_ops.len()
+        self.img_ops.len() + self.og_ops.len()
+    }
+
+    /// Enqueues the generation of a social share image with that title, using the `og_image`
+    /// config. Returns the URL of the image.
+    pub fn enqueue_og_image(&mut self, title: &str) -> Result<String> {
+        let op = OgImageOp::new(title, &self.og_image, &self.base_path, &self.output_dir)?;
+        let url =
+            format!("{}{}", self.base_url, op.output_path.file_name().unwrap().to_string_lossy());
+        self.og_ops.insert(op);
+        Ok(url)
     }
 
     pub fn enqueue(
         &mut self,
         op: ResizeOperation,
-        input_src: String,
+        post: PostProcessing,
         input_path: PathBuf,
         format: &str,
         quality: Option<u8>,
@@ -152,22 +200,28 @@ impl Processor {
                 .with_context(|| format!("Failed to read image: {}", input_path.display()))?;
             self.meta_cache.insert(input_path.clone(), meta);
         }
-        // We will have inserted it just above
+        if !self.content_hash_cache.contains_key(&input_path) {
+            let hash = hash_file_content(&input_path)?;
+            self.content_hash_cache.insert(input_path.clone(), hash);
+        }
+        // We will have inserted them just above
         let meta = &self.meta_cache[&input_path];
+        let content_hash = &self.content_hash_cache[&input_path];
         // We get the output format
         let format = Format::from_args(meta.is_lossy(), format, quality)?;
         // Now we have all the data we need to generate the output filename and the response
-        let filename = get_processed_filename(&input_path, &input_src, &op, &format);
+        let instr = ResizeInstructions::new(op, meta.size);
+        let filename = get_processed_filename(content_hash, &op, &instr, &post, &format);
         let url = format!("{}{}", self.base_url, filename);
         let static_path = Path::new("static").join(RESIZED_SUBDIR).join(&filename);
         let output_path = self.output_dir.join(&filename);
-        let instr = ResizeInstructions::new(op, meta.size);
-        let enqueue_response = EnqueueResponse::new(url, static_path, meta, &instr);
+        let enqueue_response = EnqueueResponse::new(url, static_path, meta, &instr, &post);
         let img_op = ImageOp {
             ignore: output_path.exists() && !ufs::file_stale(&input_path, &output_path),
             input_path,
             output_path,
             instr,
+            post,
             format,
         };
         self.img_ops.insert(img_op);
@@ -177,7 +231,7 @@ impl Processor {
 
     /// Run the enqueued image operations
     pub fn do_process(&mut self) -> Result<()> {
-        if !self.img_ops.is_empty() {
+        if !self.img_ops.is_empty() || !self.og_ops.is_empty() {
             ufs::create_directory(&self.output_dir)?;
         }
 
@@ -188,6 +242,15 @@ impl Processor {
                     format!("Failed to process image: {}", op.input_path.display())
                 })
             })
+            .collect::<Result<()>>()?;
+
+        self.og_ops
+            .par_iter()
+            .map(|op| {
+                op.perform().with_context(|| {
+                    format!("Failed to generate image: {}", op.output_path.display())
+                })
+            })
             .collect::<Result<()>>()
     }
 
@@ -202,7 +265,9 @@ impl Processor {
         let output_paths: HashSet<_> = self
             .img_ops
             .iter()
-            .map(|o| o.output_path.file_name().unwrap().to_string_lossy())
+            .map(|o| &o.output_path)
+            .chain(self.og_ops.iter().map(|o| &o.output_path))
+            .map(|p| p.file_name().unwrap().to_string_lossy())
             .collect();
 
         for entry in fs::read_dir(&self.output_dir)? {
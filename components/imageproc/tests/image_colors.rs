@@ -0,0 +1,40 @@
+use std::env;
+use std::path::PathBuf;
+
+use imageproc::get_image_colors;
+use libs::image::{Rgba, RgbaImage};
+use libs::once_cell::sync::Lazy;
+
+static TEST_IMGS: Lazy<PathBuf> =
+    Lazy::new(|| [env!("CARGO_MANIFEST_DIR"), "tests", "test_imgs"].iter().collect());
+
+#[test]
+fn can_get_image_colors() {
+    let colors = get_image_colors(&TEST_IMGS.join("exif_0.jpg"), 4).unwrap();
+    // The 4 quadrants have the same size so they are sorted by color
+    assert_eq!(colors, vec!["#0001fd", "#00ff01", "#fe0000", "#ffffff"]);
+}
+
+#[test]
+fn can_limit_the_number_of_colors() {
+    let colors = get_image_colors(&TEST_IMGS.join("exif_0.jpg"), 2).unwrap();
+    assert_eq!(colors.len(), 2);
+    assert_eq!(colors, get_image_colors(&TEST_IMGS.join("exif_0.jpg"), 4).unwrap()[..2]);
+}
+
+#[test]
+fn image_colors_ignore_transparent_pixels() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path().join("transparent.png");
+    // A quarter red and the rest fully transparent
+    let img = RgbaImage::from_fn(16, 16, |x, y| {
+        if x < 8 && y < 8 {
+            Rgba([255, 0, 0, 255])
+        } else {
+            Rgba([0, 0, 255, 0])
+        }
+    });
+    img.save(&path).unwrap();
+
+    assert_eq!(get_image_colors(&path, 5).unwrap(), vec!["#ff0000"]);
+}
@@ -0,0 +1,24 @@
+use std::env;
+use std::path::PathBuf;
+
+use imageproc::{get_image_lqip, MAX_LQIP_SIZE};
+use libs::base64::engine::{general_purpose::STANDARD as standard_b64, Engine};
+use libs::image::{self, GenericImageView};
+use libs::once_cell::sync::Lazy;
+
+static TEST_IMGS: Lazy<PathBuf> =
+    Lazy::new(|| [env!("CARGO_MANIFEST_DIR"), "tests", "test_imgs"].iter().collect());
+
+#[test]
+fn can_get_image_lqip() {
+    for source in ["jpg.jpg", "png.png", "webp.webp"] {
+        let uri = get_image_lqip(&TEST_IMGS.join(source)).unwrap();
+        assert!(uri.len() <= MAX_LQIP_SIZE, "{} is {} bytes", source, uri.len());
+
+        let data = uri.strip_prefix("data:image/webp;base64,").unwrap();
+        let bytes = standard_b64.decode(data).unwrap();
+        let img = image::load_from_memory(&bytes).unwrap();
+        // The test images are 300x380
+        assert_eq!(img.dimensions(), (16, 20));
+    }
+}
@@ -0,0 +1,68 @@
+use std::env;
+use std::path::PathBuf;
+
+use config::Config;
+use imageproc::Processor;
+use libs::image::{self, GenericImageView};
+use libs::once_cell::sync::Lazy;
+
+static TEST_DIR: Lazy<PathBuf> =
+    Lazy::new(|| [env!("CARGO_MANIFEST_DIR"), "tests"].iter().collect());
+
+fn og_config(template: Option<&str>) -> Config {
+    let mut config = Config::parse(
+        r#"
+title = "og_image integration tests"
+base_url = "https://example.com"
+"#,
+    )
+    .unwrap();
+    config.og_image.font =
+        Some(TEST_DIR.join("test_fonts").join("DejaVuSansMono-Oblique.ttf").display().to_string());
+    config.og_image.template =
+        template.map(|t| TEST_DIR.join("test_imgs").join(t).display().to_string());
+    config.og_image.text_color = "#ff0000".to_string();
+    config
+}
+
+fn generate(config: &Config, title: &str) -> (String, PathBuf) {
+    let tmpdir = tempfile::tempdir().unwrap().into_path();
+    let mut proc = Processor::new(tmpdir.clone(), config);
+    let url = proc.enqueue_og_image(title).unwrap();
+    proc.do_process().unwrap();
+    let filename = url.rsplit('/').next().unwrap();
+    (url.clone(), tmpdir.join("static").join("processed_images").join(filename))
+}
+
+#[test]
+fn can_generate_og_image_with_title() {
+    let config = og_config(None);
+    let (url, path) = generate(&config, "A rather long title that will need more than one line");
+    assert!(url.starts_with("https://example.com/processed_images/og-image."));
+    assert!(url.ends_with(".png"));
+
+    let img = image::open(&path).unwrap();
+    assert_eq!(img.dimensions(), (1200, 630));
+    // The title is drawn in red on the default background
+    let red_pixels = img.to_rgba8().pixels().filter(|p| p[0] > 200 && p[1] < 50).count();
+    assert!(red_pixels > 1000);
+    // and the background is left untouched around it
+    assert_eq!(img.get_pixel(0, 0).0, [0x1f, 0x29, 0x37, 255]);
+}
+
+#[test]
+fn can_generate_og_image_on_template() {
+    let config = og_config(Some("png.png"));
+    let (_, path) = generate(&config, "Hello");
+    let template = image::open(TEST_DIR.join("test_imgs").join("png.png")).unwrap();
+    let img = image::open(&path).unwrap();
+    assert_eq!(img.dimensions(), template.dimensions());
+}
+
+#[test]
+fn og_image_requires_a_font() {
+    let mut config = og_config(None);
+    config.og_image.font = None;
+    let mut proc = Processor::new(tempfile::tempdir().unwrap().into_path(), &config);
+    assert!(proc.enqueue_og_image("Hello").is_err());
+}
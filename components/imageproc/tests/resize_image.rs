@@ -2,7 +2,7 @@ use std::env;
 use std::path::{PathBuf, MAIN_SEPARATOR as SLASH};
 
 use config::Config;
-use imageproc::{fix_orientation, ImageMetaResponse, Processor, ResizeOperation};
+use imageproc::{fix_orientation, ImageMetaResponse, PostProcessing, Processor, ResizeOperation};
 use libs::image::{self, DynamicImage, GenericImageView, Pixel};
 use libs::once_cell::sync::Lazy;
 
@@ -50,7 +50,8 @@ fn image_op_test(
     let mut proc = Processor::new(tmpdir.clone(), &config);
     let resize_op = ResizeOperation::from_args(op, width, height).unwrap();
 
-    let resp = proc.enqueue(resize_op, source_img.into(), source_path, format, None).unwrap();
+    let resp =
+        proc.enqueue(resize_op, PostProcessing::default(), source_path, format, None).unwrap();
     assert_processed_path_matches(&resp.url, "https://example.com/processed_images/", expect_ext);
     assert_processed_path_matches(&resp.static_path, PROCESSED_PREFIX.as_str(), expect_ext);
     assert_eq!(resp.width, expect_width);
@@ -112,6 +113,55 @@ fn resize_image_fill2() {
     image_op_test("jpg.jpg", "fill", Some(200), Some(100), "auto", "jpg", 200, 100, 300, 380);
 }
 
+#[test]
+fn resize_image_fill_webp1() {
+    image_op_test("jpg.jpg", "fill", Some(100), Some(200), "webp", "webp", 100, 200, 300, 380);
+}
+
+#[test]
+fn resize_image_fill_webp2() {
+    image_op_test("png.png", "fill", Some(200), Some(100), "webp", "webp", 200, 100, 300, 380);
+}
+
+// `image` can't decode AVIF without the native dav1d library so we only check the output is one
+#[test]
+fn resize_image_fill_avif() {
+    let source_path = TEST_IMGS.join("jpg.jpg");
+    let tmpdir = tempfile::tempdir().unwrap().into_path();
+    let config = Config::parse(CONFIG).unwrap();
+    let mut proc = Processor::new(tmpdir.clone(), &config);
+    let resize_op = ResizeOperation::from_args("fill", Some(200), Some(100)).unwrap();
+
+    let resp =
+        proc.enqueue(resize_op, PostProcessing::default(), source_path, "avif", None).unwrap();
+    assert_processed_path_matches(&resp.static_path, PROCESSED_PREFIX.as_str(), "avif");
+    assert_eq!((resp.width, resp.height), (200, 100));
+    proc.do_process().unwrap();
+
+    let bytes = std::fs::read(tmpdir.join(&resp.static_path)).unwrap();
+    assert_eq!(&bytes[4..12], b"ftypavif");
+}
+
+fn processed_filename(source_img: &str, quality: Option<u8>) -> String {
+    // Copy the image so every invocation reads it from a different absolute path
+    let tmpdir = tempfile::tempdir().unwrap().into_path();
+    let source_path = tmpdir.join(source_img);
+    std::fs::copy(TEST_IMGS.join(source_img), &source_path).unwrap();
+    let config = Config::parse(CONFIG).unwrap();
+    let mut proc = Processor::new(tmpdir, &config);
+    let resize_op = ResizeOperation::from_args("fill", Some(100), Some(100)).unwrap();
+
+    proc.enqueue(resize_op, PostProcessing::default(), source_path, "webp", quality).unwrap().url
+}
+
+#[test]
+fn processed_filenames_are_deterministic() {
+    let first = processed_filename("jpg.jpg", Some(80));
+    assert_eq!(first, processed_filename("jpg.jpg", Some(80)));
+    assert_ne!(first, processed_filename("jpg.jpg", Some(60)));
+    assert_ne!(first, processed_filename("jpg.jpg", None));
+}
+
 #[test]
 fn resize_image_png_png() {
     image_op_test("png.png", "scale", Some(150), Some(150), "auto", "png", 150, 150, 300, 380);
@@ -229,7 +279,8 @@ fn resize_and_check(source_img: &str) -> bool {
     let mut proc = Processor::new(tmpdir.clone(), &config);
     let resize_op = ResizeOperation::from_args("scale", Some(16), Some(16)).unwrap();
 
-    let resp = proc.enqueue(resize_op, source_img.into(), source_path, "jpg", None).unwrap();
+    let resp =
+        proc.enqueue(resize_op, PostProcessing::default(), source_path, "jpg", None).unwrap();
 
     proc.do_process().unwrap();
     let processed_path = PathBuf::from(&resp.static_path);
@@ -270,3 +321,69 @@ fn asymmetric_resize_with_exif_orientations() {
     // 8: Rotate 270 CW
     image_op_test("exif_8.jpg", "scale", Some(16), Some(32), "auto", "jpg", 16, 32, 16, 16);
 }
+
+fn post_process(source_img: &str, width: u32, height: u32, post: PostProcessing) -> DynamicImage {
+    let source_path = TEST_IMGS.join(source_img);
+    let tmpdir = tempfile::tempdir().unwrap().into_path();
+    let config = Config::parse(CONFIG).unwrap();
+    let mut proc = Processor::new(tmpdir.clone(), &config);
+    let resize_op = ResizeOperation::from_args("scale", Some(width), Some(height)).unwrap();
+
+    let resp = proc.enqueue(resize_op, post, source_path, "png", None).unwrap();
+    proc.do_process().unwrap();
+    let img = image::open(tmpdir.join(&resp.static_path)).unwrap();
+    assert_eq!((resp.width, resp.height), img.dimensions());
+    img
+}
+
+#[test]
+fn resize_image_rotates_after_resizing() {
+    let post = PostProcessing::from_args(Some(90), false, None).unwrap();
+    let img = post_process("exif_0.jpg", 16, 32, post);
+    assert_eq!(img.dimensions(), (32, 16));
+    // The bottom left blue corner is now the top left one
+    assert!(img.get_pixel(0, 0)[2] > 250);
+    // and the top left red corner the top right one
+    assert!(img.get_pixel(31, 0)[0] > 250);
+}
+
+#[test]
+fn resize_image_can_be_grayscale() {
+    let post = PostProcessing::from_args(None, true, None).unwrap();
+    let img = post_process("png.png", 150, 150, post);
+    assert_eq!(img.dimensions(), (150, 150));
+    assert!(img.to_rgb8().pixels().all(|p| p[0] == p[1] && p[1] == p[2]));
+}
+
+#[test]
+fn resize_image_rejects_invalid_post_processing() {
+    assert!(PostProcessing::from_args(Some(45), false, None).is_err());
+    assert!(PostProcessing::from_args(None, false, Some(-1.0)).is_err());
+}
+
+#[test]
+fn identical_images_at_different_paths_share_one_output() {
+    let tmpdir = tempfile::tempdir().unwrap().into_path();
+    let config = Config::parse(CONFIG).unwrap();
+    let mut proc = Processor::new(tmpdir.clone(), &config);
+
+    let mut urls = Vec::new();
+    for path in ["a/photo.jpg", "b/copy.jpg"] {
+        let path = tmpdir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::copy(TEST_IMGS.join("jpg.jpg"), &path).unwrap();
+        let op = ResizeOperation::from_args("fit", Some(10), Some(10)).unwrap();
+        let resp = proc.enqueue(op, PostProcessing::default(), path, "auto", None).unwrap();
+        urls.push(resp.url);
+    }
+    assert_eq!(urls[0], urls[1]);
+    assert_eq!(proc.num_img_ops(), 1);
+
+    proc.do_process().unwrap();
+    let outputs: Vec<_> = std::fs::read_dir(tmpdir.join("static").join("processed_images"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(outputs.len(), 1);
+    assert!(urls[0].ends_with(&outputs[0]));
+}
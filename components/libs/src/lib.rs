@@ -4,10 +4,12 @@
 //! to define features, it is done in a single place.
 //! It doesn't work for crates exporting macros like `serde` or dev deps but that's ok for most.
 
+pub use ab_glyph;
 pub use ahash;
 pub use ammonia;
 pub use atty;
 pub use base64;
+pub use chrono;
 pub use csv;
 pub use elasticlunr;
 pub use filetime;
@@ -15,8 +17,10 @@ pub use gh_emoji;
 pub use glob;
 pub use globset;
 pub use grass;
+pub use ignore;
 pub use image;
 pub use lexical_sort;
+pub use md5;
 pub use minify_html;
 pub use nom_bibtex;
 pub use num_format;
@@ -24,6 +28,7 @@ pub use once_cell;
 pub use percent_encoding;
 pub use pulldown_cmark;
 pub use pulldown_cmark_escape;
+pub use pure_rust_locales;
 pub use quickxml_to_serde;
 pub use rayon;
 pub use regex;
@@ -31,6 +36,7 @@ pub use relative_path;
 pub use reqwest;
 pub use serde_json;
 pub use serde_yaml;
+pub use sha1;
 pub use sha2;
 pub use slug;
 pub use svg_metadata;
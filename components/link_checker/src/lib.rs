@@ -1,10 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::result;
 use std::sync::{Arc, RwLock};
 
 use libs::once_cell::sync::Lazy;
-use libs::reqwest::header::{HeaderMap, ACCEPT};
-use libs::reqwest::{blocking::Client, StatusCode};
+use libs::reqwest::header::{HeaderMap, ACCEPT, LOCATION};
+use libs::reqwest::{
+    blocking::{Client, Response},
+    redirect, StatusCode,
+};
 
 use config::LinkChecker;
 use errors::anyhow;
@@ -13,11 +16,14 @@ use utils::anchors::has_anchor_id;
 
 pub type Result = result::Result<StatusCode, String>;
 
+/// Whether the link is valid: the error status codes are only `Ok` if they are accepted
+/// in the config
 pub fn is_valid(res: &Result) -> bool {
-    match res {
-        Ok(ref code) => code.is_success() || *code == StatusCode::NOT_MODIFIED,
-        Err(_) => false,
-    }
+    res.is_ok()
+}
+
+fn is_success(code: StatusCode) -> bool {
+    code.is_success() || code == StatusCode::NOT_MODIFIED
 }
 
 pub fn message(res: &Result) -> String {
@@ -30,14 +36,70 @@ pub fn message(res: &Result) -> String {
 // Keep history of link checks so a rebuild doesn't have to check again
 static LINKS: Lazy<Arc<RwLock<HashMap<String, Result>>>> =
     Lazy::new(|| Arc::new(RwLock::new(HashMap::new())));
+// The final URL of the links that redirected somewhere else
+static REDIRECTS: Lazy<Arc<RwLock<HashMap<String, String>>>> =
+    Lazy::new(|| Arc::new(RwLock::new(HashMap::new())));
 // Make sure to create only a single Client so that we can reuse the connections
 static CLIENT: Lazy<Client> = Lazy::new(|| {
     Client::builder()
         .user_agent(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")))
+        // We follow the redirects ourselves to detect the loops and know the final URL
+        .redirect(redirect::Policy::none())
         .build()
         .expect("reqwest client build")
 });
 
+/// Where that URL ended up after following the redirects, if it was checked and redirected
+pub fn redirect_target(url: &str) -> Option<String> {
+    REDIRECTS.read().unwrap().get(url).cloned()
+}
+
+/// Sends the request, following the redirects up to `max_redirects` hops
+fn get_following_redirects(
+    url: &str,
+    headers: HeaderMap,
+    config: &LinkChecker,
+) -> result::Result<Response, String> {
+    let mut current = libs::url::Url::parse(url).map_err(|e| e.to_string())?;
+    let mut visited = HashSet::new();
+
+    let response = loop {
+        current.set_fragment(None);
+        visited.insert(current.clone());
+
+        let response = CLIENT
+            .get(current.as_str())
+            .headers(headers.clone())
+            .send()
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_redirection() {
+            break response;
+        }
+        let Some(location) = response.headers().get(LOCATION) else {
+            break response;
+        };
+        let mut next = location
+            .to_str()
+            .ok()
+            .and_then(|l| current.join(l).ok())
+            .ok_or_else(|| format!("Invalid redirect location received from {}", current))?;
+        next.set_fragment(None);
+
+        if visited.contains(&next) {
+            return Err(format!("Redirect loop detected: {} redirects to {}", current, next));
+        }
+        if visited.len() > config.max_redirects {
+            return Err(format!("Too many redirects (more than {})", config.max_redirects));
+        }
+        current = next;
+    };
+
+    if visited.len() > 1 {
+        REDIRECTS.write().unwrap().insert(url.to_string(), current.to_string());
+    }
+    Ok(response)
+}
+
 pub fn check_url(url: &str, config: &LinkChecker) -> Result {
     {
         let guard = LINKS.read().unwrap();
@@ -55,8 +117,11 @@ pub fn check_url(url: &str, config: &LinkChecker) -> Result {
     let check_anchor = !config.skip_anchor_prefixes.iter().any(|prefix| url.starts_with(prefix));
 
     // Need to actually do the link checking
-    let res = match CLIENT.get(url).headers(headers).send() {
-        Ok(ref mut response) if check_anchor && has_anchor(url) => {
+    let res = match get_following_redirects(url, headers, config) {
+        // There's no point checking the anchor of a page that wasn't found
+        Ok(ref mut response)
+            if check_anchor && has_anchor(url) && is_success(response.status()) =>
+        {
             let body = {
                 let mut buf: Vec<u8> = vec![];
                 response.copy_to(&mut buf).unwrap();
@@ -72,7 +137,9 @@ pub fn check_url(url: &str, config: &LinkChecker) -> Result {
             }
         }
         Ok(response) => {
-            if response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED {
+            if is_success(response.status())
+                || config.accepts_status_code(url, response.status().as_u16())
+            {
                 Ok(response.status())
             } else {
                 let error_string = if response.status().is_informational() {
@@ -90,7 +157,7 @@ pub fn check_url(url: &str, config: &LinkChecker) -> Result {
                 Err(error_string)
             }
         }
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(e),
     };
 
     LINKS.write().unwrap().insert(url.to_string(), res.clone());
@@ -121,7 +188,8 @@ fn check_page_for_anchor(url: &str, body: String) -> errors::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::{
-        check_page_for_anchor, check_url, has_anchor, is_valid, message, LinkChecker, LINKS,
+        check_page_for_anchor, check_url, has_anchor, is_valid, message, redirect_target,
+        LinkChecker, LINKS,
     };
     use libs::reqwest::StatusCode;
     use mockito::mock;
@@ -178,6 +246,61 @@ mod tests {
         assert_eq!(message(&res), "200 OK");
     }
 
+    #[test]
+    fn can_follow_redirect_chains() {
+        let server = mockito::server_url();
+        let _m1 = mock("GET", "/fz3kqwe8pd")
+            .with_status(301)
+            .with_header("Location", format!("{}/nb7tjc2ylx", server).as_str())
+            .create();
+        // Relative locations are resolved against the current URL
+        let _m2 = mock("GET", "/nb7tjc2ylx")
+            .with_status(302)
+            .with_header("Location", "/o4dvhs1wga")
+            .create();
+        let _m3 = mock("GET", "/o4dvhs1wga")
+            .with_header("Content-Type", "text/html")
+            .with_body(r#"<body><p id="target"></p></body>"#)
+            .create();
+
+        let url = format!("{}{}", server, "/fz3kqwe8pd#target");
+        let res = check_url(&url, &LinkChecker::default());
+        assert!(is_valid(&res));
+        assert_eq!(message(&res), "200 OK");
+        assert_eq!(redirect_target(&url), Some(format!("{}/o4dvhs1wga", server)));
+
+        let config = LinkChecker { max_redirects: 1, ..Default::default() };
+        let url = format!("{}{}", server, "/fz3kqwe8pd");
+        let res = check_url(&url, &config);
+        assert!(!is_valid(&res));
+        assert_eq!(message(&res), "Too many redirects (more than 1)");
+
+        let url = format!("{}{}", server, "/o4dvhs1wga");
+        assert!(is_valid(&check_url(&url, &config)));
+        assert_eq!(redirect_target(&url), None);
+    }
+
+    #[test]
+    fn can_fail_redirect_loops() {
+        let server = mockito::server_url();
+        let _m1 = mock("GET", "/h6wpq0zkre")
+            .with_status(301)
+            .with_header("Location", format!("{}/j2cnl5uxtb", server).as_str())
+            .create();
+        let _m2 = mock("GET", "/j2cnl5uxtb")
+            .with_status(301)
+            .with_header("Location", format!("{}/h6wpq0zkre", server).as_str())
+            .create();
+
+        let url = format!("{}{}", server, "/h6wpq0zkre");
+        let res = check_url(&url, &LinkChecker::default());
+        assert!(!is_valid(&res));
+        assert_eq!(
+            message(&res),
+            format!("Redirect loop detected: {0}/j2cnl5uxtb redirects to {0}/h6wpq0zkre", server)
+        );
+    }
+
     #[test]
     fn set_default_user_agent() {
         let user_agent = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
@@ -245,6 +368,38 @@ mod tests {
         assert_eq!(message(&res), "Server error status code (500 Internal Server Error) received");
     }
 
+    #[test]
+    fn can_accept_status_codes_for_url_patterns() {
+        let _m1 = mock("GET", "/bots/x7qpjnmw3k").with_status(403).with_body("Forbidden").create();
+        let _m2 = mock("GET", "/bots/d8rfu2vnzl").with_status(999).create();
+        let _m3 = mock("GET", "/gone/ka2mvbxq9s").with_status(404).with_body("Not Found").create();
+        let _m4 = mock("GET", "/bots/w3hfpt6cyo").with_status(404).with_body("Not Found").create();
+
+        let mut config = LinkChecker::default();
+        config
+            .accepted_status_codes
+            .insert(format!("{}/bots/*", mockito::server_url()), vec![403, 999]);
+        config.accepted_status_codes.insert("https://example.com/*".to_owned(), vec![404]);
+        config.resolve_globset().unwrap();
+
+        let url = format!("{}{}", mockito::server_url(), "/bots/x7qpjnmw3k");
+        let res = check_url(&url, &config);
+        assert!(is_valid(&res));
+        assert_eq!(res.unwrap(), StatusCode::FORBIDDEN);
+
+        // The anchor can't be checked on that kind of response
+        let url = format!("{}{}", mockito::server_url(), "/bots/d8rfu2vnzl#anchor");
+        assert!(is_valid(&check_url(&url, &config)));
+
+        // Those URLs don't match the patterns of the accepted codes
+        let url = format!("{}{}", mockito::server_url(), "/gone/ka2mvbxq9s");
+        assert!(!is_valid(&check_url(&url, &config)));
+        let url = format!("{}{}", mockito::server_url(), "/bots/w3hfpt6cyo");
+        let res = check_url(&url, &config);
+        assert!(!is_valid(&res));
+        assert_eq!(message(&res), "Client error status code (404 Not Found) received");
+    }
+
     #[test]
     fn can_fail_unresolved_links() {
         let res = check_url("https://t6l5cn9lpm.lxizfnzckd", &LinkChecker::default());
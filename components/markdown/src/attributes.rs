@@ -0,0 +1,213 @@
+//! Attribute lists like `{.class #id key=value}` at the end of a paragraph, to set the attributes
+//! of the `<p>` tag without having to write HTML.
+//! Headings already get theirs parsed by pulldown-cmark.
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use libs::pulldown_cmark::{Event, Tag, TagEnd};
+use libs::pulldown_cmark_escape::escape_html;
+
+/// The attributes of an element, from an attribute list
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Attributes {
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    pub attrs: Vec<(String, Option<String>)>,
+}
+
+impl Attributes {
+    /// Parses the content of an attribute list, without the braces.
+    /// Returns `None` if it's not a valid attribute list, eg for `{not an attribute list}`.
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut attributes = Attributes::default();
+        let mut chars = input.trim().chars().peekable();
+
+        while chars.peek().is_some() {
+            let mut token = String::new();
+            let mut in_quotes = false;
+            for c in chars.by_ref() {
+                if c == '"' {
+                    in_quotes = !in_quotes;
+                } else if c.is_whitespace() && !in_quotes {
+                    break;
+                }
+                token.push(c);
+            }
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if in_quotes {
+                return None;
+            }
+
+            if let Some(class) = token.strip_prefix('.') {
+                if !is_valid_name(class) {
+                    return None;
+                }
+                attributes.classes.push(class.to_owned());
+            } else if let Some(id) = token.strip_prefix('#') {
+                if !is_valid_name(id) {
+                    return None;
+                }
+                attributes.id = Some(id.to_owned());
+            } else if let Some((key, value)) = token.split_once('=') {
+                if !is_valid_key(key) {
+                    return None;
+                }
+                let value = unquote(value);
+                if value.contains('"') {
+                    return None;
+                }
+                attributes.attrs.push((key.to_owned(), Some(value.to_owned())));
+            } else {
+                return None;
+            }
+        }
+
+        if attributes == Attributes::default() {
+            return None;
+        }
+        Some(attributes)
+    }
+
+    /// Writes the attributes as HTML, with a leading space
+    pub fn write_html(&self, buffer: &mut String) {
+        if let Some(ref id) = self.id {
+            buffer.write_str(" id=\"").unwrap();
+            escape_html(&mut *buffer, id).unwrap();
+            buffer.write_str("\"").unwrap();
+        }
+        if !self.classes.is_empty() {
+            buffer.write_str(" class=\"").unwrap();
+            escape_html(&mut *buffer, &self.classes.join(" ")).unwrap();
+            buffer.write_str("\"").unwrap();
+        }
+        write_key_values(buffer, &self.attrs);
+    }
+}
+
+/// Writes `key="value"` attributes as HTML, with a leading space.
+/// The invalid keys and the event handlers, like `onclick`, are skipped.
+pub fn write_key_values(buffer: &mut String, attrs: &[(String, Option<String>)]) {
+    for (key, value) in attrs.iter().filter(|(key, _)| is_valid_key(key)) {
+        buffer.write_str(" ").unwrap();
+        buffer.write_str(key).unwrap();
+        if let Some(value) = value {
+            buffer.write_str("=\"").unwrap();
+            escape_html(&mut *buffer, value).unwrap();
+            buffer.write_str("\"").unwrap();
+        }
+    }
+}
+
+/// Removes the quotes around an attribute value, if there are some
+pub fn unquote(value: &str) -> &str {
+    value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.chars().any(|c| c.is_whitespace() || matches!(c, '"' | '{' | '}'))
+}
+
+fn is_valid_key(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+        && !key.get(..2).is_some_and(|prefix| prefix.eq_ignore_ascii_case("on"))
+}
+
+/// Finds an attribute list at the end of the text, returning the text without it
+/// and the parsed attributes.
+fn split_trailing_attributes(text: &str) -> Option<(&str, Attributes)> {
+    let text = text.trim_end();
+    let inner = text.strip_suffix('}')?;
+    let start = inner.rfind('{')?;
+    let attributes = Attributes::parse(&inner[start + 1..])?;
+    Some((text[..start].trim_end(), attributes))
+}
+
+/// Applies the attribute lists found at the end of paragraphs, replacing their opening tag.
+/// The attribute list can be on its own line, eg:
+///
+/// ```md
+/// Some text
+/// {.note}
+/// ```
+pub fn apply_paragraph_attributes(events: &mut [Event], escaped_text_events: &HashSet<usize>) {
+    let mut paragraph_start = None;
+
+    for i in 0..events.len() {
+        match events[i] {
+            Event::Start(Tag::Paragraph) => paragraph_start = Some(i),
+            Event::End(TagEnd::Paragraph) => {
+                let Some(start) = paragraph_start.take() else { continue };
+                let last = i - 1;
+                if last == start {
+                    continue;
+                }
+                let Event::Text(ref text) = events[last] else { continue };
+                let Some((remaining, attributes)) = split_trailing_attributes(text) else {
+                    continue;
+                };
+                let remaining = remaining.to_owned();
+                if remaining.is_empty() {
+                    // `\{.class}` is not an attribute list
+                    if escaped_text_events.contains(&last) {
+                        continue;
+                    }
+                    // We need some content before the attribute list
+                    if last - 1 == start {
+                        continue;
+                    }
+                    if matches!(events[last - 1], Event::SoftBreak | Event::HardBreak) {
+                        events[last - 1] = Event::Html("".into());
+                    }
+                }
+
+                events[last] = Event::Text(remaining.into());
+                let mut html = String::from("<p");
+                attributes.write_html(&mut html);
+                html.push('>');
+                events[start] = Event::Html(html.into());
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_attributes() {
+        assert_eq!(
+            Attributes::parse(".fancy #custom .other data-x=\"a b\" lang=fr"),
+            Some(Attributes {
+                id: Some("custom".to_owned()),
+                classes: vec!["fancy".to_owned(), "other".to_owned()],
+                attrs: vec![
+                    ("data-x".to_owned(), Some("a b".to_owned())),
+                    ("lang".to_owned(), Some("fr".to_owned())),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_attributes() {
+        assert_eq!(Attributes::parse(""), None);
+        assert_eq!(Attributes::parse("not an attribute list"), None);
+        assert_eq!(Attributes::parse(". #"), None);
+        assert_eq!(Attributes::parse("data-x=\"unclosed"), None);
+        assert_eq!(Attributes::parse("1=2"), None);
+        assert_eq!(Attributes::parse("onclick=alert(1)"), None);
+        assert_eq!(Attributes::parse("ONLOAD=alert(1)"), None);
+    }
+
+    #[test]
+    fn can_split_trailing_attributes() {
+        let (text, attributes) = split_trailing_attributes("Hello {.note} ").unwrap();
+        assert_eq!(text, "Hello");
+        assert_eq!(attributes.classes, vec!["note"]);
+        assert!(split_trailing_attributes("Hello {.note} world").is_none());
+        assert!(split_trailing_attributes("A set {1, 2}").is_none());
+    }
+}
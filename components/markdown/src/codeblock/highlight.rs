@@ -210,7 +210,7 @@ impl<'config> SyntaxHighlighter<'config> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use config::highlighting::resolve_syntax_and_theme;
+    use config::highlighting::{resolve_syntax_and_theme, HighlightSource};
     use config::Config;
     use libs::syntect::util::LinesWithEndings;
 
@@ -265,4 +265,20 @@ mod tests {
         }
         assert!(!out.contains("<script>"));
     }
+
+    #[test]
+    fn can_highlight_language_aliases() {
+        let mut config = Config::default();
+        config.markdown.highlight_code = true;
+        let syntax_and_theme = resolve_syntax_and_theme(Some("sh"), &config);
+        assert_eq!(syntax_and_theme.syntax.name, "Bourne Again Shell (bash)");
+        assert_eq!(syntax_and_theme.source, HighlightSource::BuiltIn);
+
+        config.markdown.language_aliases.insert("rs-nightly".to_string(), "rust".to_string());
+        // User-defined aliases take precedence over the default ones
+        config.markdown.language_aliases.insert("sh".to_string(), "python".to_string());
+        let syntax_and_theme = resolve_syntax_and_theme(Some("rs-nightly"), &config);
+        assert_eq!(syntax_and_theme.syntax.name, "Rust");
+        assert_eq!(resolve_syntax_and_theme(Some("sh"), &config).syntax.name, "Python");
+    }
 }
@@ -3,20 +3,32 @@ mod highlight;
 
 use std::ops::RangeInclusive;
 
+use libs::pulldown_cmark::{CodeBlockKind, Event, Parser, Tag};
+use libs::pulldown_cmark_escape::escape_html;
 use libs::syntect::util::LinesWithEndings;
 
 use crate::codeblock::highlight::SyntaxHighlighter;
-use config::highlighting::{resolve_syntax_and_theme, HighlightSource};
+use config::highlighting::{is_known_language, resolve_syntax_and_theme, HighlightSource};
 use config::Config;
 pub(crate) use fence::FenceSettings;
 
+/// `language` is the one of the fence, used in the classes, and `data_lang` the one it is
+/// highlighted as once the aliases are resolved
 fn opening_html(
     language: Option<&str>,
+    data_lang: Option<&str>,
+    label: Option<&str>,
     pre_style: Option<String>,
     pre_class: Option<String>,
     line_numbers: bool,
 ) -> String {
-    let mut html = String::from("<pre");
+    let mut html = String::new();
+    if let (Some(label), Some(lang)) = (label, data_lang) {
+        html.push_str("<span class=\"visually-hidden\">");
+        escape_html(&mut html, &label.replace("{lang}", lang)).unwrap();
+        html.push_str("</span>");
+    }
+    html.push_str("<pre");
     if line_numbers {
         html.push_str(" data-linenos");
     }
@@ -24,11 +36,12 @@ fn opening_html(
 
     if let Some(lang) = language {
         classes.push_str("language-");
-        classes.push_str(lang);
+        escape_html(&mut classes, lang).unwrap();
         classes.push(' ');
-
+    }
+    if let Some(lang) = data_lang {
         html.push_str(" data-lang=\"");
-        html.push_str(lang);
+        escape_html(&mut html, lang).unwrap();
         html.push('"');
     }
 
@@ -51,9 +64,12 @@ fn opening_html(
     html.push_str("><code");
     if let Some(lang) = language {
         html.push_str(" class=\"language-");
-        html.push_str(lang);
-        html.push_str("\" data-lang=\"");
-        html.push_str(lang);
+        escape_html(&mut html, lang).unwrap();
+        html.push('"');
+    }
+    if let Some(lang) = data_lang {
+        html.push_str(" data-lang=\"");
+        escape_html(&mut html, lang).unwrap();
         html.push('"');
     }
     html.push('>');
@@ -62,6 +78,8 @@ fn opening_html(
 
 pub struct CodeBlock<'config> {
     highlighter: SyntaxHighlighter<'config>,
+    // whether the code block is rendered as a diagram instead, see `markdown.diagram_languages`
+    diagram: bool,
     // fence options
     line_numbers: bool,
     line_number_start: usize,
@@ -69,6 +87,24 @@ pub struct CodeBlock<'config> {
     hide_lines: Vec<RangeInclusive<usize>>,
 }
 
+/// Returns the languages of the fenced code blocks of that markdown that can't be highlighted,
+/// without duplicates. The diagram languages are never highlighted so they are ignored.
+pub fn find_unknown_code_languages(content: &str, config: &Config) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for event in Parser::new(content) {
+        if let Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) = event {
+            let Some(lang) = FenceSettings::new(&info).language else { continue };
+            if !config.markdown.diagram_languages.iter().any(|d| d == lang)
+                && !is_known_language(lang, config)
+                && !unknown.iter().any(|l| l == lang)
+            {
+                unknown.push(lang.to_string());
+            }
+        }
+    }
+    unknown
+}
+
 impl<'config> CodeBlock<'config> {
     pub fn new<'fence_info>(
         fence: FenceSettings<'fence_info>,
@@ -76,8 +112,32 @@ impl<'config> CodeBlock<'config> {
         // path to the current file if there is one, to point where the error is
         path: Option<&'config str>,
     ) -> (Self, String) {
+        if let Some(lang) =
+            fence.language.filter(|l| config.markdown.diagram_languages.iter().any(|d| d == l))
+        {
+            let mut html_start = String::from("<pre class=\"");
+            escape_html(&mut html_start, lang).unwrap();
+            html_start.push_str("\">");
+            let highlighter = SyntaxHighlighter::new(false, resolve_syntax_and_theme(None, config));
+            return (
+                Self {
+                    highlighter,
+                    diagram: true,
+                    line_numbers: false,
+                    line_number_start: 1,
+                    highlight_lines: Vec::new(),
+                    hide_lines: Vec::new(),
+                },
+                html_start,
+            );
+        }
+
         let syntax_and_theme = resolve_syntax_and_theme(fence.language, config);
-        if syntax_and_theme.source == HighlightSource::NotFound && config.markdown.highlight_code {
+        // `zola check` reports all of them with `find_unknown_code_languages`
+        if syntax_and_theme.source == HighlightSource::NotFound
+            && config.markdown.highlight_code
+            && !config.is_in_check_mode()
+        {
             let lang = fence.language.unwrap();
             if let Some(p) = path {
                 eprintln!("Warning: Highlight language {} not found in {}", lang, p);
@@ -89,6 +149,8 @@ impl<'config> CodeBlock<'config> {
 
         let html_start = opening_html(
             fence.language,
+            fence.language.map(|l| config.markdown.resolve_language_alias(l)),
+            config.markdown.code_block_label.as_deref(),
             highlighter.pre_style(),
             highlighter.pre_class(),
             fence.line_numbers,
@@ -96,6 +158,7 @@ impl<'config> CodeBlock<'config> {
         (
             Self {
                 highlighter,
+                diagram: false,
                 line_numbers: fence.line_numbers,
                 line_number_start: fence.line_number_start,
                 highlight_lines: fence.highlight_lines,
@@ -107,6 +170,11 @@ impl<'config> CodeBlock<'config> {
 
     pub fn highlight(&mut self, content: &str) -> String {
         let mut buffer = String::new();
+        if self.diagram {
+            // The diagram libraries read the text content so it only needs to be escaped
+            escape_html(&mut buffer, content).unwrap();
+            return buffer;
+        }
         let mark_style = self.highlighter.mark_style();
 
         if self.line_numbers {
@@ -174,4 +242,12 @@ impl<'config> CodeBlock<'config> {
 
         buffer
     }
+    /// The HTML closing the code block
+    pub fn closing_html(&self) -> &'static str {
+        if self.diagram {
+            "</pre>\n"
+        } else {
+            "</code></pre>\n"
+        }
+    }
 }
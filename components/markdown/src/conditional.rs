@@ -0,0 +1,71 @@
+use errors::{bail, Result};
+use libs::once_cell::sync::Lazy;
+use libs::regex::Regex;
+
+use crate::context::RenderContext;
+
+/// Matches the start of the conditional blocks, the start of the other shortcodes with a body
+/// so we know which `{% end %}` closes what, and the ends
+static BLOCK_TAG_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"\{%\s*(?:(?P<cond>if_env|if_lang)\(\s*(?:"(?P<dq>[^"]*)"|'(?P<sq>[^']*)'|(?P<bare>[\w-]+))\s*\)|(?P<other>[a-zA-Z_]\w*)\([^%]*\)|(?P<end>end))\s*%\}"#,
+    )
+    .unwrap()
+});
+
+enum Block {
+    /// A `if_env` or `if_lang` block, whether its condition holds
+    Condition(bool),
+    /// Another shortcode with a body, left as is
+    Shortcode,
+}
+
+/// Removes the `{% if_env(production) %}...{% end %}` and `{% if_lang(fr) %}...{% end %}` blocks
+/// whose condition doesn't hold and keeps the content of the others.
+/// Those blocks can be nested, the content of a block is only kept if the conditions of all the
+/// blocks around it hold, and can contain other shortcodes.
+pub fn filter_conditional_blocks(content: &str, context: &RenderContext) -> Result<String> {
+    if !content.contains("{% if_") && !content.contains("{%if_") {
+        return Ok(content.to_string());
+    }
+
+    let environment = context.config.environment();
+    let mut out = String::with_capacity(content.len());
+    let mut stack = Vec::new();
+    let is_visible = |stack: &[Block]| stack.iter().all(|b| !matches!(b, Block::Condition(false)));
+    let mut last = 0;
+
+    for caps in BLOCK_TAG_RE.captures_iter(content) {
+        let tag = caps.get(0).unwrap();
+        if is_visible(&stack) {
+            out.push_str(&content[last..tag.start()]);
+        }
+        last = tag.end();
+
+        if let Some(cond) = caps.name("cond") {
+            let value = ["dq", "sq", "bare"].iter().find_map(|n| caps.name(n)).unwrap().as_str();
+            let holds = match cond.as_str() {
+                "if_env" => value == environment,
+                _ => value == context.lang,
+            };
+            stack.push(Block::Condition(holds));
+            continue;
+        }
+
+        let is_end = caps.name("end").is_some();
+        let visible = is_visible(&stack);
+        let block = if is_end { stack.pop() } else { None };
+        if caps.name("other").is_some() {
+            stack.push(Block::Shortcode);
+        }
+        if visible && !matches!(block, Some(Block::Condition(_))) {
+            out.push_str(tag.as_str());
+        }
+    }
+
+    if stack.iter().any(|b| matches!(b, Block::Condition(_))) {
+        bail!("A `if_env` or `if_lang` block is not closed with `{{% end %}}`");
+    }
+    out.push_str(&content[last..]);
+    Ok(out)
+}
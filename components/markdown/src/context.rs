@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::path::Path;
 
 use config::Config;
 use libs::tera::{Context, Tera};
@@ -18,6 +19,8 @@ pub struct RenderContext<'a> {
     pub insert_anchor: InsertAnchor,
     pub lang: &'a str,
     pub shortcode_definitions: Cow<'a, HashMap<String, ShortcodeDefinition>>,
+    /// The content directory, where the files of `include` are looked for
+    pub content_path: Option<&'a Path>,
 }
 
 impl<'a> RenderContext<'a> {
@@ -31,6 +34,7 @@ impl<'a> RenderContext<'a> {
     ) -> RenderContext<'a> {
         let mut tera_context = Context::new();
         tera_context.insert("config", &config.serialize(lang));
+        config.build_info.insert_into(&mut tera_context);
         tera_context.insert("lang", lang);
 
         Self {
@@ -43,6 +47,7 @@ impl<'a> RenderContext<'a> {
             config,
             lang,
             shortcode_definitions: Cow::Owned(HashMap::new()),
+            content_path: None,
         }
     }
 
@@ -57,6 +62,11 @@ impl<'a> RenderContext<'a> {
         self.current_page_path = Some(path);
     }
 
+    /// Same as above
+    pub fn set_content_path(&mut self, path: &'a Path) {
+        self.content_path = Some(path);
+    }
+
     // In use in the markdown filter
     // NOTE: This RenderContext is not i18n-aware, see MarkdownFilter::filter for details
     // If this function is ever used outside of MarkdownFilter, take this into consideration
@@ -71,6 +81,7 @@ impl<'a> RenderContext<'a> {
             config,
             lang: &config.default_language,
             shortcode_definitions: Cow::Owned(HashMap::new()),
+            content_path: None,
         }
     }
 }
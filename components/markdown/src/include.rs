@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use errors::{bail, Context, Result};
+use libs::once_cell::sync::Lazy;
+use libs::regex::{Captures, Regex};
+use utils::fs::{is_path_in_directory, read_file};
+
+use crate::context::RenderContext;
+
+static INCLUDE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\{\{\s*include\(\s*path\s*=\s*(?:"([^"]*)"|'([^']*)')\s*\)\s*\}\}"#).unwrap()
+});
+
+static FRONT_MATTER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^[[:space:]]*(?:\+\+\+\r?\n(?s:.*?)\+\+\+|---\r?\n(?s:.*?)---)[[:space:]]*?(?:\r?\n|$)",
+    )
+    .unwrap()
+});
+
+/// Replaces the `{{ include(path="@/snippets/legal.md") }}` in the content by the markdown of the
+/// files they point to, without their front matter, so their shortcodes are rendered as if they
+/// were written in the content. The included files can include other files themselves.
+/// A user-defined `include` shortcode takes precedence over this.
+pub fn insert_includes(content: &str, context: &RenderContext) -> Result<String> {
+    if context.shortcode_definitions.contains_key("include") || !INCLUDE_RE.is_match(content) {
+        return Ok(content.to_string());
+    }
+    let mut stack: Vec<String> = context.current_page_path.map(String::from).into_iter().collect();
+    expand(content, context, &mut stack)
+}
+
+fn expand(content: &str, context: &RenderContext, stack: &mut Vec<String>) -> Result<String> {
+    let mut error = None;
+    let res = INCLUDE_RE.replace_all(content, |caps: &Captures| {
+        let path = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+        match include(path, context, stack) {
+            Ok(included) => included,
+            Err(e) => {
+                error.get_or_insert(e);
+                String::new()
+            }
+        }
+    });
+    match error {
+        Some(e) => Err(e),
+        None => Ok(res.into_owned()),
+    }
+}
+
+fn include(path: &str, context: &RenderContext, stack: &mut Vec<String>) -> Result<String> {
+    let relative = match path.strip_prefix("@/") {
+        Some(p) => p,
+        None => bail!("`include` only works with paths starting with `@/`, got `{}`", path),
+    };
+    let content_path: &Path = match context.content_path {
+        Some(p) => p,
+        None => bail!("`include` can only be used in the content of pages and sections"),
+    };
+    if stack.iter().any(|p| p == relative) {
+        bail!("Include cycle detected: {} -> {}", stack.join(" -> "), relative);
+    }
+
+    let file_path = content_path.join(relative);
+    if !file_path.exists() {
+        bail!("`include`: file `{}` not found", path);
+    }
+    if !is_path_in_directory(content_path, &file_path)? {
+        bail!("`include`: `{}` is not inside the content directory", path);
+    }
+    let source = read_file(&file_path)?;
+    let source = FRONT_MATTER_RE.replace(&source, "");
+
+    stack.push(relative.to_string());
+    let res =
+        expand(&source, context, stack).with_context(|| format!("Failed to include `{}`", path));
+    stack.pop();
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_strip_front_matter_of_included_files() {
+        for (source, expected) in [
+            ("+++\ntitle = \"Legal\"\n+++\nHello", "Hello"),
+            ("---\ntitle: Legal\n---\nHello", "Hello"),
+            ("+++\n+++\n", ""),
+            ("No front matter\n+++", "No front matter\n+++"),
+        ] {
+            assert_eq!(FRONT_MATTER_RE.replace(source, ""), expected);
+        }
+    }
+}
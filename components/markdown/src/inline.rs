@@ -0,0 +1,218 @@
+//! Inline extensions pulldown-cmark doesn't know about, like `==mark==` or `^superscript^`.
+//! They are applied on the events after parsing: only `Text` events are looked at so
+//! code spans and code blocks are never transformed.
+use std::collections::{HashMap, HashSet};
+
+use libs::pulldown_cmark::{Event, Tag, TagEnd};
+
+/// A run of `len` times `marker` wrapping some text that will be rendered in a `tag` element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InlineDelimiter {
+    pub marker: char,
+    pub len: usize,
+    pub tag: &'static str,
+}
+
+pub const HIGHLIGHT_MARK: InlineDelimiter = InlineDelimiter { marker: '=', len: 2, tag: "mark" };
+/// `~~` is a strikethrough and is handled by pulldown-cmark
+pub const SUBSCRIPT: InlineDelimiter = InlineDelimiter { marker: '~', len: 1, tag: "sub" };
+pub const SUPERSCRIPT: InlineDelimiter = InlineDelimiter { marker: '^', len: 1, tag: "sup" };
+
+/// A delimiter run found in a text event
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    event_idx: usize,
+    offset: usize,
+    delimiter: InlineDelimiter,
+}
+
+fn is_inline_tag(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. } | Tag::Image { .. }
+    )
+}
+
+fn is_inline_tag_end(tag: &TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link | TagEnd::Image
+    )
+}
+
+/// Finds the runs of delimiters in a text event and pairs them with the openers found so far.
+/// `openers` is modified in place and the pairs found are pushed to `pairs`.
+/// `prev_char` is the last char of the previous event if it was a text event.
+fn find_runs(
+    text: &str,
+    event_idx: usize,
+    starts_escaped: bool,
+    prev_char: Option<char>,
+    delimiters: &[InlineDelimiter],
+    openers: &mut Vec<Run>,
+    pairs: &mut Vec<(Run, Run)>,
+) {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let char_before = |i: usize| i.checked_sub(1).map(|p| chars[p].1).or(prev_char);
+    let mut i = if starts_escaped { 1 } else { 0 };
+
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        let mut end = i;
+        while end < chars.len() && chars[end].1 == c {
+            end += 1;
+        }
+        let run_len = end - i;
+
+        // `[^` is a footnote reference that pulldown-cmark didn't find the definition of
+        let is_footnote_ref = c == '^' && char_before(i) == Some('[');
+
+        if let Some(delimiter) = delimiters
+            .iter()
+            .find(|d| d.marker == c && d.len == run_len)
+            .filter(|_| !is_footnote_ref)
+        {
+            // We can't know what's in other events so we assume we can open/close there
+            let can_open = chars.get(end).is_none_or(|(_, n)| !n.is_whitespace());
+            let can_close = char_before(i).is_none_or(|p| !p.is_whitespace());
+            let run = Run { event_idx, offset, delimiter: *delimiter };
+
+            match openers.iter().rposition(|o| o.delimiter == *delimiter) {
+                Some(pos) if can_close => {
+                    // Anything opened after that opener can't be closed anymore without
+                    // overlapping tags
+                    let opener = openers[pos];
+                    openers.truncate(pos);
+                    pairs.push((opener, run));
+                }
+                _ if can_open => {
+                    // The closest opener wins: `==a ==b==` highlights only `b`
+                    openers.retain(|o| o.delimiter != *delimiter);
+                    openers.push(run);
+                }
+                _ => (),
+            }
+        }
+
+        i = end;
+    }
+}
+
+/// Replaces the matching delimiters from text events with their HTML tags.
+/// `escaped_text_events` contains the index of the text events starting with an escaped
+/// character, eg `\==` which should not be transformed.
+pub fn insert_inline_tags(
+    events: &mut Vec<Event>,
+    escaped_text_events: &HashSet<usize>,
+    delimiters: &[InlineDelimiter],
+) {
+    if delimiters.is_empty() {
+        return;
+    }
+
+    let mut pairs = Vec::new();
+    // Openers of the current inline level and the ones of the parent levels
+    // Delimiters can only be paired at the same level, eg `==a *b== c*` is not valid
+    let mut openers: Vec<Run> = Vec::new();
+    let mut parent_openers: Vec<Vec<Run>> = Vec::new();
+
+    let mut prev_char = None;
+
+    for (i, event) in events.iter().enumerate() {
+        let text_prev_char = prev_char.take();
+        match event {
+            Event::Start(tag) if is_inline_tag(tag) => {
+                parent_openers.push(std::mem::take(&mut openers));
+            }
+            Event::End(tag) if is_inline_tag_end(tag) => {
+                openers = parent_openers.pop().unwrap_or_default();
+            }
+            Event::Start(_) | Event::End(_) => {
+                // Delimiters cannot span blocks
+                openers.clear();
+                parent_openers.clear();
+            }
+            Event::Text(text) => {
+                find_runs(
+                    text,
+                    i,
+                    escaped_text_events.contains(&i),
+                    text_prev_char,
+                    delimiters,
+                    &mut openers,
+                    &mut pairs,
+                );
+                prev_char = text.chars().last();
+            }
+            _ => (),
+        }
+    }
+
+    if pairs.is_empty() {
+        return;
+    }
+
+    // event idx -> [(offset in text, delimiter len, html tag)]
+    let mut splits: HashMap<usize, Vec<(usize, usize, String)>> = HashMap::new();
+    for (opener, closer) in pairs {
+        for (run, html) in [
+            (opener, format!("<{}>", opener.delimiter.tag)),
+            (closer, format!("</{}>", closer.delimiter.tag)),
+        ] {
+            splits.entry(run.event_idx).or_default().push((run.offset, run.delimiter.len, html));
+        }
+    }
+
+    let old_events = std::mem::take(events);
+    for (i, event) in old_events.into_iter().enumerate() {
+        match (splits.get_mut(&i), event) {
+            (Some(event_splits), Event::Text(text)) => {
+                event_splits.sort_by_key(|s| s.0);
+                let mut start = 0;
+                for (offset, len, html) in event_splits.drain(..) {
+                    events.push(Event::Text(text[start..offset].to_string().into()));
+                    events.push(Event::Html(html.into()));
+                    // Markers are all ASCII
+                    start = offset + len;
+                }
+                events.push(Event::Text(text[start..].to_string().into()));
+            }
+            (_, event) => events.push(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libs::pulldown_cmark::{html, Options, Parser};
+
+    fn render(content: &str) -> String {
+        let mut events: Vec<_> = Parser::new_ext(content, Options::empty()).collect();
+        insert_inline_tags(&mut events, &HashSet::new(), &[HIGHLIGHT_MARK, SUPERSCRIPT]);
+        let mut out = String::new();
+        html::push_html(&mut out, events.into_iter());
+        out
+    }
+
+    #[test]
+    fn can_pair_delimiters_at_same_level_only() {
+        assert_eq!(render("==a *b== c*"), "<p>==a <em>b== c</em></p>\n");
+        assert_eq!(render("==a *b* c=="), "<p><mark>a <em>b</em> c</mark></p>\n");
+    }
+
+    #[test]
+    fn closest_opener_wins() {
+        assert_eq!(render("==a ==b=="), "<p>==a <mark>b</mark></p>\n");
+    }
+
+    #[test]
+    fn can_mix_delimiters() {
+        assert_eq!(render("==x^2^== ^==a^"), "<p><mark>x<sup>2</sup></mark> <sup>==a</sup></p>\n");
+    }
+
+    #[test]
+    fn ignores_runs_of_other_lengths() {
+        assert_eq!(render("a === b ===c==="), "<p>a === b ===c===</p>\n");
+    }
+}
@@ -1,14 +1,25 @@
+mod attributes;
 mod codeblock;
+mod conditional;
 mod context;
+mod include;
+mod inline;
 mod markdown;
+mod math;
+mod sanitize;
 mod shortcode;
+mod translations;
 
+use conditional::filter_conditional_blocks;
+use include::insert_includes;
 use shortcode::{extract_shortcodes, insert_md_shortcodes};
+use translations::insert_translations;
 
 use errors::Result;
 
 use crate::markdown::markdown_to_html;
 pub use crate::markdown::Rendered;
+pub use codeblock::find_unknown_code_languages;
 pub use context::RenderContext;
 
 pub fn render_content(content: &str, context: &RenderContext) -> Result<markdown::Rendered> {
@@ -17,9 +28,16 @@ pub fn render_content(content: &str, context: &RenderContext) -> Result<markdown
         return markdown_to_html(content, context, Vec::new());
     }
 
+    // Inline the included files first so their shortcodes are extracted with the others
+    let content = insert_includes(content, context)?;
+    // Then remove the conditional blocks that don't apply, they can contain shortcodes
+    let content = filter_conditional_blocks(&content, context)?;
+    // The shared content is translated in the language it is rendered in
+    let content = insert_translations(&content, context)?;
+
     let definitions = context.shortcode_definitions.as_ref();
     // Extract all the defined shortcodes
-    let (content, shortcodes) = extract_shortcodes(content, definitions)?;
+    let (content, shortcodes) = extract_shortcodes(&content, definitions)?;
 
     // Step 1: we render the MD shortcodes before rendering the markdown so they can get processed
     let (content, html_shortcodes) =
@@ -1,7 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 
 use crate::markdown::cmark::CowStr;
+use config::FootnotesAt;
 use errors::bail;
 use libs::gh_emoji::Replacer as EmojiReplacer;
 use libs::once_cell::sync::Lazy;
@@ -16,11 +17,15 @@ use libs::pulldown_cmark_escape::escape_html;
 use libs::regex::{Regex, RegexBuilder};
 use utils::site::resolve_internal_link;
 use utils::slugs::slugify_anchors;
-use utils::table_of_contents::{make_table_of_contents, Heading};
+use utils::table_of_contents::{make_table_of_contents, FlatHeading, Heading};
 use utils::types::InsertAnchor;
 
 use self::cmark::{Event, LinkType, Options, Parser, Tag, TagEnd};
+use crate::attributes::{apply_paragraph_attributes, unquote, write_key_values};
 use crate::codeblock::{CodeBlock, FenceSettings};
+use crate::inline::{insert_inline_tags, HIGHLIGHT_MARK, SUBSCRIPT, SUPERSCRIPT};
+use crate::math::tex_to_mathml;
+use crate::sanitize::TrustedHtml;
 use crate::shortcode::{Shortcode, SHORTCODE_PLACEHOLDER};
 
 const CONTINUE_READING: &str = "<span id=\"continue-reading\"></span>";
@@ -79,11 +84,22 @@ fn is_colocated_asset_link(link: &str) -> bool {
         && !STARTS_WITH_SCHEMA_RE.is_match(link)
 }
 
+/// The URL colocated assets are relative to.
+/// With ugly URLs, the assets of `hello.html` are found in the `hello/` directory.
+fn colocated_asset_base(permalink: &str) -> String {
+    match permalink.strip_suffix(".html") {
+        Some(base) => format!("{}/", base),
+        None => permalink.to_owned(),
+    }
+}
+
 #[derive(Debug)]
 pub struct Rendered {
     pub body: String,
     pub summary: Option<String>,
     pub toc: Vec<Heading>,
+    /// The same headings as `toc`, in the order of the document and without nesting
+    pub toc_flat: Vec<FlatHeading>,
     /// Links to site-local pages: relative path plus optional anchor target.
     pub internal_links: Vec<(String, Option<String>)>,
     /// Outgoing links to external webpages (i.e. HTTP(S) targets).
@@ -98,11 +114,25 @@ struct HeadingRef {
     level: u32,
     id: Option<String>,
     classes: Vec<String>,
+    attrs: Vec<(String, Option<String>)>,
 }
 
 impl HeadingRef {
-    fn new(start: usize, level: u32, anchor: Option<String>, classes: &[String]) -> HeadingRef {
-        HeadingRef { start_idx: start, end_idx: 0, level, id: anchor, classes: classes.to_vec() }
+    fn new(
+        start: usize,
+        level: u32,
+        anchor: Option<String>,
+        classes: &[String],
+        attrs: Vec<(String, Option<String>)>,
+    ) -> HeadingRef {
+        HeadingRef {
+            start_idx: start,
+            end_idx: 0,
+            level,
+            id: anchor,
+            classes: classes.to_vec(),
+            attrs,
+        }
     }
 
     fn to_html(&self, id: &str) -> String {
@@ -128,6 +158,8 @@ impl HeadingRef {
             buffer.write_str("\"").unwrap();
         }
 
+        write_key_values(&mut buffer, &self.attrs);
+
         buffer.write_str(">").unwrap();
         buffer
     }
@@ -166,16 +198,21 @@ fn fix_link(
     // - it could be a link to a co-located asset
     // - it could be a normal link
     let result = if link.starts_with("@/") {
-        match resolve_internal_link(link, &context.permalinks) {
+        match resolve_internal_link(
+            link,
+            &context.permalinks,
+            context.config.internal_links_without_extension,
+        ) {
             Ok(resolved) => {
                 internal_links.push((resolved.md_path, resolved.anchor));
                 resolved.permalink
             }
-            Err(_) => {
+            Err(err) => {
                 let msg = format!(
-                    "Broken relative link `{}` in {}",
+                    "Broken relative link `{}` in {}: {}",
                     link,
                     context.current_page_path.unwrap_or("unknown"),
+                    err
                 );
                 match context.config.link_checker.internal_level {
                     config::LinkCheckerLevel::Error => bail!(msg),
@@ -187,7 +224,7 @@ fn fix_link(
             }
         }
     } else if is_colocated_asset_link(link) {
-        format!("{}{}", context.current_page_permalink, link)
+        format!("{}{}", colocated_asset_base(context.current_page_permalink), link)
     } else if is_external_link(link) {
         external_links.push(link.to_owned());
         link.to_owned()
@@ -222,17 +259,27 @@ fn get_text(parser_slice: &[Event]) -> String {
     title
 }
 
-fn get_heading_refs(events: &[Event]) -> Vec<HeadingRef> {
+/// The `key=value` attributes of the headings are only kept if `with_attrs` is set
+fn get_heading_refs(events: &[Event], with_attrs: bool) -> Vec<HeadingRef> {
     let mut heading_refs = vec![];
 
     for (i, event) in events.iter().enumerate() {
         match event {
-            Event::Start(Tag::Heading { level, id, classes, .. }) => {
+            Event::Start(Tag::Heading { level, id, classes, attrs }) => {
+                let attrs = if with_attrs {
+                    attrs
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.as_ref().map(|v| unquote(v).to_string())))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
                 heading_refs.push(HeadingRef::new(
                     i,
                     *level as u32,
                     id.clone().map(|a| a.to_string()),
                     &classes.iter().map(|x| x.to_string()).collect::<Vec<_>>(),
+                    attrs,
                 ));
             }
             Event::End(TagEnd::Heading { .. }) => {
@@ -245,7 +292,7 @@ fn get_heading_refs(events: &[Event]) -> Vec<HeadingRef> {
     heading_refs
 }
 
-fn convert_footnotes_to_github_style(old_events: &mut Vec<Event>) {
+fn convert_footnotes_to_github_style(old_events: &mut Vec<Event>, footnotes_at: FootnotesAt) {
     let events = std::mem::take(old_events);
     // step 1: We need to extract footnotes from the event stream and tweak footnote references
 
@@ -255,12 +302,15 @@ fn convert_footnotes_to_github_style(old_events: &mut Vec<Event>) {
     let mut footnotes = Vec::new();
     // this will allow to create a multiple back references
     let mut footnote_numbers = HashMap::new();
-    let filtered_events = events.into_iter().filter_map(|event| {
+    // when listing the footnotes by section, where each section ends in the events and in which
+    // section each footnote is first referenced
+    let mut section_ends = Vec::new();
+    let mut footnote_sections = HashMap::new();
+    for event in events {
         match event {
             // New footnote definition is pushed to the stack
             Event::Start(Tag::FootnoteDefinition(_)) => {
                 footnote_bodies_stack.push(vec![event]);
-                None
             }
             // The topmost footnote definition is popped from the stack
             Event::End(TagEnd::FootnoteDefinition) => {
@@ -269,7 +319,6 @@ fn convert_footnotes_to_github_style(old_events: &mut Vec<Event>) {
                 let mut footnote_body = footnote_bodies_stack.pop().unwrap();
                 footnote_body.push(event);
                 footnotes.push(footnote_body);
-                None
             }
             Event::FootnoteReference(name) => {
                 // n will be a unique index of the footnote
@@ -278,34 +327,34 @@ fn convert_footnotes_to_github_style(old_events: &mut Vec<Event>) {
                 let (n, nr) = footnote_numbers.entry(name.clone()).or_insert((n, 0usize));
                 *nr += 1;
                 let reference = Event::Html(format!(r##"<sup class="footnote-reference" id="fr-{name}-{nr}"><a href="#fn-{name}">[{n}]</a></sup>"##).into());
+                footnote_sections.entry(name).or_insert(section_ends.len());
 
                 if footnote_bodies_stack.is_empty() {
                     // we are in the main text, just output the reference
-                    Some(reference)
+                    old_events.push(reference);
                 } else {
                     // we are inside other footnote, we have to push that reference into that
                     // footnote
                     footnote_bodies_stack.last_mut().unwrap().push(reference);
-                    None
                 }
             }
             _ if !footnote_bodies_stack.is_empty() => {
                 footnote_bodies_stack.last_mut().unwrap().push(event);
-                None
             }
-            _ => Some(event),
+            Event::Start(Tag::Heading { level, .. })
+                if footnotes_at == FootnotesAt::Section && level <= cmark::HeadingLevel::H2 =>
+            {
+                section_ends.push(old_events.len());
+                old_events.push(event);
+            }
+            _ => old_events.push(event),
         }
     }
-    );
-
-    old_events.extend(filtered_events);
 
     if footnotes.is_empty() {
         return;
     }
 
-    old_events.push(Event::Html("<hr><ol class=\"footnotes-list\">\n".into()));
-
     // Step 2: retain only footnotes which was actually referenced
     footnotes.retain(|f| match f.first() {
         Some(Event::Start(Tag::FootnoteDefinition(name))) => {
@@ -322,7 +371,42 @@ fn convert_footnotes_to_github_style(old_events: &mut Vec<Event>) {
         _ => unreachable!(),
     });
 
-    // Step 4: Add backreferences to footnotes
+    // Step 4: List the footnotes at the end of the section they are first referenced in, starting
+    // from the last section so the positions of the previous ones don't move
+    let section_of = |f: &Vec<Event>| match f.first() {
+        Some(Event::Start(Tag::FootnoteDefinition(name))) => footnote_sections[name],
+        _ => unreachable!(),
+    };
+    for section in (0..=section_ends.len()).rev() {
+        let split = footnotes.partition_point(|f| section_of(f) < section);
+        let section_footnotes = footnotes.split_off(split);
+        if section_footnotes.is_empty() {
+            continue;
+        }
+        let list = footnotes_list(section_footnotes, &footnote_numbers);
+        let position = section_ends.get(section).copied().unwrap_or(old_events.len());
+        old_events.splice(position..position, list);
+    }
+}
+
+/// Renders the footnotes as an ordered list with back references to where they are used
+fn footnotes_list<'a>(
+    footnotes: Vec<Vec<Event<'a>>>,
+    footnote_numbers: &HashMap<CowStr<'a>, (usize, usize)>,
+) -> Vec<Event<'a>> {
+    let first_number = match footnotes.first().and_then(|f| f.first()) {
+        Some(Event::Start(Tag::FootnoteDefinition(name))) => footnote_numbers[name].0,
+        _ => unreachable!(),
+    };
+    let mut list = if first_number == 1 {
+        vec![Event::Html("<hr><ol class=\"footnotes-list\">\n".into())]
+    } else {
+        vec![Event::Html(
+            format!("<hr><ol class=\"footnotes-list\" start=\"{first_number}\">\n").into(),
+        )]
+    };
+
+    // Step 5: Add backreferences to the footnotes of each list of step 4
     let footnotes = footnotes.into_iter().flat_map(|fl| {
         // To write backrefs, the name needs kept until the end of the footnote definition.
         let mut name = CowStr::from("");
@@ -357,7 +441,6 @@ fn convert_footnotes_to_github_style(old_events: &mut Vec<Event>) {
         // pushed after the last tag instead.
         let mut has_written_backrefs = false;
         let fl_len = fl.len();
-        let footnote_numbers = &footnote_numbers;
         fl.into_iter().enumerate().map(move |(i, f)| match f {
             Event::Start(Tag::FootnoteDefinition(current_name)) => {
                 name = current_name;
@@ -393,8 +476,9 @@ fn convert_footnotes_to_github_style(old_events: &mut Vec<Event>) {
         })
     });
 
-    old_events.extend(footnotes);
-    old_events.push(Event::Html("</ol>\n".into()));
+    list.extend(footnotes);
+    list.push(Event::Html("</ol>\n".into()));
+    list
 }
 
 pub fn markdown_to_html(
@@ -424,6 +508,8 @@ pub fn markdown_to_html(
     let mut external_links = Vec::new();
 
     let mut stop_next_end_p = false;
+    // Whether each strikethrough we are in is actually a subscript
+    let mut strikethroughs_as_subscript = Vec::new();
 
     let lazy_async_image = context.config.markdown.lazy_async_image;
 
@@ -438,12 +524,29 @@ pub fn markdown_to_html(
     if context.config.markdown.smart_punctuation {
         opts.insert(Options::ENABLE_SMART_PUNCTUATION);
     }
+    if context.config.markdown.render_math.is_some() || context.config.markdown.math.is_some() {
+        opts.insert(Options::ENABLE_MATH);
+    }
 
     // we reverse their order so we can pop them easily in order
     let mut html_shortcodes: Vec<_> = html_shortcodes.into_iter().rev().collect();
     let mut next_shortcode = html_shortcodes.pop();
     let contains_shortcode = |txt: &str| -> bool { txt.contains(SHORTCODE_PLACEHOLDER) };
 
+    let mut inline_delimiters = Vec::new();
+    if context.config.markdown.highlight_mark {
+        inline_delimiters.push(HIGHLIGHT_MARK);
+    }
+    if context.config.markdown.subscript {
+        inline_delimiters.push(SUBSCRIPT);
+    }
+    if context.config.markdown.superscript {
+        inline_delimiters.push(SUPERSCRIPT);
+    }
+    // Text events starting with an escaped char, eg `\==`, for the inline delimiters
+    let mut escaped_text_events = HashSet::new();
+    let mut trusted_html = TrustedHtml::new(context.config.markdown.sanitize_html);
+
     {
         let mut events = Vec::new();
         macro_rules! render_shortcodes {
@@ -481,6 +584,9 @@ pub fn markdown_to_html(
                         let shortcode = next_shortcode.take().unwrap();
                         match shortcode.render(&context.tera, &context.tera_context) {
                             Ok(s) => {
+                                // Shortcodes in code blocks are part of the highlighted code
+                                let s =
+                                    if code_block.is_some() { s } else { trusted_html.protect(s) };
                                 events.push(Event::Html(s.into()));
                                 $range.start += SHORTCODE_PLACEHOLDER.len();
                             }
@@ -546,6 +652,15 @@ pub fn markdown_to_html(
                                 escape_html(&mut buffer, text.as_ref()).unwrap();
                                 events.push(Event::Html(buffer.into()));
                             } else {
+                                // pulldown-cmark starts the text event after the backslash
+                                let backslashes = content[..range.start]
+                                    .bytes()
+                                    .rev()
+                                    .take_while(|b| *b == b'\\')
+                                    .count();
+                                if backslashes % 2 == 1 {
+                                    escaped_text_events.insert(events.len());
+                                }
                                 events.push(Event::Text(text));
                             }
                             continue;
@@ -561,22 +676,28 @@ pub fn markdown_to_html(
                     };
                     let (block, begin) = CodeBlock::new(fence, context.config, path);
                     code_block = Some(block);
-                    events.push(Event::Html(begin.into()));
+                    events.push(Event::Html(trusted_html.protect(begin).into()));
                 }
                 Event::End(TagEnd::CodeBlock { .. }) => {
                     if let Some(ref mut code_block) = code_block {
                         let html = code_block.highlight(&accumulated_block);
-                        events.push(Event::Html(html.into()));
+                        events.push(Event::Html(trusted_html.protect(html).into()));
                         accumulated_block.clear();
+                        // close the code block
+                        let closing = code_block.closing_html().to_owned();
+                        events.push(Event::Html(trusted_html.protect(closing).into()));
                     }
 
-                    // reset highlight and close the code block
+                    // reset highlight
                     code_block = None;
-                    events.push(Event::Html("</code></pre>\n".into()));
                 }
                 Event::Start(Tag::Image { link_type, dest_url, title, id }) => {
                     let link = if is_colocated_asset_link(&dest_url) {
-                        let link = format!("{}{}", context.current_page_permalink, &*dest_url);
+                        let link = format!(
+                            "{}{}",
+                            colocated_asset_base(context.current_page_permalink),
+                            &*dest_url
+                        );
                         link.into()
                     } else {
                         dest_url
@@ -684,6 +805,45 @@ pub fn markdown_to_html(
                         event
                     });
                 }
+                Event::SoftBreak if context.config.markdown.hard_line_breaks => {
+                    events.push(Event::HardBreak);
+                }
+                Event::Start(Tag::Strikethrough) if context.config.markdown.subscript => {
+                    // pulldown-cmark parses `~text~` as a strikethrough as well but we only
+                    // want `~~text~~` to be one if subscripts are enabled
+                    let is_subscript = !content[range].starts_with("~~");
+                    strikethroughs_as_subscript.push(is_subscript);
+                    events.push(if is_subscript { Event::Html("<sub>".into()) } else { event });
+                }
+                Event::End(TagEnd::Strikethrough) if context.config.markdown.subscript => {
+                    events.push(if strikethroughs_as_subscript.pop().unwrap_or_default() {
+                        Event::Html("</sub>".into())
+                    } else {
+                        event
+                    });
+                }
+                Event::InlineMath(ref tex) | Event::DisplayMath(ref tex) => {
+                    let display = matches!(event, Event::DisplayMath(_));
+                    if context.config.markdown.render_math.is_some() {
+                        // Only MathML for now. It's generated by us so it doesn't need to be sanitized
+                        let html = trusted_html.protect(tex_to_mathml(tex, display));
+                        events.push(Event::Html(html.into()));
+                    } else {
+                        // Using the `\(...\)` and `\[...\]` delimiters as they are the ones
+                        // KaTeX and MathJax look for by default
+                        let mut html = String::new();
+                        if display {
+                            html.push_str("<span class=\"math math-display\">\\[");
+                            escape_html(&mut html, tex).unwrap();
+                            html.push_str("\\]</span>");
+                        } else {
+                            html.push_str("<span class=\"math math-inline\">\\(");
+                            escape_html(&mut html, tex).unwrap();
+                            html.push_str("\\)</span>");
+                        }
+                        events.push(Event::Html(html.into()));
+                    }
+                }
                 Event::Html(text) if !has_summary && MORE_DIVIDER_RE.is_match(text.as_ref()) => {
                     has_summary = true;
                     events.push(Event::Html(CONTINUE_READING.into()));
@@ -697,18 +857,37 @@ pub fn markdown_to_html(
             }
         }
 
+        if context.config.markdown.attribute_lists {
+            apply_paragraph_attributes(&mut events, &escaped_text_events);
+        }
+        insert_inline_tags(&mut events, &escaped_text_events, &inline_delimiters);
+
         // We remove all the empty things we might have pushed before so we don't get some random \n
         events.retain(|e| match e {
             Event::Text(text) | Event::Html(text) => !text.is_empty(),
             _ => true,
         });
 
-        let heading_refs = get_heading_refs(&events);
+        // Before the headings are rendered to HTML, since their start is where sections end
+        let footnotes_at = context.config.markdown.footnotes_at;
+        if context.config.markdown.bottom_footnotes || footnotes_at == FootnotesAt::Section {
+            convert_footnotes_to_github_style(&mut events, footnotes_at);
+        }
+
+        let heading_refs = get_heading_refs(&events, context.config.markdown.attribute_lists);
 
         let mut anchors_to_insert = vec![];
         let mut inserted_anchors = vec![];
+        let warn_duplicate_anchors = context.config.markdown.warn_duplicate_anchors;
+        let content_path = context.current_page_path.unwrap_or("the content");
         for heading in &heading_refs {
             if let Some(s) = &heading.id {
+                if warn_duplicate_anchors && inserted_anchors.contains(s) {
+                    console::warn(&format!(
+                        "The heading anchor `{}` is set on several headings in {}",
+                        s, content_path
+                    ));
+                }
                 inserted_anchors.push(s.to_owned());
             }
         }
@@ -720,11 +899,15 @@ pub fn markdown_to_html(
             let title = get_text(&events[start_idx + 1..end_idx]);
 
             if heading_ref.id.is_none() {
-                heading_ref.id = Some(find_anchor(
-                    &inserted_anchors,
-                    slugify_anchors(&title, context.config.slugify.anchors),
-                    0,
-                ));
+                let slug = slugify_anchors(&title, context.config.slugify.anchors);
+                let id = find_anchor(&inserted_anchors, slug.clone(), 0);
+                if warn_duplicate_anchors && id != slug {
+                    console::warn(&format!(
+                        "The heading anchor `{}` is already used, `{}` is used instead in {}",
+                        slug, id, content_path
+                    ));
+                }
+                heading_ref.id = Some(id);
             }
 
             inserted_anchors.push(heading_ref.id.clone().unwrap());
@@ -754,11 +937,14 @@ pub fn markdown_to_html(
                 )
                 .context("Failed to render anchor link template")?;
                 if context.insert_anchor != InsertAnchor::Heading {
+                    let anchor_link = trusted_html.protect(anchor_link);
                     anchors_to_insert.push((anchor_idx, Event::Html(anchor_link.into())));
                 } else if let Some(captures) = A_HTML_TAG.captures(&anchor_link) {
                     let opening_tag = captures.get(1).map_or("", |m| m.as_str()).to_string();
+                    let opening_tag = trusted_html.protect(opening_tag);
+                    let closing_tag = trusted_html.protect("</a>".to_owned());
                     anchors_to_insert.push((start_idx + 1, Event::Html(opening_tag.into())));
-                    anchors_to_insert.push((end_idx, Event::Html("</a>".into())));
+                    anchors_to_insert.push((end_idx, Event::Html(closing_tag.into())));
                 }
             }
 
@@ -778,10 +964,6 @@ pub fn markdown_to_html(
             insert_many(&mut events, anchors_to_insert);
         }
 
-        if context.config.markdown.bottom_footnotes {
-            convert_footnotes_to_github_style(&mut events);
-        }
-
         let continue_reading = events
             .iter()
             .position(|e| matches!(e, Event::Html(CowStr::Borrowed(CONTINUE_READING))))
@@ -795,11 +977,12 @@ pub fn markdown_to_html(
         if has_summary {
             // remove footnotes
             let summary_html = FOOTNOTES_RE.replace_all(&html, "").into_owned();
-            summary = Some(summary_html)
+            summary = Some(trusted_html.sanitize(&summary_html))
         }
 
         // emit everything after summary
         cmark::html::push_html(&mut html, events);
+        html = trusted_html.sanitize(&html);
     }
 
     if let Some(e) = error {
@@ -808,6 +991,7 @@ pub fn markdown_to_html(
         Ok(Rendered {
             summary,
             body: html,
+            toc_flat: headings.iter().map(FlatHeading::from).collect(),
             toc: make_table_of_contents(headings),
             internal_links,
             external_links,
@@ -864,6 +1048,12 @@ mod tests {
         }
     }
 
+    #[test]
+    fn can_find_colocated_asset_base() {
+        assert_eq!(colocated_asset_base("https://a.com/hello/"), "https://a.com/hello/");
+        assert_eq!(colocated_asset_base("https://a.com/hello.html"), "https://a.com/hello/");
+    }
+
     #[test]
     // Tests for summary being split out
     fn test_summary_split() {
@@ -902,7 +1092,7 @@ mod tests {
 
         let content = "Some text *without* footnotes.\n\nOnly ~~fancy~~ formatting.";
         let mut events: Vec<_> = Parser::new_ext(&content, opts).collect();
-        convert_footnotes_to_github_style(&mut events);
+        convert_footnotes_to_github_style(&mut events, FootnotesAt::Page);
         let mut html = String::new();
         cmark::html::push_html(&mut html, events.into_iter());
         assert_snapshot!(html);
@@ -919,7 +1109,7 @@ mod tests {
 
         let content = "This text has a footnote[^1]\n [^1]:But it is meaningless.";
         let mut events: Vec<_> = Parser::new_ext(&content, opts).collect();
-        convert_footnotes_to_github_style(&mut events);
+        convert_footnotes_to_github_style(&mut events, FootnotesAt::Page);
         let mut html = String::new();
         cmark::html::push_html(&mut html, events.into_iter());
         assert_snapshot!(html);
@@ -936,7 +1126,7 @@ mod tests {
 
         let content = "This text has two[^2] footnotes[^1]\n[^1]: not sorted.\n[^2]: But they are";
         let mut events: Vec<_> = Parser::new_ext(&content, opts).collect();
-        convert_footnotes_to_github_style(&mut events);
+        convert_footnotes_to_github_style(&mut events, FootnotesAt::Page);
         let mut html = String::new();
         cmark::html::push_html(&mut html, events.into_iter());
         assert_snapshot!(html);
@@ -953,7 +1143,7 @@ mod tests {
 
         let content = "[^1]:It's before the reference.\n\n There is footnote definition?[^1]";
         let mut events: Vec<_> = Parser::new_ext(&content, opts).collect();
-        convert_footnotes_to_github_style(&mut events);
+        convert_footnotes_to_github_style(&mut events, FootnotesAt::Page);
         let mut html = String::new();
         cmark::html::push_html(&mut html, events.into_iter());
         assert_snapshot!(html);
@@ -970,7 +1160,7 @@ mod tests {
 
         let content = "This text has two[^1] identical footnotes[^1]\n[^1]: So one is present.\n[^2]: But another in not.";
         let mut events: Vec<_> = Parser::new_ext(&content, opts).collect();
-        convert_footnotes_to_github_style(&mut events);
+        convert_footnotes_to_github_style(&mut events, FootnotesAt::Page);
         let mut html = String::new();
         cmark::html::push_html(&mut html, events.into_iter());
         assert_snapshot!(html);
@@ -987,7 +1177,7 @@ mod tests {
 
         let content = "This text has a footnote[^1]\n[^1]: But the footnote has another footnote[^2].\n[^2]: That's it.";
         let mut events: Vec<_> = Parser::new_ext(&content, opts).collect();
-        convert_footnotes_to_github_style(&mut events);
+        convert_footnotes_to_github_style(&mut events, FootnotesAt::Page);
         let mut html = String::new();
         cmark::html::push_html(&mut html, events.into_iter());
         assert_snapshot!(html);
@@ -0,0 +1,392 @@
+//! Conversion of TeX math to MathML at build time, for `markdown.render_math = "mathml"`.
+//! Only the common subset of TeX is supported: scripts, fractions, roots, greek letters and
+//! the usual operators and symbols. Unknown commands are output in a `<merror>`.
+use libs::pulldown_cmark_escape::escape_html;
+
+/// Renders the TeX math as a MathML `<math>` element
+pub fn tex_to_mathml(tex: &str, display: bool) -> String {
+    let mut parser = TexParser { chars: tex.chars().collect(), pos: 0 };
+    let content = parser.parse_sequence(None);
+    let mut html = String::from(if display { "<math display=\"block\">" } else { "<math>" });
+    html.push_str(&wrap_row(content));
+    html.push_str("</math>");
+    html
+}
+
+const GREEK_LETTERS: &[(&str, &str)] = &[
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ϵ"),
+    ("varepsilon", "ε"),
+    ("zeta", "ζ"),
+    ("eta", "η"),
+    ("theta", "θ"),
+    ("vartheta", "ϑ"),
+    ("iota", "ι"),
+    ("kappa", "κ"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("nu", "ν"),
+    ("xi", "ξ"),
+    ("pi", "π"),
+    ("rho", "ρ"),
+    ("sigma", "σ"),
+    ("tau", "τ"),
+    ("upsilon", "υ"),
+    ("phi", "ϕ"),
+    ("varphi", "φ"),
+    ("chi", "χ"),
+    ("psi", "ψ"),
+    ("omega", "ω"),
+    ("Gamma", "Γ"),
+    ("Delta", "Δ"),
+    ("Theta", "Θ"),
+    ("Lambda", "Λ"),
+    ("Xi", "Ξ"),
+    ("Pi", "Π"),
+    ("Sigma", "Σ"),
+    ("Upsilon", "Υ"),
+    ("Phi", "Φ"),
+    ("Psi", "Ψ"),
+    ("Omega", "Ω"),
+    ("infty", "∞"),
+    ("partial", "∂"),
+    ("nabla", "∇"),
+    ("ell", "ℓ"),
+    ("hbar", "ℏ"),
+    ("emptyset", "∅"),
+];
+
+const OPERATORS: &[(&str, &str)] = &[
+    ("sum", "∑"),
+    ("prod", "∏"),
+    ("int", "∫"),
+    ("iint", "∬"),
+    ("oint", "∮"),
+    ("pm", "±"),
+    ("mp", "∓"),
+    ("times", "×"),
+    ("div", "÷"),
+    ("cdot", "⋅"),
+    ("cdots", "⋯"),
+    ("ldots", "…"),
+    ("dots", "…"),
+    ("ast", "∗"),
+    ("circ", "∘"),
+    ("leq", "≤"),
+    ("le", "≤"),
+    ("geq", "≥"),
+    ("ge", "≥"),
+    ("neq", "≠"),
+    ("ne", "≠"),
+    ("approx", "≈"),
+    ("equiv", "≡"),
+    ("sim", "∼"),
+    ("propto", "∝"),
+    ("in", "∈"),
+    ("notin", "∉"),
+    ("subset", "⊂"),
+    ("subseteq", "⊆"),
+    ("supset", "⊃"),
+    ("supseteq", "⊇"),
+    ("cup", "∪"),
+    ("cap", "∩"),
+    ("forall", "∀"),
+    ("exists", "∃"),
+    ("neg", "¬"),
+    ("land", "∧"),
+    ("wedge", "∧"),
+    ("lor", "∨"),
+    ("vee", "∨"),
+    ("to", "→"),
+    ("rightarrow", "→"),
+    ("leftarrow", "←"),
+    ("Rightarrow", "⇒"),
+    ("Leftarrow", "⇐"),
+    ("leftrightarrow", "↔"),
+    ("Leftrightarrow", "⇔"),
+    ("implies", "⟹"),
+    ("iff", "⟺"),
+    ("mapsto", "↦"),
+    ("langle", "⟨"),
+    ("rangle", "⟩"),
+    ("lfloor", "⌊"),
+    ("rfloor", "⌋"),
+    ("lceil", "⌈"),
+    ("rceil", "⌉"),
+    ("mid", "∣"),
+    ("{", "{"),
+    ("}", "}"),
+    ("|", "‖"),
+];
+
+const FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan", "sinh", "cosh", "tanh",
+    "log", "ln", "lg", "exp", "lim", "max", "min", "sup", "inf", "det", "gcd", "deg", "dim", "ker",
+    "arg", "Pr",
+];
+
+const SPACES: &[(&str, &str)] =
+    &[(",", "0.1667em"), (":", "0.2222em"), (";", "0.2778em"), ("quad", "1em"), ("qquad", "2em")];
+
+fn escaped(s: &str) -> String {
+    let mut buffer = String::new();
+    escape_html(&mut buffer, s).unwrap();
+    buffer
+}
+
+fn wrap_row(mut nodes: Vec<String>) -> String {
+    if nodes.len() == 1 {
+        nodes.pop().unwrap()
+    } else {
+        format!("<mrow>{}</mrow>", nodes.concat())
+    }
+}
+
+struct TexParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl TexParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parses until the end of the input or the given closing char, which is consumed
+    fn parse_sequence(&mut self, until: Option<char>) -> Vec<String> {
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => break,
+                Some(c) if Some(c) == until => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => {
+                    if let Some(node) = self.parse_scripted() {
+                        nodes.push(node);
+                    }
+                }
+            }
+        }
+        nodes
+    }
+
+    /// Parses an atom with its optional subscript and superscript
+    fn parse_scripted(&mut self) -> Option<String> {
+        let base = self.parse_atom()?;
+        let mut sub = None;
+        let mut sup = None;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('_') if sub.is_none() => {
+                    self.pos += 1;
+                    sub = Some(self.parse_argument());
+                }
+                Some('^') if sup.is_none() => {
+                    self.pos += 1;
+                    sup = Some(self.parse_argument());
+                }
+                Some('\'') if sup.is_none() => {
+                    let mut primes = String::new();
+                    while self.peek() == Some('\'') {
+                        self.pos += 1;
+                        primes.push('′');
+                    }
+                    sup = Some(format!("<mo>{}</mo>", primes));
+                }
+                _ => break,
+            }
+        }
+
+        Some(match (sub, sup) {
+            (None, None) => base,
+            (Some(sub), None) => format!("<msub>{}{}</msub>", base, sub),
+            (None, Some(sup)) => format!("<msup>{}{}</msup>", base, sup),
+            (Some(sub), Some(sup)) => format!("<msubsup>{}{}{}</msubsup>", base, sub, sup),
+        })
+    }
+
+    /// Parses the argument of a command or script: a group or a single atom
+    fn parse_argument(&mut self) -> String {
+        self.skip_whitespace();
+        if self.peek() == Some('{') {
+            self.pos += 1;
+            return wrap_row(self.parse_sequence(Some('}')));
+        }
+        // A single digit for things like `x^23` which is `x^{2}3` in TeX
+        if let Some(c) = self.peek().filter(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+            return format!("<mn>{}</mn>", c);
+        }
+        self.parse_atom().unwrap_or_else(|| "<mrow></mrow>".to_owned())
+    }
+
+    /// Reads the raw content of a `{...}` group, for `\text`
+    fn parse_raw_group(&mut self) -> String {
+        self.skip_whitespace();
+        if self.peek() != Some('{') {
+            return String::new();
+        }
+        self.pos += 1;
+        let mut depth = 0;
+        let mut raw = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => break,
+                '}' => depth -= 1,
+                _ => (),
+            }
+            raw.push(c);
+        }
+        raw
+    }
+
+    fn parse_atom(&mut self) -> Option<String> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(match c {
+            '{' => wrap_row(self.parse_sequence(Some('}'))),
+            '\\' => self.parse_command(),
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut number = c.to_string();
+                while let Some(d) = self.peek().filter(|d| d.is_ascii_digit() || *d == '.') {
+                    self.pos += 1;
+                    number.push(d);
+                }
+                format!("<mn>{}</mn>", number)
+            }
+            c if c.is_alphabetic() => format!("<mi>{}</mi>", c),
+            // Stray closing braces and scripts without a base
+            '}' | '_' | '^' => return None,
+            '&' => return None,
+            '-' => "<mo>−</mo>".to_owned(),
+            c => format!("<mo>{}</mo>", escaped(&c.to_string())),
+        })
+    }
+
+    fn parse_command(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.peek().filter(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+            name.push(c);
+        }
+        // Commands made of a single symbol, like `\{` or `\,`
+        if name.is_empty() {
+            if let Some(c) = self.peek() {
+                self.pos += 1;
+                name.push(c);
+            }
+        }
+
+        if let Some((_, letter)) = GREEK_LETTERS.iter().find(|(n, _)| *n == name) {
+            return format!("<mi>{}</mi>", letter);
+        }
+        if let Some((_, op)) = OPERATORS.iter().find(|(n, _)| *n == name) {
+            return format!("<mo>{}</mo>", escaped(op));
+        }
+        if FUNCTIONS.contains(&name.as_str()) {
+            return format!("<mi>{}</mi>", name);
+        }
+        if let Some((_, width)) = SPACES.iter().find(|(n, _)| *n == name) {
+            return format!("<mspace width=\"{}\"></mspace>", width);
+        }
+
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.parse_argument();
+                let denominator = self.parse_argument();
+                format!("<mfrac>{}{}</mfrac>", numerator, denominator)
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                if self.peek() == Some('[') {
+                    self.pos += 1;
+                    let index = wrap_row(self.parse_sequence(Some(']')));
+                    let radicand = self.parse_argument();
+                    format!("<mroot>{}{}</mroot>", radicand, index)
+                } else {
+                    format!("<msqrt>{}</msqrt>", self.parse_argument())
+                }
+            }
+            "text" | "textrm" | "mathrm" | "operatorname" => {
+                let raw = self.parse_raw_group();
+                if name == "text" || name == "textrm" {
+                    format!("<mtext>{}</mtext>", escaped(&raw))
+                } else {
+                    format!("<mi mathvariant=\"normal\">{}</mi>", escaped(&raw))
+                }
+            }
+            // The delimiters are output as they are
+            "left" | "right" => {
+                self.skip_whitespace();
+                match self.parse_atom() {
+                    Some(delimiter) if delimiter != "<mo>.</mo>" => delimiter,
+                    _ => String::new(),
+                }
+            }
+            // Line breaks are not supported in MathML core
+            "\\" => String::new(),
+            _ => format!("<merror><mtext>\\{}</mtext></merror>", escaped(&name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_render_simple_expressions() {
+        assert_eq!(tex_to_mathml("x", false), "<math><mi>x</mi></math>");
+        assert_eq!(
+            tex_to_mathml("x^2 + 1", false),
+            "<math><mrow><msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><mn>1</mn></mrow></math>"
+        );
+        assert_eq!(
+            tex_to_mathml("a_{i,j} < b", true),
+            "<math display=\"block\"><mrow><msub><mi>a</mi><mrow><mi>i</mi><mo>,</mo><mi>j</mi></mrow></msub><mo>&lt;</mo><mi>b</mi></mrow></math>"
+        );
+    }
+
+    #[test]
+    fn can_render_commands() {
+        assert_eq!(
+            tex_to_mathml("\\frac{\\alpha}{2}", false),
+            "<math><mfrac><mi>α</mi><mn>2</mn></mfrac></math>"
+        );
+        assert_eq!(
+            tex_to_mathml("\\sqrt[3]{x}", false),
+            "<math><mroot><mi>x</mi><mn>3</mn></mroot></math>"
+        );
+        assert_eq!(
+            tex_to_mathml("\\sum_{i=0}^n i", false),
+            "<math><mrow><msubsup><mo>∑</mo><mrow><mi>i</mi><mo>=</mo><mn>0</mn></mrow><mi>n</mi></msubsup><mi>i</mi></mrow></math>"
+        );
+        assert_eq!(
+            tex_to_mathml("\\text{if <x>}", false),
+            "<math><mtext>if &lt;x&gt;</mtext></math>"
+        );
+    }
+
+    #[test]
+    fn shows_unknown_commands_as_errors() {
+        assert_eq!(
+            tex_to_mathml("\\unknown", false),
+            "<math><merror><mtext>\\unknown</mtext></merror></math>"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Optional sanitizing of the rendered HTML with an allowlist, for sites rendering markdown
+//! they don't fully trust.
+//! Only the HTML coming from the markdown is sanitized: the HTML generated by Zola or by templates
+//! (shortcodes, highlighted code blocks, anchor links) is kept out of the sanitizer.
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use libs::ammonia;
+use libs::once_cell::sync::Lazy;
+
+const TRUSTED_HTML_PLACEHOLDER_PREFIX: &str = "@@ZOLA_TRUSTED_HTML_";
+
+static AMMONIA: Lazy<ammonia::Builder<'static>> = Lazy::new(|| {
+    let mut builder = ammonia::Builder::default();
+    builder
+        // We don't want to modify the rel of the links, Zola can set it via the config
+        .link_rel(None)
+        .add_tags(["input"])
+        .add_generic_attributes(["id", "class"])
+        .add_tag_attributes("a", ["rel", "target"])
+        .add_tag_attributes("img", ["title", "loading", "decoding"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        .add_tag_attributes("ol", ["start"])
+        // Alignment of table columns
+        .add_tag_attributes("th", ["style"])
+        .add_tag_attributes("td", ["style"]);
+    builder
+});
+
+/// Keeps track of the HTML we trust while rendering so it can be put back after sanitizing.
+/// It does nothing if sanitizing is not enabled.
+#[derive(Debug, Default)]
+pub struct TrustedHtml {
+    enabled: bool,
+    /// Random for each render so the placeholders can't be written in the markdown to move trusted
+    /// HTML somewhere else, like in an attribute
+    nonce: u64,
+    fragments: Vec<String>,
+}
+
+impl TrustedHtml {
+    pub fn new(enabled: bool) -> Self {
+        let nonce = if enabled { RandomState::new().build_hasher().finish() } else { 0 };
+        Self { enabled, nonce, fragments: Vec::new() }
+    }
+
+    fn placeholder(&self, index: usize) -> String {
+        format!("{}{:016x}_{}@@", TRUSTED_HTML_PLACEHOLDER_PREFIX, self.nonce, index)
+    }
+
+    /// Returns a placeholder to use instead of that HTML if we are sanitizing
+    pub fn protect(&mut self, html: String) -> String {
+        if !self.enabled {
+            return html;
+        }
+
+        let placeholder = self.placeholder(self.fragments.len());
+        self.fragments.push(html);
+        placeholder
+    }
+
+    /// Sanitizes the HTML and puts back the trusted HTML in place of the placeholders
+    pub fn sanitize(&self, html: &str) -> String {
+        if !self.enabled {
+            return html.to_owned();
+        }
+
+        let mut clean = AMMONIA.clean(html).to_string();
+        for (i, fragment) in self.fragments.iter().enumerate() {
+            clean = clean.replacen(&self.placeholder(i), fragment, 1);
+        }
+        clean
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_nothing_if_not_enabled() {
+        let mut trusted = TrustedHtml::new(false);
+        assert_eq!(trusted.protect("<script></script>".to_owned()), "<script></script>");
+        assert_eq!(trusted.sanitize("<p onclick=\"x\">hi</p>"), "<p onclick=\"x\">hi</p>");
+    }
+
+    #[test]
+    fn keeps_trusted_html() {
+        let mut trusted = TrustedHtml::new(true);
+        let placeholder = trusted.protect("<script>trusted()</script>".to_owned());
+        let html = format!("<script>evil()</script><p>{}</p>", placeholder);
+        assert_eq!(trusted.sanitize(&html), "<p><script>trusted()</script></p>");
+    }
+
+    #[test]
+    fn placeholders_written_in_the_markdown_are_not_replaced() {
+        let mut trusted = TrustedHtml::new(true);
+        let placeholder = trusted.protect("<b class=\"x\">trusted</b>".to_owned());
+        for guess in [format!("{}0@@", TRUSTED_HTML_PLACEHOLDER_PREFIX), trusted.placeholder(1)] {
+            let html = format!("<img title=\"{}\" src=\"a.png\"><p>{}</p>", guess, placeholder);
+            let clean = trusted.sanitize(&html);
+            assert!(clean.contains(&format!("title=\"{}\"", guess)));
+            assert!(clean.ends_with("<p><b class=\"x\">trusted</b></p>"));
+        }
+        // Each render has its own placeholders
+        assert_ne!(TrustedHtml::new(true).placeholder(0), trusted.placeholder(0));
+    }
+
+    #[test]
+    fn keeps_html_generated_from_markdown() {
+        let trusted = TrustedHtml::new(true);
+        let html = r##"<h1 id="hello">Hello</h1>
+<ol start="3"><li><input disabled="" type="checkbox" checked=""> task</li></ol>
+<table><thead><tr><th style="text-align: center">a</th></tr></thead></table>
+<p><a rel="noopener" target="_blank" href="https://example.com">link</a><sup class="footnote-reference"><a href="#1">1</a></sup></p>"##;
+        assert_eq!(trusted.sanitize(html), html);
+    }
+}
@@ -0,0 +1,37 @@
+use errors::Result;
+use libs::once_cell::sync::Lazy;
+use libs::regex::{Captures, Regex};
+
+use crate::context::RenderContext;
+
+static TRANS_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\{\{\s*trans\(\s*key\s*=\s*(?:"([^"]*)"|'([^']*)')\s*\)\s*\}\}"#).unwrap()
+});
+
+/// Replaces the `{{ trans(key="greeting") }}` in the content by the translation of the key in the
+/// language of the content, for the `shared_content` of the config where one file is rendered in
+/// every language. A user-defined `trans` shortcode takes precedence over this.
+pub fn insert_translations(content: &str, context: &RenderContext) -> Result<String> {
+    if !context.config.shared_content
+        || context.shortcode_definitions.contains_key("trans")
+        || !TRANS_RE.is_match(content)
+    {
+        return Ok(content.to_string());
+    }
+
+    let mut error = None;
+    let res = TRANS_RE.replace_all(content, |caps: &Captures| {
+        let key = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+        match context.config.get_translation(context.lang, key) {
+            Ok(term) => term,
+            Err(e) => {
+                error.get_or_insert(e);
+                String::new()
+            }
+        }
+    });
+    match error {
+        Some(e) => Err(e),
+        None => Ok(res.into_owned()),
+    }
+}
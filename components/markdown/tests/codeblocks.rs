@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use config::Config;
 
 mod common;
@@ -390,3 +392,136 @@ bar
     );
     insta::assert_snapshot!(body);
 }
+
+#[test]
+fn data_lang_is_the_language_once_aliases_are_resolved() {
+    let mut config = Config::default_for_test();
+    config.markdown.language_aliases.insert("rs-nightly".to_owned(), "rust".to_owned());
+    let body = common::render_with_config("```rs-nightly\nlet a = 1;\n```\n", config.clone())
+        .unwrap()
+        .body;
+    assert_eq!(
+        body,
+        "<pre data-lang=\"rust\" class=\"language-rs-nightly \"><code class=\"language-rs-nightly\" data-lang=\"rust\">let a = 1;\n</code></pre>\n"
+    );
+
+    config.markdown.code_block_label = Some("Code in {lang}".to_owned());
+    let body =
+        common::render_with_config("```sh\nls\n```\n\n```\nplain\n```\n", config).unwrap().body;
+    assert_eq!(
+        body,
+        "<span class=\"visually-hidden\">Code in bash</span><pre data-lang=\"bash\" class=\"language-sh \"><code class=\"language-sh\" data-lang=\"bash\">ls\n</code></pre>\n<pre><code>plain\n</code></pre>\n"
+    );
+}
+
+#[test]
+fn can_render_diagram_languages_without_highlighting() {
+    let mut config = Config::default_for_test();
+    config.markdown.highlight_code = true;
+    config.markdown.diagram_languages = vec!["mermaid".to_owned(), "graphviz".to_owned()];
+    let body = common::render_with_config(
+        r#"
+```mermaid,linenos
+graph TD;
+    A-->B & C;
+    B-- "<b>label</b>" -->D;
+```
+
+```rust
+let a = 1;
+```
+    "#,
+        config,
+    )
+    .unwrap()
+    .body;
+    insta::assert_snapshot!(body);
+}
+
+#[test]
+fn can_highlight_with_a_site_theme_file() {
+    let mut config = Config::default_for_test();
+    config.markdown.highlight_code = true;
+    config.markdown.highlight_theme = "tests/highlight_themes/custom.tmTheme".to_owned();
+    config
+        .markdown
+        .init_extra_syntaxes_and_highlight_themes(Path::new(env!("CARGO_MANIFEST_DIR")))
+        .unwrap();
+    let body = common::render_with_config(
+        r#"
+```rust
+let a = 1;
+```
+    "#,
+        config,
+    )
+    .unwrap()
+    .body;
+    assert!(body.contains("background-color:#101010;"));
+    assert!(body.contains(r#"<span style="color:#ff00ff;">let</span>"#));
+}
+
+#[test]
+fn errors_on_an_invalid_site_theme_file() {
+    let mut config = Config::default_for_test();
+    config.markdown.highlight_code = true;
+    config.markdown.highlight_theme = "tests/highlight_themes/invalid.tmTheme".to_owned();
+    let err = config
+        .markdown
+        .init_extra_syntaxes_and_highlight_themes(Path::new(env!("CARGO_MANIFEST_DIR")))
+        .unwrap_err();
+    assert!(err.to_string().starts_with("Failed to load the highlight theme"));
+    assert!(err.to_string().ends_with("invalid.tmTheme"));
+}
+
+#[test]
+fn classed_highlighting_has_no_inline_styles() {
+    let body = render_codeblock(
+        r#"
+```rust
+fn main() {}
+```
+    "#,
+        HighlightMode::Classed,
+    );
+    assert!(body.contains(r#"<span class="z-storage z-type z-function z-rust">fn</span>"#));
+    assert!(!body.contains("style="));
+}
+
+#[test]
+fn can_highlight_with_an_extra_syntax() {
+    let mut config = Config::default_for_test();
+    config.markdown.highlight_code = true;
+    config.markdown.highlight_theme = "css".to_owned();
+    config.markdown.extra_syntaxes_and_themes = vec!["tests/syntaxes/valid".to_owned()];
+    config
+        .markdown
+        .init_extra_syntaxes_and_highlight_themes(Path::new(env!("CARGO_MANIFEST_DIR")))
+        .unwrap();
+    let body = common::render_with_config(
+        r#"
+```zolang
+frob the widget
+```
+    "#,
+        config,
+    )
+    .unwrap()
+    .body;
+    assert!(body.contains(r#"<span class="z-keyword z-control z-zolang">frob</span>"#));
+}
+
+#[test]
+fn errors_on_an_invalid_extra_syntax() {
+    let mut config = Config::default_for_test();
+    config.markdown.extra_syntaxes_and_themes = vec!["tests/syntaxes/invalid".to_owned()];
+    let err = config
+        .markdown
+        .init_extra_syntaxes_and_highlight_themes(Path::new(env!("CARGO_MANIFEST_DIR")))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Failed to load the syntaxes of `extra_syntaxes_and_themes` in tests/syntaxes/invalid"
+    );
+    assert!(format!("{:?}", err).contains("broken.sublime-syntax"));
+}
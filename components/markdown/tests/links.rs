@@ -48,8 +48,56 @@ Email: <foo@bar.baz>",
     let res = common::render("[abc](@/def/123.md)");
     assert!(res.is_err());
 
+    // The error suggests the closest files in case of typo
+    let res = common::render("[abc](@/pages/abot.md)");
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "Broken relative link `@/pages/abot.md` in my_page.md: Relative link @/pages/abot.md not found. Did you mean `@/pages/about.md`?"
+    );
+
     // Empty link is an error as well
     let res = common::render("[abc]()");
     assert!(res.is_err());
     assert_eq!(res.unwrap_err().to_string(), "There is a link that is missing a URL");
 }
+
+#[test]
+fn only_adds_external_links_attributes_to_external_links() {
+    let mut config = config::Config::default_for_test();
+    config.markdown.external_links_target_blank = true;
+    config.markdown.external_links_no_follow = true;
+    config.markdown.external_links_no_referrer = true;
+
+    let body = common::render_with_config(
+        "[ext](https://google.com) [int](@/pages/about.md) [anchor](#hello)",
+        config,
+    )
+    .unwrap()
+    .body;
+    assert_eq!(
+        body,
+        "<p><a rel=\"noopener nofollow noreferrer\" target=\"_blank\" href=\"https://google.com\">ext</a> \
+<a href=\"https://getzola.org/about/\">int</a> \
+<a href=\"https://www.getzola.org/test/#hello\">anchor</a></p>\n"
+    );
+}
+
+#[test]
+fn only_adds_external_links_class_to_external_links() {
+    let mut config = config::Config::default_for_test();
+    config.markdown.external_links_class = Some("external".to_owned());
+
+    let body = common::render_with_config(
+        "[ext](https://google.com \"Google\") [int](@/pages/about.md) [anchor](#hello) [rel](/about/)",
+        config,
+    )
+    .unwrap()
+    .body;
+    assert_eq!(
+        body,
+        "<p><a class=\"external\" title=\"Google\" href=\"https://google.com\">ext</a> \
+<a href=\"https://getzola.org/about/\">int</a> \
+<a href=\"https://www.getzola.org/test/#hello\">anchor</a> \
+<a href=\"/about/\">rel</a></p>\n"
+    );
+}
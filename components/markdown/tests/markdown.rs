@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use libs::tera::Tera;
 
-use config::Config;
+use config::{Config, MathDelimiters, MathRenderer};
 use markdown::{render_content, RenderContext};
 use templates::ZOLA_TERA;
 use utils::slugs::SlugifyStrategy;
@@ -133,6 +133,237 @@ fn can_use_smart_punctuation() {
     insta::assert_snapshot!(body);
 }
 
+#[test]
+fn smart_punctuation_leaves_code_alone() {
+    let mut config = Config::default_for_test();
+    config.markdown.smart_punctuation = true;
+    let markdown = r#"
+"Curly" and 'single' quotes --- but `"raw" 'code' -- ...` in a code span.
+
+```
+let s = "raw" -- ...;
+```
+"#;
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    assert!(body.contains("<p>“Curly” and ‘single’ quotes — but "));
+    assert!(body.contains("<code>\"raw\" 'code' -- ...</code> in a code span.</p>"));
+    assert!(body.contains("let s = &quot;raw&quot; -- ...;"));
+}
+
+#[test]
+fn can_use_highlight_mark() {
+    let markdown = r#"
+This is ==highlighted==.
+
+==Some *emphasis* inside== and ==multiple== marks ==per line==.
+
+# A ==highlighted== heading
+
+Left alone in a code span: `==code==`.
+
+Unmatched == or ==unmatched and a == b == c.
+
+Escaped \==not highlighted== but \\==this is==.
+"#;
+
+    let body = common::render(markdown).unwrap().body;
+    assert!(!body.contains("<mark>"));
+
+    let mut config = Config::default_for_test();
+    config.markdown.highlight_mark = true;
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    insta::assert_snapshot!(body);
+}
+
+#[test]
+fn can_use_subscript() {
+    let mut config = Config::default_for_test();
+    config.markdown.subscript = true;
+    let body = common::render_with_config(
+        "H~2~O and ~sub~ but `H~2~O` is code and this ~ is left alone.",
+        config,
+    )
+    .unwrap()
+    .body;
+    insta::assert_snapshot!(body);
+}
+
+#[test]
+fn can_use_superscript() {
+    let mut config = Config::default_for_test();
+    config.markdown.superscript = true;
+    let markdown = r#"
+x^2^ + y^n+1^ but `x^2^` is code and this ^ is left alone.
+
+A footnote[^1] and an undefined one [^nope] are not superscripts^.
+
+[^1]: The footnote^2^.
+"#;
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    insta::assert_snapshot!(body);
+}
+
+#[test]
+fn can_use_hard_line_breaks() {
+    let mut config = Config::default_for_test();
+    config.markdown.hard_line_breaks = true;
+    let markdown = "First line\nsecond line\n\n```\nlet a = 1;\nlet b = 2;\n```\n";
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    assert_eq!(
+        body,
+        "<p>First line<br />\nsecond line</p>\n<pre><code>let a = 1;\nlet b = 2;\n</code></pre>\n"
+    );
+
+    let body = common::render(markdown).unwrap().body;
+    assert!(body.starts_with("<p>First line\nsecond line</p>"));
+}
+
+#[test]
+fn can_use_attribute_lists() {
+    let mut config = Config::default_for_test();
+    config.markdown.attribute_lists = true;
+    let markdown = r#"
+## Title {.fancy #custom data-level="two"}
+
+## Title {.fancy}
+
+A paragraph with *attributes* {.note #intro lang=fr}
+
+A paragraph with the attributes on their own line
+{.note}
+
+Not an attribute list {like this} and \{.escaped}
+
+{.alone}
+"#;
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    insta::assert_snapshot!(body);
+}
+
+#[test]
+fn skips_hostile_attributes_of_headings() {
+    let mut config = Config::default_for_test();
+    config.markdown.attribute_lists = true;
+    let markdown =
+        "## Title { onmouseover=alert(1) data-ok=yes }\n\n## Three {x\"=1 a'b=2 ONLOAD=x}\n";
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    assert_eq!(body, "<h2 id=\"title\" data-ok=\"yes\">Title</h2>\n<h2 id=\"three\">Three</h2>\n");
+}
+
+#[test]
+fn attribute_lists_are_opt_in() {
+    let markdown = r#"
+## Title {#custom data-level="two"}
+
+A paragraph {.note}
+"#;
+    let body = common::render(markdown).unwrap().body;
+    insta::assert_snapshot!(body);
+}
+
+#[test]
+fn can_render_math_to_mathml() {
+    let mut config = Config::default_for_test();
+    config.markdown.render_math = Some(MathRenderer::MathMl);
+    let markdown = r#"
+Inline $x^2$ and some display math:
+
+$$
+\frac{a_1}{\sqrt{b}} \leq \alpha
+$$
+
+`$x^2$` is code.
+"#;
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    insta::assert_snapshot!(body);
+}
+
+#[test]
+fn can_keep_math_for_client_side_rendering() {
+    let mut config = Config::default_for_test();
+    config.markdown.math = Some(MathDelimiters::Dollar);
+    let markdown = r#"
+Inline $a_b + c_d < e$ is not italicized and *this* is.
+
+Neither is $a_b$ and $c_d$.
+
+$$
+\sum_{i=0}^n i_*
+$$
+"#;
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    insta::assert_snapshot!(body);
+}
+
+#[test]
+fn leaves_math_alone_if_not_rendered() {
+    let body = common::render("Inline $x^2$ and $$y$$").unwrap().body;
+    assert_eq!(body, "<p>Inline $x^2$ and $$y$$</p>\n");
+}
+
+#[test]
+fn strikethrough_wins_over_subscript() {
+    let mut config = Config::default_for_test();
+    config.markdown.subscript = true;
+    let body = common::render_with_config(
+        "~~deleted~~ and ~~deleted with H~2~O inside~~ and ~~~three~~~",
+        config,
+    )
+    .unwrap()
+    .body;
+    insta::assert_snapshot!(body);
+
+    // Without subscript, a single tilde is still a strikethrough
+    let body = common::render("~~deleted~~ and ~deleted~").unwrap().body;
+    assert_eq!(body, "<p><del>deleted</del> and <del>deleted</del></p>\n");
+}
+
+#[test]
+fn can_sanitize_html() {
+    let mut config = Config::default_for_test();
+    config.markdown.sanitize_html = true;
+    let markdown = r#"
+Some <strong>safe</strong> HTML<script>alert("hello")</script>.
+
+<div onclick="alert('hello')">A div</div>
+
+[A link](javascript:alert(1)) and <a href="javascript:alert(2)">another</a>.
+
+{% web_component() %}<span onclick="trusted()">shortcodes are trusted</span>{% end %}
+
+```
+<script>alert("in code")</script>
+```
+"#;
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    insta::assert_snapshot!(body);
+}
+
+#[test]
+fn escapes_the_language_of_code_blocks_when_sanitizing() {
+    let mut config = Config::default_for_test();
+    config.markdown.sanitize_html = true;
+    let markdown = "```x\"><script>alert(document.cookie)</script>\nhello\n```\n";
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    assert!(body.contains(
+        r#"class="language-x&quot;&gt;&lt;script&gt;alert(document.cookie)&lt;/script&gt;"#
+    ));
+    assert!(body
+        .contains(r#"data-lang="x&quot;&gt;&lt;script&gt;alert(document.cookie)&lt;/script&gt;"#));
+    assert!(!body.contains("<script"));
+}
+
+#[test]
+fn escapes_the_language_in_code_block_labels() {
+    let mut config = Config::default_for_test();
+    config.markdown.code_block_label = Some("Code in {lang}".to_string());
+    let markdown = "```a\"<b>\nhello\n```\n";
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    assert!(body.starts_with(r#"<span class="visually-hidden">Code in a&quot;&lt;b&gt;</span>"#));
+    assert!(body.contains(r#"data-lang="a&quot;&lt;b&gt;""#));
+    assert!(!body.contains("<b>"));
+}
+
 #[test]
 fn can_use_external_links_options() {
     let mut config = Config::default_for_test();
@@ -392,3 +623,37 @@ Footnotes can also be referenced with identifiers[^first].
     let body = common::render_with_config(&markdown, config).unwrap().body;
     insta::assert_snapshot!(body);
 }
+
+#[test]
+fn footnotes_can_be_listed_by_section() {
+    let mut config = Config::default_for_test();
+    config.markdown.footnotes_at = config::FootnotesAt::Section;
+
+    let markdown = r#"Intro[^intro]
+
+## First
+
+Text[^a] and more[^b].
+
+### Nested
+
+Still the first section[^c].
+
+## Second
+
+Nothing to see.
+
+## Third
+
+Reused[^a] and new[^d].
+
+[^intro]: Before any heading.
+[^a]: A.
+[^b]: B.
+[^c]: C.
+[^d]: D.
+"#;
+
+    let body = common::render_with_config(markdown, config).unwrap().body;
+    insta::assert_snapshot!(body);
+}
@@ -1,4 +1,4 @@
-use config::Config;
+use config::{Config, LanguageOptions};
 
 mod common;
 
@@ -323,3 +323,37 @@ Here is <span>{{ ex1(page="") }}</span> example.
     .body;
     insta::assert_snapshot!(body);
 }
+
+#[test]
+fn can_render_blocks_only_in_some_environments() {
+    let content = "Always\n\n{% if_env(production) %}\nProduction {{ ex1() }}\n{% end %}\n\n{% if_env(\"development\") %}\nDevelopment\n{% end %}";
+
+    let production = common::render(content).unwrap().body;
+    assert_eq!(production, "<p>Always</p>\n<p>Production 1</p>\n");
+
+    let mut config = Config::default_for_test();
+    config.enable_serve_mode();
+    let development = common::render_with_config(content, config).unwrap().body;
+    assert_eq!(development, "<p>Always</p>\n<p>Development</p>\n");
+}
+
+#[test]
+fn can_nest_conditional_blocks() {
+    let content = "{% if_lang(fr) %}\nFrançais\n{% if_env(production) %}\n{% quote() %}Prod{% end %}\n{% end %}\n{% if_env(development) %}Dev{% end %}\n{% end %}{% if_lang(en) %}English{% end %}";
+
+    let mut config = Config::default_for_test();
+    config.default_language = "fr".to_string();
+    config.languages.insert("fr".to_string(), LanguageOptions::default());
+    let body = common::render_with_config(content, config.clone()).unwrap().body;
+    assert_eq!(body, "<p>Français</p>\n<quote>Prod</quote>");
+
+    config.default_language = "en".to_string();
+    let body = common::render_with_config(content, config).unwrap().body;
+    assert_eq!(body, "<p>English</p>\n");
+}
+
+#[test]
+fn errors_on_unclosed_conditional_blocks() {
+    let res = common::render("{% if_env(production) %}\nNever closed");
+    assert!(res.is_err());
+}
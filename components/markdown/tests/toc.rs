@@ -21,6 +21,27 @@ fn can_make_simple_toc() {
     assert_eq!(toc[0].children[1].children.len(), 1);
 }
 
+#[test]
+fn duplicate_headings_get_unique_ids_in_toc() {
+    let res = common::render(
+        r#"
+## Example
+
+## Example
+
+## Example
+    "#,
+    )
+    .unwrap();
+
+    assert!(res.body.contains(r#"<h2 id="example">"#));
+    assert!(res.body.contains(r#"<h2 id="example-1">"#));
+    assert!(res.body.contains(r#"<h2 id="example-2">"#));
+    let ids: Vec<_> = res.toc.iter().map(|h| h.id.as_str()).collect();
+    assert_eq!(ids, ["example", "example-1", "example-2"]);
+    assert!(res.toc[1].permalink.ends_with("#example-1"));
+}
+
 #[test]
 fn can_ignore_tags_in_toc() {
     let res = common::render(
@@ -75,3 +96,26 @@ fn can_make_toc_all_levels() {
     assert_eq!(toc[0].children[1].children[0].children.len(), 1);
     assert_eq!(toc[0].children[1].children[0].children[0].children.len(), 1);
 }
+
+#[test]
+fn can_make_flat_toc() {
+    let res = common::render(
+        r#"
+## First
+
+### Nested
+
+## Second
+"#,
+    )
+    .unwrap();
+
+    let flat: Vec<_> =
+        res.toc_flat.iter().map(|h| (h.level, h.id.as_str(), h.title.as_str())).collect();
+    assert_eq!(flat, vec![(2, "first", "First"), (3, "nested", "Nested"), (2, "second", "Second")]);
+    // The ids are the ones of the anchors in the HTML
+    for (_, id, _) in flat {
+        assert!(res.body.contains(&format!(r#"id="{}""#, id)));
+    }
+    assert_eq!(res.toc_flat[1].permalink, res.toc[0].children[0].permalink);
+}
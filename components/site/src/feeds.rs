@@ -2,12 +2,13 @@ use std::cmp::Ordering;
 use std::path::PathBuf;
 
 use libs::rayon::prelude::*;
-use libs::tera::Context;
+use libs::tera::{to_value, Context, Value};
 use serde::Serialize;
 
 use crate::Site;
 use content::{Page, TaxonomyTerm};
 use errors::Result;
+use utils::net::make_html_urls_absolute;
 use utils::templates::render_template;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -32,6 +33,7 @@ pub fn render_feeds(
     all_pages: Vec<&Page>,
     lang: &str,
     base_path: Option<&PathBuf>,
+    feed_filenames: &[String],
     additional_context_fn: impl Fn(Context) -> Context,
 ) -> Result<Option<Vec<String>>> {
     let mut pages = all_pages.into_iter().filter(|p| p.meta.date.is_some()).collect::<Vec<_>>();
@@ -41,8 +43,16 @@ pub fn render_feeds(
         return Ok(None);
     }
 
+    let sort_date = |page: &Page| {
+        let date = page.meta.datetime.unwrap();
+        if site.config.feed_sort_by_updated {
+            page.meta.updated_datetime.unwrap_or(date)
+        } else {
+            date
+        }
+    };
     pages.par_sort_unstable_by(|a, b| {
-        let ord = b.meta.datetime.unwrap().cmp(&a.meta.datetime.unwrap());
+        let ord = sort_date(b).cmp(&sort_date(a));
         if ord == Ordering::Equal {
             a.permalink.cmp(&b.permalink)
         } else {
@@ -66,15 +76,26 @@ pub fn render_feeds(
     let p = pages
         .iter()
         .take(num_entries)
-        .map(|x| x.serialize_without_siblings(&library))
-        .collect::<Vec<_>>();
+        .map(|x| {
+            let mut page = to_value(x.serialize_without_siblings(&library))?;
+            // Feed readers don't know where the content comes from, so its URLs need to be absolute
+            for field in ["content", "summary"] {
+                if let Some(Value::String(html)) = page.get_mut(field) {
+                    *html = make_html_urls_absolute(html, &site.config.base_url, &x.permalink);
+                }
+            }
+            Ok(page)
+        })
+        .collect::<Result<Vec<_>>>()?;
 
     context.insert("pages", &p);
     context.insert("config", &site.config.serialize(lang));
+    site.config.build_info.insert_into(&mut context);
     context.insert("lang", lang);
+    context.insert("include_content", &site.config.feed_include_content);
 
     let mut feeds = Vec::new();
-    for feed_filename in &site.config.languages[lang].feed_filenames {
+    for feed_filename in feed_filenames {
         let mut context = context.clone();
 
         let feed_url = if let Some(base) = base_path {
@@ -83,6 +104,8 @@ pub fn render_feeds(
         } else {
             site.config.make_permalink(feed_filename)
         };
+        // Only the filenames from the config are known by `make_permalink` to be files
+        let feed_url = feed_url.trim_end_matches('/');
 
         context.insert("feed_url", &feed_url);
         context = additional_context_fn(context);
@@ -1,6 +1,7 @@
 pub mod feeds;
 pub mod link_checking;
 mod minify;
+mod relative_urls;
 pub mod sass;
 pub mod sitemap;
 pub mod tpls;
@@ -8,23 +9,27 @@ pub mod tpls;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 
 use libs::once_cell::sync::Lazy;
 use libs::rayon::prelude::*;
 use libs::tera::{Context, Tera};
+use libs::time::OffsetDateTime;
 use libs::walkdir::{DirEntry, WalkDir};
 
-use config::{get_config, Config, IndexFormat};
+use config::{get_config, BuildInfo, Config, IndexFormat};
 use content::{Library, Page, Paginator, Section, Taxonomy};
-use errors::{anyhow, bail, Result};
+use errors::{anyhow, bail, Context as ErrorContext, Result};
 use libs::relative_path::RelativePathBuf;
 use std::time::Instant;
-use templates::{load_tera, render_redirect_template};
+use templates::{apply_template_variant, load_tera, render_redirect_template};
 use utils::fs::{
     clean_site_output_folder, copy_directory, copy_file_if_needed, create_directory, create_file,
 };
+use utils::globs::NestedGitignore;
 use utils::net::{get_available_port, is_external_link};
+use utils::source_date;
 use utils::templates::{render_template, ShortcodeDefinition};
 use utils::types::InsertAnchor;
 
@@ -40,6 +45,18 @@ pub enum BuildMode {
     Memory,
 }
 
+/// Which links are checked when loading the site
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum CheckScope {
+    /// The internal links and, in check mode, the external ones
+    #[default]
+    All,
+    /// Only the internal links, never making any HTTP request
+    InternalOnly,
+    /// Only the external links in check mode, without checking the anchors of internal links
+    ExternalOnly,
+}
+
 #[derive(Debug)]
 pub struct Site {
     /// The base path of the zola site
@@ -64,7 +81,14 @@ pub struct Site {
     /// Whether to load draft pages
     include_drafts: bool,
     build_mode: BuildMode,
+    check_scope: CheckScope,
     shortcode_definitions: HashMap<String, ShortcodeDefinition>,
+    /// The variant of the templates used, see `set_template_variant`
+    template_variant: Option<String>,
+    /// How many HTML files and bytes were written by the current build or incremental
+    /// render, for `max_pages` and `max_output_bytes`
+    written_pages: AtomicUsize,
+    written_bytes: AtomicU64,
 }
 
 impl Site {
@@ -80,6 +104,11 @@ impl Site {
             config.merge_with_theme(path.join("themes").join(&theme).join("theme.toml"), &theme)?;
         }
 
+        if config.respect_gitignore {
+            config.gitignore = Some(NestedGitignore::new(path)?);
+        }
+        config.build_info = BuildInfo::new(source_date::source_date_epoch()?)?;
+
         let tera = load_tera(path, &config)?;
         let shortcode_definitions = utils::templates::get_shortcodes(&tera);
 
@@ -107,7 +136,11 @@ impl Site {
             // We will allocate it properly later on
             library: Arc::new(RwLock::new(Library::default())),
             build_mode: BuildMode::Disk,
+            check_scope: CheckScope::All,
             shortcode_definitions,
+            template_variant: None,
+            written_pages: AtomicUsize::new(0),
+            written_bytes: AtomicU64::new(0),
         };
 
         Ok(site)
@@ -126,6 +159,31 @@ impl Site {
         self.include_drafts = true;
     }
 
+    /// Set which links are checked.
+    /// Needs to be called before loading it
+    pub fn set_check_scope(&mut self, scope: CheckScope) {
+        self.check_scope = scope;
+    }
+
+    /// Finds the languages of the code blocks of all the pages and sections that can't be
+    /// highlighted, as (path of the file, language) sorted by path
+    pub fn find_unknown_code_languages(&self) -> Vec<(PathBuf, String)> {
+        let library = self.library.read().unwrap();
+        let mut unknown: Vec<_> = library
+            .pages
+            .values()
+            .map(|p| (&p.file.path, &p.raw_content))
+            .chain(library.sections.values().map(|s| (&s.file.path, &s.raw_content)))
+            .flat_map(|(path, content)| {
+                markdown::find_unknown_code_languages(content, &self.config)
+                    .into_iter()
+                    .map(move |lang| (path.clone(), lang))
+            })
+            .collect();
+        unknown.sort();
+        unknown
+    }
+
     /// The index sections are ALWAYS at those paths
     /// There are one index section for the default language + 1 per language
     fn index_section_paths(&self) -> Vec<(PathBuf, Option<&str>)> {
@@ -148,9 +206,21 @@ impl Site {
         self.live_reload = Some(live_reload_port);
     }
 
+    /// Renders the site with the templates of `templates/variants/{variant}/` instead of the
+    /// ones with the same names. Needs to be called before loading it
+    pub fn set_template_variant(&mut self, variant: &str) -> Result<()> {
+        apply_template_variant(&mut self.tera, variant)?;
+        self.shortcode_definitions = utils::templates::get_shortcodes(&self.tera);
+        self.template_variant = Some(variant.to_string());
+        Ok(())
+    }
+
     /// Reloads the templates and rebuild the site without re-markdown the Markdown.
     pub fn reload_templates(&mut self) -> Result<()> {
         self.tera.full_reload()?;
+        if let Some(variant) = &self.template_variant {
+            apply_template_variant(&mut self.tera, variant)?;
+        }
         // TODO: be smarter than that, no need to recompile sass for example
         self.build()
     }
@@ -161,6 +231,28 @@ impl Site {
         imageproc.set_base_url(&self.config);
     }
 
+    /// Sets the `zola_version` available in the templates
+    pub fn set_zola_version(&mut self, version: &str) {
+        self.config.build_info.zola_version = version.to_string();
+    }
+
+    /// Overrides the `SOURCE_DATE_EPOCH` read from the environment in `Site::new`.
+    /// Needs to be called before `load` to be used by the pages and the `now` function.
+    pub fn set_source_date_epoch(
+        &mut self,
+        source_date_epoch: Option<OffsetDateTime>,
+    ) -> Result<()> {
+        let zola_version = std::mem::take(&mut self.config.build_info.zola_version);
+        self.config.build_info = BuildInfo::new(source_date_epoch)?;
+        self.config.build_info.zola_version = zola_version;
+        Ok(())
+    }
+
+    /// Makes the links to the site in the generated HTML relative, see `Config::relative_urls`
+    pub fn enable_relative_urls(&mut self) {
+        self.config.relative_urls = true;
+    }
+
     pub fn set_output_path<P: AsRef<Path>>(&mut self, path: P) {
         self.output_path = path.as_ref().to_path_buf();
     }
@@ -212,6 +304,9 @@ impl Site {
 
                 None => (),
             }
+            if self.config.is_gitignored(path) {
+                continue;
+            }
 
             // we process a section when we encounter the dir
             // so we can process it before any of the pages
@@ -268,12 +363,18 @@ impl Site {
                 pages.push(page);
             }
         }
+        if self.config.shared_content {
+            self.add_shared_content(&mut pages)?;
+        }
         self.create_default_index_sections()?;
 
-        for page in pages {
+        for mut page in pages {
             // should we skip drafts?
             if page.meta.draft && !self.include_drafts {
-                continue;
+                match self.config.drafts_secret {
+                    Some(ref secret) => page.make_unlisted(secret, &self.config),
+                    None => continue,
+                }
             }
 
             // We are only checking it on load and not in add_page since we have access to
@@ -323,7 +424,11 @@ impl Site {
         tpls::register_tera_global_fns(self);
 
         // Needs to be done after rendering markdown as we only get the anchors at that point
-        let internal_link_messages = link_checking::check_internal_links_with_anchors(self);
+        let internal_link_messages = if self.check_scope == CheckScope::ExternalOnly {
+            Vec::new()
+        } else {
+            link_checking::check_internal_links_with_anchors(self)
+        };
 
         // log any broken internal links and error out if needed
         if !internal_link_messages.is_empty() {
@@ -343,8 +448,24 @@ impl Site {
             }
         }
 
+        if self.config.link_checker.report_unlinked_pages {
+            let unlinked_pages = link_checking::find_unlinked_pages(self);
+            if !unlinked_pages.is_empty() {
+                let messages: Vec<String> = unlinked_pages
+                    .iter()
+                    .enumerate()
+                    .map(|(i, path)| format!("  {}. {}", i + 1, path))
+                    .collect();
+                console::warn(&format!(
+                    "Found {} page(s) without any internal link or listing pointing to them\n{}",
+                    messages.len(),
+                    messages.join("\n")
+                ));
+            }
+        }
+
         // check external links, log the results, and error out if needed
-        if self.config.is_in_check_mode() {
+        if self.config.is_in_check_mode() && self.check_scope != CheckScope::InternalOnly {
             let external_link_messages = link_checking::check_external_links(self);
             if !external_link_messages.is_empty() {
                 let messages: Vec<String> = external_link_messages
@@ -367,9 +488,44 @@ impl Site {
         Ok(())
     }
 
+    /// Adds a copy of the sections and pages of the default language in each other language of the
+    /// site they don't have a translation in, for `shared_content`
+    fn add_shared_content(&mut self, pages: &mut Vec<Page>) -> Result<()> {
+        let default_language = &self.config.default_language;
+        let languages = self.config.other_languages_codes();
+
+        let mut sections = Vec::new();
+        for section in self.library.read().unwrap().sections.values() {
+            if &section.lang != default_language || !section.file.path.exists() {
+                continue;
+            }
+            for lang in &languages {
+                if !section.file.translation_path(lang).exists() {
+                    sections.push(section.translated_copy(lang, &self.config, &self.base_path)?);
+                }
+            }
+        }
+
+        let mut translations = Vec::new();
+        for page in pages.iter().filter(|p| &p.lang == default_language) {
+            for lang in &languages {
+                if !page.file.translation_path(lang).exists() {
+                    translations.push(page.translated_copy(lang, &self.config, &self.base_path)?);
+                }
+            }
+        }
+        pages.extend(translations);
+
+        for section in sections {
+            self.add_section(section, false)?;
+        }
+        Ok(())
+    }
+
     /// Insert a default index section for each language if necessary so we don't need to create
     /// a _index.md to render the index page at the root of the site
     pub fn create_default_index_sections(&mut self) -> Result<()> {
+        let mut permalinks = Vec::new();
         for (index_path, lang) in self.index_section_paths() {
             if let Some(index_section) = self.library.read().unwrap().sections.get(&index_path) {
                 if self.config.build_search_index && !index_section.meta.in_search_index {
@@ -407,9 +563,13 @@ impl Site {
                     &self.config.default_language,
                     &self.config.other_languages_codes(),
                 )?;
+                permalinks
+                    .push((index_section.file.relative.clone(), index_section.permalink.clone()));
                 library.insert_section(index_section);
             }
         }
+        // So `@/_index.md` links resolve even without the file
+        self.permalinks.extend(permalinks);
 
         Ok(())
     }
@@ -466,6 +626,12 @@ impl Site {
     /// Add a page to the site
     /// The `render` parameter is used in the serve command with --fast, when rebuilding a page.
     pub fn add_page(&mut self, mut page: Page, render_md: bool) -> Result<()> {
+        // The `not_found_page` is only rendered as the 404.html, not at its own path
+        if self.not_found_page_path().is_some_and(|path| path == page.file.path) {
+            page.meta.render = false;
+            page.unlisted = true;
+        }
+
         for taxa_name in page.meta.taxonomies.keys() {
             if !self.config.has_taxonomy(taxa_name, &page.lang) {
                 bail!(
@@ -476,7 +642,19 @@ impl Site {
             }
         }
 
-        self.permalinks.insert(page.file.relative.clone(), page.permalink.clone());
+        if page.meta.og_image {
+            let title = page.meta.title.as_deref().unwrap_or(&page.slug);
+            let mut imageproc = self.imageproc.lock().expect("Couldn't lock imageproc (add_page)");
+            let url = imageproc.enqueue_og_image(title).with_context(|| {
+                format!("Failed to generate og_image of {}", page.file.relative)
+            })?;
+            page.og_image = Some(url);
+        }
+
+        // The unlisted drafts can't be linked to, that would leak their secret path
+        if !page.unlisted {
+            self.permalinks.insert(page.file.relative.clone(), page.permalink.clone());
+        }
         if render_md {
             let insert_anchor =
                 self.find_parent_section_insert_anchor(&page.file.parent, &page.lang);
@@ -505,6 +683,7 @@ impl Site {
         self.populate_taxonomies()?;
         let library = self.library.read().unwrap();
         let page = library.pages.get(path).unwrap();
+        self.reset_output_counters();
         self.render_page(page)
     }
 
@@ -535,6 +714,7 @@ impl Site {
         self.populate_sections();
         let library = self.library.read().unwrap();
         let section = library.sections.get(path).unwrap();
+        self.reset_output_counters();
         self.render_section(section, true)
     }
 
@@ -593,6 +773,7 @@ impl Site {
                 &self.output_path,
                 false,
                 None,
+                self.config.gitignore.as_ref(),
             )?;
         }
         // We're fine with missing static folders
@@ -603,6 +784,7 @@ impl Site {
                     &self.output_path,
                     self.config.hard_link_static,
                     Some(gs),
+                    self.config.gitignore.as_ref(),
                 )?;
             } else {
                 copy_directory(
@@ -610,6 +792,7 @@ impl Site {
                     &self.output_path,
                     self.config.hard_link_static,
                     None,
+                    self.config.gitignore.as_ref(),
                 )?;
             }
         }
@@ -635,6 +818,36 @@ impl Site {
         clean_site_output_folder(&self.output_path, self.config.preserve_dotfiles_in_output)
     }
 
+    /// Resets the counters of `count_output`, at the start of every render pass so that
+    /// the incremental renders of `zola serve` don't add up
+    fn reset_output_counters(&self) {
+        self.written_pages.store(0, Ordering::Relaxed);
+        self.written_bytes.store(0, Ordering::Relaxed);
+    }
+
+    /// Counts a file about to be written, erroring if it goes over the `max_pages` or
+    /// `max_output_bytes` of the config
+    fn count_output(&self, filename: &str, content: &str) -> Result<()> {
+        if filename.ends_with(".html") {
+            let pages = self.written_pages.fetch_add(1, Ordering::Relaxed) + 1;
+            if let Some(max) = self.config.max_pages.filter(|max| pages > *max) {
+                bail!(
+                    "The build wrote more than {} pages, which is the `max_pages` of the config",
+                    max
+                );
+            }
+        }
+        let bytes = self.written_bytes.fetch_add(content.len() as u64, Ordering::Relaxed)
+            + content.len() as u64;
+        if let Some(max) = self.config.max_output_bytes.filter(|max| bytes > *max) {
+            bail!(
+                "The build wrote more than {} bytes, which is the `max_output_bytes` of the config",
+                max
+            );
+        }
+        Ok(())
+    }
+
     /// Handles whether to write to disk or to memory
     pub fn write_content(
         &self,
@@ -650,6 +863,13 @@ impl Site {
             site_path.push(component);
         }
 
+        let content = if filename.ends_with("html") && self.config.relative_urls {
+            let depth = components.iter().filter(|c| !c.is_empty()).count();
+            relative_urls::rewrite(&content, &self.config.base_url, depth)
+        } else {
+            content
+        };
+
         let final_content = if !filename.ends_with("html") || !self.config.minify_html {
             content
         } else {
@@ -659,6 +879,8 @@ impl Site {
             }
         };
 
+        self.count_output(filename, &final_content)?;
+
         match self.build_mode {
             BuildMode::Disk => {
                 let end_path = current_path.join(filename);
@@ -696,10 +918,32 @@ impl Site {
             return Ok(());
         }
 
-        let output = page.render_html(&self.tera, &self.config, &self.library.read().unwrap())?;
+        let library = self.library.read().unwrap();
+        let output = page.render_html(&self.tera, &self.config, &library)?;
         let content = self.inject_livereload(output);
-        let components: Vec<&str> = page.path.split('/').collect();
-        let current_path = self.write_content(&components, "index.html", content)?;
+        let current_path = match page.path.rsplit_once('/') {
+            // Ugly URLs: `/posts/hello.html` and its assets go in `/posts/hello/`
+            Some((parent, filename)) if filename.ends_with(".html") => {
+                let components: Vec<&str> = parent.split('/').collect();
+                let parent_path = self.write_content(&components, filename, content)?;
+                let stem = filename.trim_end_matches(".html");
+                for format in &page.output_formats {
+                    let output =
+                        page.render_output_format(format, &self.tera, &self.config, &library)?;
+                    self.write_content(&components, &format!("{}.{}", stem, format), output)?;
+                }
+                parent_path.join(stem)
+            }
+            _ => {
+                let components: Vec<&str> = page.path.split('/').collect();
+                for format in &page.output_formats {
+                    let output =
+                        page.render_output_format(format, &self.tera, &self.config, &library)?;
+                    self.write_content(&components, &format!("index.{}", format), output)?;
+                }
+                self.write_content(&components, "index.html", content)?
+            }
+        };
 
         // Copy any asset we found previously into the same directory as the index.html
         self.copy_assets(page.file.path.parent().unwrap(), &page.assets, &current_path)?;
@@ -710,6 +954,7 @@ impl Site {
     /// Deletes the `public` directory (only for `zola build`) and builds the site
     pub fn build(&self) -> Result<()> {
         let mut start = Instant::now();
+        self.reset_output_counters();
         // Do not clean on `zola serve` otherwise we end up copying assets all the time
         if self.build_mode == BuildMode::Disk {
             self.clean()?;
@@ -751,11 +996,15 @@ impl Site {
         if self.config.generate_feeds {
             let is_multilingual = self.config.is_multilingual();
             let pages: Vec<_> = if is_multilingual {
-                library.pages.values().filter(|p| p.lang == self.config.default_language).collect()
+                library
+                    .pages
+                    .values()
+                    .filter(|p| p.lang == self.config.default_language && !p.unlisted)
+                    .collect()
             } else {
-                library.pages.values().collect()
+                library.pages.values().filter(|p| !p.unlisted).collect()
             };
-            self.render_feeds(pages, None, &self.config.default_language, |c| c)?;
+            self.render_feeds(pages, None, &self.config.default_language, None, |c| c)?;
             start = log_time(start, "Generated feed in default language");
         }
 
@@ -763,8 +1012,9 @@ impl Site {
             if !language.generate_feeds {
                 continue;
             }
-            let pages: Vec<_> = library.pages.values().filter(|p| &p.lang == code).collect();
-            self.render_feeds(pages, Some(&PathBuf::from(code)), code, |c| c)?;
+            let pages: Vec<_> =
+                library.pages.values().filter(|p| &p.lang == code && !p.unlisted).collect();
+            self.render_feeds(pages, Some(&PathBuf::from(code)), code, None, |c| c)?;
             start = log_time(start, "Generated feed in other language");
         }
         self.render_themes_css()?;
@@ -795,11 +1045,20 @@ impl Site {
             create_directory(&self.static_path)?;
         }
 
+        // Several themes can share a file when they are scoped with a selector
+        let mut files: Vec<(&str, String)> = Vec::new();
         for t in themes {
-            let p = self.static_path.join(&t.filename);
+            let css = self.config.markdown.export_theme_css(&t.theme, t.selector.as_deref())?;
+            match files.iter_mut().find(|(filename, _)| *filename == t.filename) {
+                Some((_, content)) => content.push_str(&css),
+                None => files.push((&t.filename, css)),
+            }
+        }
+
+        for (filename, content) in files {
+            let p = self.static_path.join(filename);
             if !p.exists() {
-                let content = &self.config.markdown.export_theme_css(&t.theme)?;
-                create_file(&p, content)?;
+                create_file(&p, &content)?;
             }
         }
 
@@ -888,12 +1147,27 @@ impl Site {
         Ok(())
     }
 
-    /// Renders 404.html
+    /// The file of the `not_found_page` of the config, if it is set
+    fn not_found_page_path(&self) -> Option<PathBuf> {
+        let path = self.config.not_found_page.as_ref()?;
+        Some(self.content_path.join(path.trim_start_matches("@/")))
+    }
+
+    /// Renders 404.html, with the `not_found_page` of the config if it is set
     pub fn render_404(&self) -> Result<()> {
-        let mut context = Context::new();
-        context.insert("config", &self.config.serialize(&self.config.default_language));
-        context.insert("lang", &self.config.default_language);
-        let output = render_template("404.html", &self.tera, context, &self.config.theme)?;
+        let output = if let Some(ref path) = self.config.not_found_page {
+            let library = self.library.read().unwrap();
+            match library.pages.get(&self.not_found_page_path().unwrap()) {
+                Some(page) => page.render_html(&self.tera, &self.config, &library)?,
+                None => bail!("The `not_found_page` `{}` set in the config was not found", path),
+            }
+        } else {
+            let mut context = Context::new();
+            context.insert("config", &self.config.serialize(&self.config.default_language));
+            self.config.build_info.insert_into(&mut context);
+            context.insert("lang", &self.config.default_language);
+            render_template("404.html", &self.tera, context, &self.config.theme)?
+        };
         let content = self.inject_livereload(output);
         self.write_content(&[], "404.html", content)?;
         Ok(())
@@ -903,6 +1177,7 @@ impl Site {
     pub fn render_robots(&self) -> Result<()> {
         let mut context = Context::new();
         context.insert("config", &self.config.serialize(&self.config.default_language));
+        self.config.build_info.insert_into(&mut context);
         let content = render_template("robots.txt", &self.tera, context, &self.config.theme)?;
         self.write_content(&[], "robots.txt", content)?;
         Ok(())
@@ -973,6 +1248,7 @@ impl Site {
                         item.pages.iter().map(|p| library.pages.get(p).unwrap()).collect(),
                         Some(&tax_path),
                         &taxonomy.lang,
+                        None,
                         |mut context: Context| {
                             context.insert("taxonomy", &taxonomy.kind);
                             context.insert(
@@ -1037,22 +1313,29 @@ impl Site {
     /// Renders feeds for the given path and at the given path
     /// If both arguments are `None`, it will render only the feeds for the whole
     /// site at the root folder.
+    /// The feeds are named after `feed_filenames`, or the ones of the language if it is `None`.
     pub fn render_feeds(
         &self,
         all_pages: Vec<&Page>,
         base_path: Option<&PathBuf>,
         lang: &str,
+        feed_filenames: Option<&[String]>,
         additional_context_fn: impl Fn(Context) -> Context,
     ) -> Result<()> {
-        let feeds =
-            match feeds::render_feeds(self, all_pages, lang, base_path, additional_context_fn)? {
-                Some(v) => v,
-                None => return Ok(()),
-            };
+        let feed_filenames = feed_filenames.unwrap_or(&self.config.languages[lang].feed_filenames);
+        let feeds = match feeds::render_feeds(
+            self,
+            all_pages,
+            lang,
+            base_path,
+            feed_filenames,
+            additional_context_fn,
+        )? {
+            Some(v) => v,
+            None => return Ok(()),
+        };
 
-        for (feed, feed_filename) in
-            feeds.into_iter().zip(self.config.languages[lang].feed_filenames.iter())
-        {
+        for (feed, feed_filename) in feeds.into_iter().zip(feed_filenames.iter()) {
             if let Some(base) = base_path {
                 let mut components = Vec::new();
                 for component in base.components() {
@@ -1093,8 +1376,12 @@ impl Site {
                 pages,
                 Some(&PathBuf::from(&section.path[1..])),
                 &section.lang,
+                Some(&section.feed_filenames),
                 |mut context: Context| {
                     context.insert("section", &section.serialize(library));
+                    if let Some(include_content) = section.meta.feed_include_content {
+                        context.insert("include_content", &include_content);
+                    }
                     context
                 },
             )?;
@@ -1162,6 +1449,10 @@ impl Site {
         for page in library.get_all_orphan_pages() {
             self.render_page(page)?;
         }
+        // The unlisted drafts are not in the pages of their sections so they are rendered here
+        for page in library.pages.values().filter(|p| p.unlisted && !p.ancestors.is_empty()) {
+            self.render_page(page)?;
+        }
 
         Ok(())
     }
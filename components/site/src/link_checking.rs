@@ -1,4 +1,5 @@
 use core::time;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use std::{cmp, collections::HashMap, collections::HashSet, iter::FromIterator, thread};
 
@@ -122,6 +123,62 @@ fn get_link_domain(link: &str) -> Result<String> {
     };
 }
 
+/// Finds the pages that no other page or section links to with an internal link and that are
+/// not listed in the pages of a rendered section or a rendered taxonomy term either, returning
+/// their paths relative to the `content` directory.
+/// Sections are not reported and neither are pages with `render = false` since they have no URL.
+/// Links from a section content (eg a hand-written list of pages in an `_index.md`) do count.
+pub fn find_unlinked_pages(site: &Site) -> Vec<String> {
+    let library = site.library.read().expect("Get lock for find_unlinked_pages");
+
+    let mut listed: HashSet<&PathBuf> =
+        library.sections.values().filter(|s| s.meta.render).flat_map(|s| &s.pages).collect();
+    listed.extend(
+        site.taxonomies
+            .iter()
+            .filter(|t| t.kind.render)
+            .flat_map(|t| &t.items)
+            .flat_map(|term| &term.pages),
+    );
+
+    let mut unlinked: Vec<String> = library
+        .pages
+        .values()
+        // The unlisted pages are not meant to be linked to
+        .filter(|p| p.meta.render && !p.unlisted && !listed.contains(&p.file.path))
+        .filter(|p| {
+            library
+                .backlinks
+                .get(&p.file.relative)
+                .is_none_or(|sources| sources.iter().all(|source| source == &p.file.path))
+        })
+        .map(|p| p.file.relative.clone())
+        .collect();
+    unlinked.sort();
+    unlinked
+}
+
+/// Lists the external links of the content of the site, with the permalinks of the pages and
+/// sections linking to them, both sorted for the report to be the same on every run
+pub fn external_links_report(site: &Site) -> BTreeMap<String, Vec<String>> {
+    let library = site.library.read().expect("Get lock for external_links_report");
+    let mut report: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let sources = library
+        .pages
+        .values()
+        .map(|p| (&p.permalink, &p.external_links))
+        .chain(library.sections.values().map(|s| (&s.permalink, &s.external_links)));
+    for (permalink, links) in sources {
+        for link in links {
+            report.entry(link).or_default().insert(permalink);
+        }
+    }
+    report
+        .into_iter()
+        .map(|(link, sources)| (link.to_string(), sources.into_iter().map(String::from).collect()))
+        .collect()
+}
+
 /// Checks all external links and returns all the errors that were encountered.
 /// Empty vec == all good
 pub fn check_external_links(site: &Site) -> Vec<String> {
@@ -292,5 +349,29 @@ pub fn check_external_links(site: &Site) -> Vec<String> {
         Err(pool_err) => messages.push(pool_err.to_string()),
     }
 
+    if site.config.link_checker.warn_on_redirects {
+        let mut redirects: Vec<_> = checked_links
+            .iter()
+            .filter_map(|link_def| {
+                let target = link_checker::redirect_target(&link_def.external_link)?;
+                Some(format!(
+                    "  - {} to {} redirects to {}",
+                    link_def.file_path.to_string_lossy(),
+                    link_def.external_link,
+                    target
+                ))
+            })
+            .collect();
+        redirects.sort();
+        redirects.dedup();
+        if !redirects.is_empty() {
+            console::warn(&format!(
+                "Found {} external link(s) redirecting to another URL:\n{}",
+                redirects.len(),
+                redirects.join("\n")
+            ));
+        }
+    }
+
     messages
 }
@@ -0,0 +1,55 @@
+use libs::once_cell::sync::Lazy;
+use libs::regex::{Captures, Regex};
+
+static URL_ATTRIBUTE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?P<attr>\s(?:href|src)=)(?P<quote>["'])(?P<url>[^"']*)["']"#).unwrap()
+});
+
+/// Rewrites the `href` and `src` attributes pointing to the site into URLs relative to a page
+/// `depth` folders deep in the output directory, so the site can be browsed from `file://`.
+/// Links to a directory get its `index.html` appended since there is no server to resolve them.
+pub fn rewrite(html: &str, base_url: &str, depth: usize) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let prefix = "../".repeat(depth);
+
+    URL_ATTRIBUTE_RE
+        .replace_all(html, |caps: &Captures| {
+            let url = match caps["url"].strip_prefix(base_url) {
+                Some(url) if url.is_empty() || url.starts_with(['/', '#', '?']) => url,
+                // Another site, or only sharing a prefix with the base URL
+                _ => return caps[0].to_string(),
+            };
+            let (path, rest) = url.split_at(url.find(['#', '?']).unwrap_or(url.len()));
+            let path = path.trim_start_matches('/');
+            let index = if path.is_empty() || path.ends_with('/') { "index.html" } else { "" };
+            format!(
+                "{}{}{}{}{}{}{}",
+                &caps["attr"], &caps["quote"], prefix, path, index, rest, &caps["quote"],
+            )
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rewrite;
+
+    #[test]
+    fn can_rewrite_urls_relative_to_the_page_depth() {
+        let html = r#"<a href="https://example.com/">Home</a><a href='https://example.com/blog/post/#title'>Post</a><img src="https://example.com/images/logo.png"><a href="https://other.com/">Other</a>"#;
+        assert_eq!(
+            rewrite(html, "https://example.com", 2),
+            r#"<a href="../../index.html">Home</a><a href='../../blog/post/index.html#title'>Post</a><img src="../../images/logo.png"><a href="https://other.com/">Other</a>"#
+        );
+        assert_eq!(
+            rewrite(html, "https://example.com/", 0),
+            r#"<a href="index.html">Home</a><a href='blog/post/index.html#title'>Post</a><img src="images/logo.png"><a href="https://other.com/">Other</a>"#
+        );
+    }
+
+    #[test]
+    fn does_not_rewrite_urls_only_sharing_a_prefix_with_the_base_url() {
+        let html = r#"<a href="https://example.com.evil/">Other</a>"#;
+        assert_eq!(rewrite(html, "https://example.com", 1), html);
+    }
+}
@@ -64,7 +64,7 @@ pub fn find_entries<'a>(
     let mut entries = HashSet::new();
 
     for p in library.pages.values() {
-        if !p.meta.render {
+        if !p.meta.render || p.unlisted {
             continue;
         }
         let mut entry = SitemapEntry::new(
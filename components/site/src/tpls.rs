@@ -5,10 +5,18 @@ use templates::{filters, global_fns};
 
 /// Adds global fns that are to be available to shortcodes while rendering markdown
 pub fn register_early_global_fns(site: &mut Site) -> TeraResult<()> {
+    site.tera.register_filter("slugify", filters::slugify);
     site.tera.register_filter(
         "num_format",
         filters::NumFormatFilter::new(&site.config.default_language),
     );
+    site.tera.register_filter("date", filters::DateFilter);
+    site.tera.register_filter("escape_xml", filters::EscapeXmlFilter);
+    site.tera.register_filter("cdata", filters::CdataFilter);
+    site.tera
+        .register_filter("absolute_url", filters::AbsoluteUrlFilter::new(&site.config.base_url));
+    site.tera
+        .register_filter("relative_url", filters::RelativeUrlFilter::new(&site.config.base_url));
 
     site.tera.register_function(
         "get_url",
@@ -36,6 +44,22 @@ pub fn register_early_global_fns(site: &mut Site) -> TeraResult<()> {
             site.output_path.clone(),
         ),
     );
+    site.tera.register_function(
+        "get_image_colors",
+        global_fns::GetImageColors::new(
+            site.base_path.clone(),
+            site.config.theme.clone(),
+            site.output_path.clone(),
+        ),
+    );
+    site.tera.register_function(
+        "get_image_lqip",
+        global_fns::GetImageLqip::new(
+            site.base_path.clone(),
+            site.config.theme.clone(),
+            site.output_path.clone(),
+        ),
+    );
     site.tera.register_function(
         "load_data",
         global_fns::LoadData::new(
@@ -45,6 +69,13 @@ pub fn register_early_global_fns(site: &mut Site) -> TeraResult<()> {
         ),
     );
     site.tera.register_function("trans", global_fns::Trans::new(site.config.clone()));
+    site.tera
+        .register_function("now", global_fns::Now::new(site.config.build_info.source_date_epoch));
+    site.tera.register_function("get_config", global_fns::GetConfig::new(&site.config));
+    site.tera.register_function(
+        "get_env",
+        global_fns::GetEnv::new(site.config.allowed_env_prefixes.clone()),
+    );
     site.tera.register_function(
         "get_taxonomy_url",
         global_fns::GetTaxonomyUrl::new(
@@ -95,6 +126,10 @@ pub fn register_tera_global_fns(site: &mut Site) {
             site.library.clone(),
         ),
     );
+    site.tera.register_function(
+        "get_related_pages",
+        global_fns::GetRelatedPages::new(site.base_path.clone(), site.library.clone()),
+    );
     site.tera.register_function(
         "get_taxonomy",
         global_fns::GetTaxonomy::new(
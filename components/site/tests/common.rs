@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::env;
 use std::path::{Path, PathBuf};
 
+use errors::Result;
 use path_slash::PathExt;
 use site::Site;
 use std::ffi::OsStr;
@@ -71,6 +72,63 @@ where
     (site, tmp_dir, public.clone())
 }
 
+/// A site of its own in a temporary directory, for the tests that need a few specific files
+/// rather than one of the test sites
+pub struct TempSite {
+    pub root: PathBuf,
+    /// Where the site is built
+    pub public: PathBuf,
+    // Deletes the directory at the end of the test
+    _tmp_dir: TempDir,
+}
+
+impl TempSite {
+    pub fn new() -> TempSite {
+        let tmp_dir = tempdir().expect("create temp dir");
+        let root = tmp_dir.path().to_path_buf();
+        TempSite { public: root.join("public"), root, _tmp_dir: tmp_dir }
+    }
+
+    /// Writes a file of the site, creating its parent directories
+    pub fn write(&self, path: &str, content: &str) {
+        let path = self.root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// Same as `load` but has a hook to setup some options before loading the site
+    pub fn load_with_setup<F: FnOnce(&mut Site)>(&self, setup_cb: F) -> Result<Site> {
+        let mut site = Site::new(&self.root, self.root.join("config.toml"))?;
+        setup_cb(&mut site);
+        site.load()?;
+        site.set_output_path(&self.public);
+        Ok(site)
+    }
+
+    pub fn load(&self) -> Result<Site> {
+        self.load_with_setup(|_| {})
+    }
+
+    /// Same as `build` but has a hook to setup some options before loading the site
+    pub fn build_with_setup<F: FnOnce(&mut Site)>(&self, setup_cb: F) -> Result<Site> {
+        let site = self.load_with_setup(setup_cb)?;
+        site.build()?;
+        Ok(site)
+    }
+
+    /// Loads and builds the site in `public`, re-reading all its files so it can be called
+    /// again after changing some of them
+    pub fn build(&self) -> Result<Site> {
+        self.build_with_setup(|_| {})
+    }
+}
+
+impl Default for TempSite {
+    fn default() -> TempSite {
+        TempSite::new()
+    }
+}
+
 /// Finds the unified path (eg. _index.fr.md -> _index.md) and
 /// potential language (if not default) associated with a path
 /// When the path is not a markdown file (.md), None is returned
@@ -4,12 +4,14 @@ use std::collections::HashMap;
 use std::env;
 use std::path::{Path, PathBuf};
 
-use common::{build_site, build_site_with_setup};
-use config::TaxonomyConfig;
+use common::{build_site, build_site_with_setup, TempSite};
+use config::{TaxonomyConfig, TaxonomyPagesSortBy, TaxonomySortBy, UrlStyle};
 use content::Page;
 use libs::ahash::AHashMap;
+use libs::time::OffsetDateTime;
 use site::sitemap;
-use site::Site;
+use site::{CheckScope, Site};
+use utils::site::resolve_internal_link;
 
 #[test]
 fn can_parse_site() {
@@ -582,6 +584,10 @@ fn can_build_site_with_pagination_for_taxonomy() {
             paginate_path: None,
             render: true,
             feed: true,
+            hierarchical: false,
+            sort_by: TaxonomySortBy::Name,
+            sort_pages_by: TaxonomyPagesSortBy::Date,
+            slugs: HashMap::new(),
         });
         site.load().unwrap();
         {
@@ -851,6 +857,17 @@ fn panics_on_invalid_external_domain() {
     site.load().expect("link check test_site");
 }
 
+#[test]
+fn internal_only_check_does_not_request_external_links() {
+    let (mut site, _tmp_dir, _public) = build_site("test_site");
+    // Without this skip prefix, checking the external links would fail on the invalid domain
+    site.config.link_checker.skip_prefixes.retain(|prefix| prefix != "http://invaliddomain");
+
+    site.config.enable_check_mode();
+    site.set_check_scope(CheckScope::InternalOnly);
+    site.load().expect("internal only link check of test_site");
+}
+
 #[test]
 fn can_find_site_and_page_authors() {
     let mut path = env::current_dir().unwrap().parent().unwrap().parent().unwrap().to_path_buf();
@@ -876,7 +893,1033 @@ fn can_find_site_and_page_authors() {
     assert_eq!(0, p2.meta.authors.len());
 }
 
+#[test]
+fn can_build_site_with_ugly_urls() {
+    let (site, _tmp_dir, public) = build_site_with_setup("test_site", |mut site| {
+        site.config.url_style = UrlStyle::Ugly;
+        (site, true)
+    });
+    let library = site.library.read().unwrap();
+    let posts_path = site.base_path.join("content").join("posts");
+
+    let python = &library.pages[&posts_path.join("python.md")];
+    assert_eq!(python.path, "/posts/python.html");
+    assert_eq!(python.permalink, "https://replace-this-with-your-url.com/posts/python.html");
+    assert!(file_exists!(public, "posts/python.html"));
+    assert!(!file_exists!(public, "posts/python/index.html"));
+
+    // Colocated assets end up in a folder named after the page
+    let with_assets = &library.pages[&posts_path.join("with-assets").join("index.md")];
+    assert_eq!(with_assets.path, "/posts/with-assets.html");
+    assert!(file_exists!(public, "posts/with-assets.html"));
+    assert!(file_exists!(public, "posts/with-assets/with.js"));
+    assert!(file_contains!(
+        public,
+        "posts/with-assets.html",
+        "https://replace-this-with-your-url.com/posts/with-assets/with.js"
+    ));
+
+    // Sections are still directories
+    assert!(file_exists!(public, "posts/index.html"));
+    assert!(file_contains!(
+        public,
+        "sitemap.xml",
+        "<loc>https://replace-this-with-your-url.com/posts/python.html</loc>"
+    ));
+}
+
+#[test]
+fn can_build_site_with_pretty_urls() {
+    let (site, _tmp_dir, public) = build_site("test_site");
+    let library = site.library.read().unwrap();
+    let posts_path = site.base_path.join("content").join("posts");
+
+    let python = &library.pages[&posts_path.join("python.md")];
+    assert_eq!(python.path, "/posts/python/");
+    assert_eq!(python.permalink, "https://replace-this-with-your-url.com/posts/python/");
+    assert!(file_exists!(public, "posts/python/index.html"));
+    assert!(!file_exists!(public, "posts/python.html"));
+}
+
 // Follows test_site/themes/sample/templates/current_path.html
 fn current_path(path: &str) -> String {
     format!("[current_path]({})", path)
 }
+
+#[test]
+fn can_find_unlinked_pages() {
+    let tmp_site = TempSite::new();
+    tmp_site.write(
+        "config.toml",
+        "base_url = \"https://example.com\"\ntaxonomies = [{name = \"tags\"}]\n",
+    );
+    tmp_site.write("content/blog/_index.md", "+++\n+++\n");
+    // Only listed in the pages of its section
+    tmp_site.write("content/blog/listed.md", "+++\n+++\n[linked](@/hidden/linked.md)");
+    tmp_site.write("content/hidden/_index.md", "+++\nrender = false\n+++\n");
+    tmp_site.write("content/hidden/linked.md", "+++\n+++\n");
+    tmp_site.write("content/hidden/tagged.md", "+++\n[taxonomies]\ntags = [\"a\"]\n+++\n");
+    tmp_site.write("content/hidden/orphan.md", "+++\n+++\n[me](@/hidden/orphan.md)");
+    tmp_site.write("content/hidden/not-rendered.md", "+++\nrender = false\n+++\n");
+
+    let site = tmp_site.load().unwrap();
+
+    assert_eq!(site::link_checking::find_unlinked_pages(&site), vec!["hidden/orphan.md"]);
+}
+
+#[test]
+fn can_exclude_gitignored_files() {
+    let tmp_site = TempSite::new();
+    let root = &tmp_site.root;
+    tmp_site.write(".git/HEAD", "");
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\nrespect_gitignore = true\n");
+    tmp_site.write(
+        "templates/index.html",
+        "{% for page in section.pages %}{{ page.title }}{% endfor %}",
+    );
+    tmp_site.write("templates/section.html", "{{ section.title }}");
+    tmp_site.write("templates/page.html", "{{ page.title }}");
+    tmp_site.write(".gitignore", "*.log\n/content/notes.md\n");
+    tmp_site.write("content/_index.md", "+++\nsort_by = \"title\"\n+++\n");
+    tmp_site.write("content/notes.md", "+++\ntitle = \"Notes\"\n+++\n");
+    tmp_site.write("content/hello.md", "+++\ntitle = \"Hello\"\n+++\n");
+    tmp_site.write("content/blog/_index.md", "+++\ntitle = \"Blog\"\n+++\n");
+    tmp_site.write("content/blog/.gitignore", "wip.md\n");
+    tmp_site.write("content/blog/wip.md", "+++\ntitle = \"WIP\"\n+++\n");
+    tmp_site.write("content/blog/post/index.md", "+++\ntitle = \"Post\"\n+++\n");
+    tmp_site.write("content/blog/post/build.log", "");
+    tmp_site.write("static/robots.log", "");
+    tmp_site.write("static/robots.txt", "");
+
+    let site = tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_exists!(public, "hello/index.html"));
+    assert!(!file_exists!(public, "notes/index.html"));
+    assert!(file_exists!(public, "blog/post/index.html"));
+    assert!(!file_exists!(public, "blog/post/build.log"));
+    assert!(!file_exists!(public, "blog/wip/index.html"));
+    assert!(file_exists!(public, "robots.txt"));
+    assert!(!file_exists!(public, "robots.log"));
+    assert!(file_contains!(public, "index.html", "Hello"));
+    assert!(!file_contains!(public, "index.html", "Notes"));
+
+    let library = site.library.read().unwrap();
+    assert!(library.pages.get(&root.join("content").join("blog").join("wip.md")).is_none());
+}
+
+#[test]
+fn can_generate_og_image_for_opted_in_pages() {
+    let tmp_site = TempSite::new();
+    let root = &tmp_site.root;
+    let font = env::current_dir()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("imageproc/tests/test_fonts/DejaVuSansMono-Oblique.ttf");
+    tmp_site.write(
+        "config.toml",
+        &format!(
+            "base_url = \"https://example.com\"\n[og_image]\nfont = {:?}\n",
+            font.display().to_string()
+        ),
+    );
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/page.html", "{{ page.og_image | default(value='none') | safe }}");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write("content/shared.md", "+++\ntitle = \"Shared\"\nog_image = true\n+++\n");
+    tmp_site.write("content/plain.md", "+++\ntitle = \"Plain\"\n+++\n");
+
+    let site = tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    let library = site.library.read().unwrap();
+    let page = &library.pages[&root.join("content").join("shared.md")];
+    let url = page.og_image.clone().unwrap();
+    let filename = url.strip_prefix("https://example.com/processed_images/").unwrap();
+    assert!(file_exists!(public, &format!("processed_images/{}", filename)));
+    assert!(file_contains!(public, "shared/index.html", &url));
+    assert!(file_contains!(public, "plain/index.html", "none"));
+}
+
+#[test]
+fn can_highlight_with_classes_and_export_theme_css() {
+    let tmp_site = TempSite::new();
+    tmp_site.write(
+        "config.toml",
+        r#"base_url = "https://example.com"
+[markdown]
+highlight_code = true
+highlight_theme = "css"
+highlight_themes_css = [{ theme = "base16-ocean-dark", filename = "syntax.css" }]
+"#,
+    );
+    tmp_site.write("templates/index.html", "{{ section.content | safe }}");
+    tmp_site.write("content/_index.md", "+++\n+++\n```rust\nfn main() {}\n```\n");
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(public, "index.html", r#"<span class="z-storage"#));
+    assert!(!file_contains!(public, "index.html", "style="));
+    assert!(file_contains!(public, "syntax.css", ".z-code {"));
+}
+
+#[test]
+fn can_export_several_highlight_themes_css() {
+    let tmp_site = TempSite::new();
+    tmp_site.write(
+        "config.toml",
+        r#"base_url = "https://example.com"
+[markdown]
+highlight_code = true
+highlight_theme = "css"
+highlight_themes_css = [
+  { theme = "base16-ocean-dark", filename = "dark.css" },
+  { theme = "base16-ocean-light", filename = "light.css" },
+  { theme = "base16-ocean-dark", filename = "syntax.css", selector = "[data-theme=\"dark\"]" },
+  { theme = "base16-ocean-light", filename = "syntax.css", selector = "[data-theme=\"light\"]" },
+]
+"#,
+    );
+    tmp_site.write("templates/index.html", "{{ section.content | safe }}");
+    tmp_site.write("content/_index.md", "+++\n+++\n```rust\nfn main() {}\n```\n");
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    // Both themes style the same classes
+    assert!(file_contains!(public, "dark.css", ".z-code {\n color: #c0c5ce;"));
+    assert!(file_contains!(public, "light.css", ".z-code {\n color: #4f5b66;"));
+    assert!(file_contains!(
+        public,
+        "syntax.css",
+        "[data-theme=\"dark\"] .z-code {\n color: #c0c5ce;"
+    ));
+    assert!(file_contains!(
+        public,
+        "syntax.css",
+        "[data-theme=\"light\"] .z-code {\n color: #4f5b66;"
+    ));
+    assert!(file_contains!(
+        public,
+        "syntax.css",
+        "[data-theme=\"light\"] .z-comment, [data-theme=\"light\"] .z-punctuation.z-definition.z-comment {"
+    ));
+}
+
+#[test]
+fn can_find_unknown_code_languages() {
+    let tmp_site = TempSite::new();
+    let root = &tmp_site.root;
+    tmp_site.write(
+        "config.toml",
+        "base_url = \"https://example.com\"\n[markdown]\ndiagram_languages = [\"mermaid\"]\nlanguage_aliases = { rs-nightly = \"rust\" }\n",
+    );
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/page.html", "");
+    tmp_site.write("content/_index.md", "+++\n+++\n```pyhton\nprint(1)\n```\n");
+    tmp_site.write(
+        "content/hello.md",
+        "+++\n+++\n```rust\n```\n```sh\n```\n```rs-nightly\n```\n```mermaid\n```\n```\n```\n```rustt\n```\n```rustt\n```\n",
+    );
+
+    let site = tmp_site.load().unwrap();
+
+    assert_eq!(
+        site.find_unknown_code_languages(),
+        vec![
+            (root.join("content").join("_index.md"), "pyhton".to_string()),
+            (root.join("content").join("hello.md"), "rustt".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn can_override_the_base_url_everywhere() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\ngenerate_feeds = true\n");
+    tmp_site.write("templates/index.html", "{{ section.content | safe }}");
+    tmp_site.write(
+        "templates/page.html",
+        "{{ page.permalink | safe }} {{ get_url(path=\"@/other.md\") }} {{ page.content | safe }}",
+    );
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write("content/hello.md", "+++\ndate = 2024-01-01\n+++\n[Other](@/other.md)\n");
+    tmp_site.write("content/other.md", "+++\ndate = 2024-01-02\n+++\n");
+
+    let build = |base_url: Option<&str>| {
+        tmp_site
+            .build_with_setup(|site| {
+                if let Some(b) = base_url {
+                    site.set_base_url(b.to_string());
+                }
+            })
+            .unwrap();
+    };
+
+    build(None);
+    let public = &tmp_site.public;
+    let page = "https://example.com/hello/ https://example.com/other/ \
+                <p><a href=\"https://example.com/other/\">Other</a></p>\n";
+    assert!(file_contains!(public, "hello/index.html", page));
+    assert!(file_contains!(public, "sitemap.xml", "<loc>https://example.com/hello/</loc>"));
+    assert!(file_contains!(public, "atom.xml", "https://example.com/hello/"));
+
+    build(Some("https://preview.example.com"));
+    let page = page.replace("https://example.com", "https://preview.example.com");
+    assert!(file_contains!(public, "hello/index.html", &page));
+    assert!(file_contains!(public, "sitemap.xml", "<loc>https://preview.example.com/hello/</loc>"));
+    assert!(file_contains!(public, "atom.xml", "https://preview.example.com/hello/"));
+    assert!(!file_contains!(public, "atom.xml", "https://example.com"));
+}
+
+#[test]
+fn can_make_urls_relative_to_nested_pages() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\nrelative_urls = true\n");
+    tmp_site.write("static/style.css", "body {}");
+    tmp_site.write(
+        "templates/index.html",
+        "<link href=\"{{ get_url(path=\"style.css\") }}\"> {{ section.content | safe }}",
+    );
+    tmp_site.write(
+        "templates/page.html",
+        "<link href=\"{{ get_url(path=\"style.css\") }}\"> \
+         <a href=\"{{ config.base_url | safe }}\">Home</a> {{ page.content | safe }}",
+    );
+    tmp_site.write("content/_index.md", "+++\n+++\n[Deep](@/a/b/deep.md)\n");
+    tmp_site.write(
+        "content/a/b/deep.md",
+        "+++\n+++\n[Home](@/_index.md) [Anchor](@/a/b/deep.md#top)\n",
+    );
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(public, "index.html", "<link href=\"style.css\">"));
+    assert!(file_contains!(public, "index.html", "<a href=\"a/b/deep/index.html\">Deep</a>"));
+    let deep = "a/b/deep/index.html";
+    assert!(file_contains!(public, deep, "<link href=\"../../../style.css\">"));
+    assert!(file_contains!(public, deep, "<a href=\"../../../index.html\">Home</a>"));
+    assert!(file_contains!(
+        public,
+        deep,
+        "<a href=\"../../../a/b/deep/index.html#top\">Anchor</a>"
+    ));
+    // Feeds and sitemaps need absolute URLs
+    assert!(file_contains!(public, "sitemap.xml", "<loc>https://example.com/a/b/deep/</loc>"));
+}
+
+#[test]
+fn can_use_author_details_in_templates() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\n");
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write(
+        "templates/page.html",
+        "{{ page.authors | join(sep=\", \") }}\n\
+         {% for author in page.author_details %}\
+         [{{ author.name }}|{{ author.url | default(value=\"-\") | safe }}|{{ author.avatar | default(value=\"-\") | safe }}]\
+         {% endfor %}",
+    );
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write(
+        "content/post.md",
+        "+++\nauthors = [\"Alice\", { name = \"Bob\", url = \"https://bob.example.com\", avatar = \"/bob.png\" }]\n+++\n",
+    );
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(public, "post/index.html", "Alice, Bob\n"));
+    assert!(file_contains!(
+        public,
+        "post/index.html",
+        "[Alice|-|-][Bob|https://bob.example.com|/bob.png]"
+    ));
+}
+
+#[test]
+fn can_use_build_info_in_templates() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\n");
+    tmp_site.write("templates/index.html", "{{ zola_version }} {{ build_time }}");
+    tmp_site.write("templates/page.html", "{{ zola_version }} {{ build_time }}");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write("content/hello.md", "+++\n+++\n");
+
+    tmp_site
+        .build_with_setup(|site| {
+            site.set_zola_version("1.2.3");
+            let epoch = OffsetDateTime::from_unix_timestamp(1700000000).unwrap();
+            site.set_source_date_epoch(Some(epoch)).unwrap();
+        })
+        .unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(public, "index.html", "1.2.3 2023-11-14T22:13:20Z"));
+    assert!(file_contains!(public, "hello/index.html", "1.2.3 2023-11-14T22:13:20Z"));
+}
+
+#[test]
+fn builds_are_reproducible_with_source_date_epoch() {
+    let tmp_site = TempSite::new();
+    tmp_site.write(
+        "config.toml",
+        "base_url = \"https://example.com\"\ngenerate_feeds = true\nallow_missing_frontmatter = true\n",
+    );
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/page.html", "{{ now(utc=true) }} {{ page.date }}");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+
+    let build = || {
+        // Recreating the page gives it a new modification time, as a fresh checkout would
+        tmp_site.write("content/hello.md", "Hello");
+        tmp_site
+            .build_with_setup(|site| {
+                let epoch = OffsetDateTime::from_unix_timestamp(1700000000).unwrap();
+                site.set_source_date_epoch(Some(epoch)).unwrap();
+            })
+            .unwrap();
+        ["atom.xml", "sitemap.xml", "hello/index.html"]
+            .map(|f| std::fs::read_to_string(tmp_site.public.join(f)).unwrap())
+    };
+    let first = build();
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    let second = build();
+
+    assert_eq!(first, second);
+    assert!(first[0].contains("<updated>2023-11-14T22:13:20+00:00</updated>"));
+    assert!(first[1].contains("<lastmod>2023-11-14T22:13:20Z</lastmod>"));
+    assert_eq!(first[2], "2023-11-14T22:13:20Z 2023-11-14T22:13:20Z");
+}
+
+#[test]
+fn can_render_pages_to_additional_output_formats() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\n");
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/section.html", "");
+    tmp_site.write("templates/page.html", "<h1>{{ page.title }}</h1>");
+    tmp_site.write("templates/page.txt", "{{ page.title }} & {{ page.content | striptags }}");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write("content/docs/_index.md", "+++\npage_output_formats = [\"txt\"]\n+++\n");
+    tmp_site.write("content/docs/intro.md", "+++\ntitle = \"Intro\"\n+++\n*Hello*");
+    tmp_site.write("content/docs/nested/_index.md", "+++\n+++\n");
+    tmp_site.write("content/docs/nested/deep.md", "+++\ntitle = \"Deep\"\n+++\n");
+    tmp_site.write("content/blog/_index.md", "+++\n+++\n");
+    tmp_site.write("content/blog/post.md", "+++\ntitle = \"Post\"\n+++\n");
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(public, "docs/intro/index.html", "<h1>Intro</h1>"));
+    assert!(file_contains!(public, "docs/intro/index.txt", "Intro & Hello"));
+    // Inherited by the pages of subsections
+    assert!(file_contains!(public, "docs/nested/deep/index.txt", "Deep"));
+    assert!(file_exists!(public, "blog/post/index.html"));
+    assert!(!file_exists!(public, "blog/post/index.txt"));
+}
+
+#[test]
+fn errors_on_output_formats_without_templates() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\n");
+    tmp_site.write("templates/page.csv", "{{ page.title }}");
+    tmp_site.write("content/_index.md", "+++\npage_output_formats = [\"csv\"]\n+++\n");
+
+    let err = tmp_site.load().unwrap_err();
+    assert!(format!("{:?}", err).contains("template extensions other than `html`"));
+}
+
+#[test]
+fn can_include_markdown_files() {
+    let tmp_site = TempSite::new();
+    tmp_site.write(
+        "config.toml",
+        "base_url = \"https://example.com\"\nignored_content = [\"**/snippets/*\"]\n",
+    );
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/page.html", "{{ page.content | safe }}");
+    tmp_site.write("templates/shortcodes/notice.html", "<div class=\"notice\">{{ text }}</div>");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write(
+        "content/snippets/legal.md",
+        "+++\ntitle = \"Legal\"\n+++\n**Legal** {{ notice(text=\"Read\") }}\n\n{{ include(path=\"@/snippets/footer.md\") }}",
+    );
+    tmp_site.write("content/snippets/footer.md", "The footer");
+    tmp_site.write(
+        "content/post.md",
+        "+++\ntitle = \"Post\"\n+++\nBefore\n\n{{ include(path=\"@/snippets/legal.md\") }}",
+    );
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(public, "post/index.html", "<p>Before</p>"));
+    assert!(file_contains!(
+        public,
+        "post/index.html",
+        "<p><strong>Legal</strong> <div class=\"notice\">Read</div></p>"
+    ));
+    assert!(file_contains!(public, "post/index.html", "<p>The footer</p>"));
+    assert!(!file_contains!(public, "post/index.html", "title"));
+}
+
+#[test]
+fn errors_on_include_cycles() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\n");
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/page.html", "{{ page.content | safe }}");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write("content/a.md", "+++\n+++\n{{ include(path=\"@/b.md\") }}");
+    tmp_site.write("content/b.md", "+++\n+++\n{{ include(path=\"@/a.md\") }}");
+
+    let err = tmp_site.load().unwrap_err();
+    let message = format!("{:?}", err);
+    assert!(
+        message.contains("Include cycle detected: a.md -> b.md -> a.md")
+            || message.contains("Include cycle detected: b.md -> a.md -> b.md"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn sections_can_override_their_feed_filenames() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\n");
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/page.html", "");
+    tmp_site.write(
+        "templates/section.html",
+        r#"{% for f in section.feed_filenames %}<link rel="alternate" href="{{ section.permalink ~ f }}">{% endfor %}"#,
+    );
+    tmp_site.write("templates/podcast.xml", "<podcast>{{ feed_url | safe }}</podcast>");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write(
+        "content/blog/_index.md",
+        "+++\ngenerate_feeds = true\nfeed_filenames = [\"rss.xml\"]\n+++\n",
+    );
+    tmp_site.write("content/blog/post.md", "+++\ndate = 2024-01-01\n+++\n");
+    tmp_site.write(
+        "content/podcast/_index.md",
+        "+++\ngenerate_feeds = true\nfeed_filenames = [\"podcast.xml\"]\n+++\n",
+    );
+    tmp_site.write("content/podcast/episode.md", "+++\ndate = 2024-01-01\n+++\n");
+    tmp_site.write("content/news/_index.md", "+++\ngenerate_feeds = true\n+++\n");
+    tmp_site.write("content/news/item.md", "+++\ndate = 2024-01-01\n+++\n");
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(public, "blog/rss.xml", "<rss"));
+    assert!(!file_exists!(public, "blog/atom.xml"));
+    assert!(file_contains!(
+        public,
+        "podcast/podcast.xml",
+        "<podcast>https://example.com/podcast/podcast.xml</podcast>"
+    ));
+    // Sections without an override use the config ones
+    assert!(file_exists!(public, "news/atom.xml"));
+    // The autodiscovery links use the filenames of each section
+    assert!(file_contains!(
+        public,
+        "blog/index.html",
+        r#"<link rel="alternate" href="https://example.com/blog/rss.xml">"#
+    ));
+    assert!(file_contains!(
+        public,
+        "podcast/index.html",
+        r#"<link rel="alternate" href="https://example.com/podcast/podcast.xml">"#
+    ));
+}
+
+#[test]
+fn can_add_podcast_tags_to_rss_feeds() {
+    let tmp_site = TempSite::new();
+    tmp_site.write(
+        "config.toml",
+        "base_url = \"https://example.com\"\nfeed_filenames = [\"rss.xml\"]\n",
+    );
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/section.html", "");
+    tmp_site.write("templates/page.html", "");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write("content/podcast/_index.md", "+++\ngenerate_feeds = true\n+++\n");
+    tmp_site.write(
+        "content/podcast/episode-1.md",
+        "+++\ndate = 2024-01-01\n[extra]\naudio = \"episodes/1.mp3\"\naudio_length = 1234\nduration = \"00:42:00\"\nexplicit = false\n+++\n",
+    );
+    tmp_site.write("content/blog/_index.md", "+++\ngenerate_feeds = true\n+++\n");
+    tmp_site.write(
+        "content/blog/post.md",
+        "+++\ndate = 2024-01-01\n[extra]\nimage = \"hero.png\"\n+++\n",
+    );
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(
+        public,
+        "podcast/rss.xml",
+        r#"xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd""#
+    ));
+    assert!(file_contains!(
+        public,
+        "podcast/rss.xml",
+        r#"<enclosure url="https://example.com/episodes/1.mp3" length="1234" type="audio/mpeg"/>"#
+    ));
+    assert!(file_contains!(
+        public,
+        "podcast/rss.xml",
+        "<itunes:duration>00:42:00</itunes:duration>"
+    ));
+    assert!(file_contains!(public, "podcast/rss.xml", "<itunes:explicit>false</itunes:explicit>"));
+    // The pages without audio are not episodes
+    assert!(!file_contains!(public, "blog/rss.xml", "itunes"));
+    assert!(!file_contains!(public, "blog/rss.xml", "<enclosure"));
+}
+
+#[test]
+fn can_sort_feeds_by_updated_date() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/page.html", "");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write("content/old.md", "+++\ndate = 2024-01-01\nupdated = 2024-06-01\n+++\n");
+    tmp_site.write("content/new.md", "+++\ndate = 2024-03-01\n+++\n");
+
+    let build = |sort_by_updated: bool| {
+        tmp_site.write(
+            "config.toml",
+            &format!(
+                "base_url = \"https://example.com\"\ngenerate_feeds = true\nfeed_sort_by_updated = {}\n",
+                sort_by_updated
+            ),
+        );
+        tmp_site.build().unwrap();
+        let feed = std::fs::read_to_string(tmp_site.public.join("atom.xml")).unwrap();
+        feed.find("https://example.com/old/").unwrap()
+            < feed.find("https://example.com/new/").unwrap()
+    };
+
+    assert!(!build(false));
+    // The older post was edited after the newer one was published
+    assert!(build(true));
+}
+
+#[test]
+fn can_choose_between_full_content_and_summary_in_feeds() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\n");
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/section.html", "");
+    tmp_site.write("templates/page.html", "");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    let post =
+        "+++\ndate = 2024-01-01\n+++\nThe summary\n<!-- more -->\nThe rest with [a link](rest/)\n";
+    tmp_site.write("content/blog/_index.md", "+++\ngenerate_feeds = true\n+++\n");
+    tmp_site.write("content/blog/post.md", post);
+    tmp_site.write(
+        "content/full/_index.md",
+        "+++\ngenerate_feeds = true\nfeed_include_content = true\n+++\n",
+    );
+    tmp_site.write("content/full/post.md", post);
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(
+        public,
+        "blog/atom.xml",
+        r#"<summary type="html" xml:base="https://example.com/blog/post/">&lt;p&gt;The summary&lt;&#x2F;p&gt;"#
+    ));
+    assert!(!file_contains!(public, "blog/atom.xml", "The rest"));
+    assert!(file_contains!(
+        public,
+        "full/atom.xml",
+        r#"<content type="html" xml:base="https://example.com/full/post/">&lt;p&gt;The summary&lt;&#x2F;p&gt;"#
+    ));
+    assert!(file_contains!(public, "full/atom.xml", "The rest with"));
+    // The relative links of the content are made absolute
+    assert!(file_contains!(
+        public,
+        "full/atom.xml",
+        "href=&quot;https:&#x2F;&#x2F;example.com&#x2F;full&#x2F;post&#x2F;rest&#x2F;&quot;"
+    ));
+    assert!(!file_contains!(public, "full/atom.xml", "<summary"));
+}
+
+#[test]
+fn makes_urls_absolute_in_feeds() {
+    let tmp_site = TempSite::new();
+    tmp_site.write(
+        "config.toml",
+        "base_url = \"https://example.com\"\ngenerate_feeds = true\nfeed_filenames = [\"rss.xml\"]\n",
+    );
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/page.html", "");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write(
+        "content/blog/post.md",
+        "+++\ndate = 2024-01-01\n+++\n![A cat](cat.png) ![A dot](data:image/png;base64,AAAA) [About](/about/)\n",
+    );
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(
+        public,
+        "rss.xml",
+        "src=&quot;https:&#x2F;&#x2F;example.com&#x2F;blog&#x2F;post&#x2F;cat.png&quot;"
+    ));
+    assert!(file_contains!(public, "rss.xml", "src=&quot;data:image&#x2F;png;base64,AAAA&quot;"));
+    assert!(file_contains!(
+        public,
+        "rss.xml",
+        "href=&quot;https:&#x2F;&#x2F;example.com&#x2F;about&#x2F;&quot;"
+    ));
+}
+
+#[test]
+fn sections_can_skip_their_index_but_keep_their_pages() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\n");
+    tmp_site.write(
+        "templates/index.html",
+        "{% set group = get_section(path=\"group/_index.md\") %}{{ group.pages | length }}",
+    );
+    tmp_site.write("templates/section.html", "");
+    tmp_site.write("templates/page.html", "{{ page.permalink | safe }}");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write("content/group/_index.md", "+++\nrender = false\ngenerate_feeds = true\n+++\n");
+    tmp_site.write("content/group/post.md", "+++\ndate = 2024-01-01\n+++\n");
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(!file_exists!(public, "group/index.html"));
+    assert!(file_contains!(public, "group/post/index.html", "https://example.com/group/post/"));
+    assert!(file_contains!(public, "group/atom.xml", "https://example.com/group/post/"));
+    assert!(file_contains!(public, "index.html", "1"));
+}
+
+#[test]
+fn can_use_a_page_as_404() {
+    let tmp_site = TempSite::new();
+    tmp_site.write(
+        "config.toml",
+        "base_url = \"https://example.com\"\nnot_found_page = \"@/404.md\"\ngenerate_feeds = true\n",
+    );
+    tmp_site
+        .write("templates/index.html", "{% for p in section.pages %}[{{ p.title }}]{% endfor %}");
+    tmp_site.write("templates/page.html", "{{ page.content | safe }}");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write("content/hello.md", "+++\ntitle = \"Hello\"\ndate = 2023-01-01\n+++\n");
+    tmp_site.write(
+        "content/404.md",
+        "+++\ntitle = \"Lost\"\ndate = 2023-01-02\n+++\nThis page is *lost*\n",
+    );
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(public, "404.html", "<p>This page is <em>lost</em></p>"));
+    // It is not rendered at its own path nor listed anywhere
+    assert!(!file_exists!(public, "404/index.html"));
+    assert!(file_contains!(public, "index.html", "[Hello]"));
+    assert!(!file_contains!(public, "index.html", "Lost"));
+    assert!(file_contains!(public, "atom.xml", "Hello"));
+    assert!(!file_contains!(public, "atom.xml", "Lost"));
+    assert!(!file_contains!(public, "sitemap.xml", "/404/"));
+
+    tmp_site.write(
+        "config.toml",
+        "base_url = \"https://example.com\"\nnot_found_page = \"@/missing.md\"\n",
+    );
+    let err = tmp_site.build().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The `not_found_page` `@/missing.md` set in the config was not found"
+    );
+}
+
+#[test]
+fn can_list_external_links_with_their_sources() {
+    let tmp_site = TempSite::new();
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\n");
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/page.html", "");
+    tmp_site.write("content/_index.md", "+++\n+++\n[Zola](https://www.getzola.org/)\n");
+    tmp_site.write(
+        "content/a.md",
+        "+++\n+++\n[Rust](https://www.rust-lang.org/) [Zola](https://www.getzola.org/) \
+         [again](https://www.rust-lang.org/) [internal](@/b.md)\n",
+    );
+    tmp_site.write("content/b.md", "+++\n+++\n[Rust](https://www.rust-lang.org/)\n");
+
+    let site = tmp_site.load().unwrap();
+    let report = site::link_checking::external_links_report(&site);
+    let expected: Vec<(&str, Vec<&str>)> = vec![
+        ("https://www.getzola.org/", vec!["https://example.com/", "https://example.com/a/"]),
+        ("https://www.rust-lang.org/", vec!["https://example.com/a/", "https://example.com/b/"]),
+    ];
+    let report: Vec<(&str, Vec<&str>)> = report
+        .iter()
+        .map(|(link, sources)| (link.as_str(), sources.iter().map(String::as_str).collect()))
+        .collect();
+    assert_eq!(report, expected);
+}
+
+#[test]
+fn can_link_to_section_index_files() {
+    let tmp_site = TempSite::new();
+    let root = &tmp_site.root;
+    tmp_site.write(
+        "config.toml",
+        "base_url = \"https://example.com\"\ndefault_language = \"en\"\n[languages.fr]\n",
+    );
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/section.html", "");
+    tmp_site.write("templates/page.html", "{{ page.content | safe }}");
+    tmp_site.write("content/blog/_index.md", "+++\n+++\n");
+    tmp_site.write("content/blog/_index.fr.md", "+++\n+++\n");
+    tmp_site.write(
+        "content/links.md",
+        "+++\n+++\n[blog](@/blog/_index.md) [blog fr](@/blog/_index.fr.md) \
+         [home](@/_index.md) [accueil](@/_index.fr.md#top)\n",
+    );
+
+    let site = tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    let library = site.library.read().unwrap();
+    for (link, section) in [
+        ("@/blog/_index.md", "blog/_index.md"),
+        ("@/blog/_index.fr.md", "blog/_index.fr.md"),
+        ("@/_index.md", "_index.md"),
+        ("@/_index.fr.md", "_index.fr.md"),
+    ] {
+        let section = library.sections.get(&root.join("content").join(section)).unwrap();
+        let resolved = resolve_internal_link(link, &site.permalinks, false).unwrap();
+        assert_eq!(resolved.permalink, section.permalink);
+    }
+    assert!(file_contains!(
+        public,
+        "links/index.html",
+        r#"<a href="https://example.com/blog/">blog</a> <a href="https://example.com/fr/blog/">blog fr</a> <a href="https://example.com/">home</a> <a href="https://example.com/fr/#top">accueil</a>"#
+    ));
+}
+
+#[test]
+fn build_aborts_when_going_over_the_output_limits() {
+    let tmp_site = TempSite::new();
+    let root = &tmp_site.root;
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write("templates/page.html", "{{ page.content | safe }}");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    for i in 0..5 {
+        tmp_site.write(&format!("content/page-{}.md", i), "+++\n+++\nSome content");
+    }
+    let build = |limits: &str| {
+        tmp_site.write("config.toml", &format!("base_url = \"https://example.com\"\n{}\n", limits));
+        tmp_site.build()
+    };
+
+    assert!(build("max_pages = 100\nmax_output_bytes = 100000").is_ok());
+    assert_eq!(
+        build("max_pages = 3").unwrap_err().to_string(),
+        "The build wrote more than 3 pages, which is the `max_pages` of the config"
+    );
+    assert_eq!(
+        build("max_output_bytes = 50").unwrap_err().to_string(),
+        "The build wrote more than 50 bytes, which is the `max_output_bytes` of the config"
+    );
+
+    // The incremental renders of `zola serve` are counted on their own
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\nmax_pages = 10\n");
+    let mut site = tmp_site.build().unwrap();
+    for _ in 0..20 {
+        site.add_and_render_page(&root.join("content/page-0.md")).unwrap();
+    }
+}
+
+#[test]
+fn processed_images_are_reused_by_the_next_build() {
+    let tmp_site = TempSite::new();
+    let root = &tmp_site.root;
+    tmp_site.write("config.toml", "base_url = \"https://example.com\"\n");
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write(
+        "templates/page.html",
+        "{% set img = resize_image(path=page.assets[0], width=2, height=2, op=\"fit\") %}{{ img.url | safe }}",
+    );
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write("content/post/index.md", "+++\n+++\n");
+    let img = Path::new(env!("CARGO_MANIFEST_DIR")).join("../imageproc/tests/test_imgs/png.png");
+    std::fs::copy(img, root.join("content/post/image.png")).unwrap();
+    let public = &tmp_site.public;
+    let build = || {
+        let site = tmp_site.build().unwrap();
+        assert_eq!(site.num_img_ops(), 1);
+        let processed: Vec<_> = std::fs::read_dir(root.join("static/processed_images"))
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+                (path, modified)
+            })
+            .collect();
+        processed
+    };
+
+    let first = build();
+    assert_eq!(first.len(), 1);
+    assert!(file_contains!(public, "post/index.html", "https://example.com/processed_images/"));
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    // The image is not resized again: the output file is unchanged
+    assert_eq!(build(), first);
+}
+
+#[test]
+fn can_render_shared_content_in_every_language() {
+    let tmp_site = TempSite::new();
+    tmp_site.write(
+        "config.toml",
+        r#"
+base_url = "https://example.com"
+shared_content = true
+
+[translations]
+greeting = "Hello"
+
+[languages.fr.translations]
+greeting = "Bonjour"
+"#,
+    );
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write(
+        "templates/section.html",
+        "{% for page in section.pages %}{{ page.permalink | safe }}{% endfor %}",
+    );
+    tmp_site.write("templates/page.html", "{{ page.lang }} {{ page.content | safe }}");
+    tmp_site.write("content/blog/_index.md", "+++\n+++\n");
+    tmp_site.write("content/blog/hello.md", "+++\n+++\n{{ trans(key=\"greeting\") }} world\n");
+    tmp_site.write("content/blog/translated.md", "+++\n+++\nEnglish\n");
+    tmp_site.write("content/blog/translated.fr.md", "+++\n+++\nFrançais\n");
+
+    tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    assert!(file_contains!(public, "blog/hello/index.html", "en <p>Hello world</p>"));
+    assert!(file_contains!(public, "fr/blog/hello/index.html", "fr <p>Bonjour world</p>"));
+    assert!(file_contains!(public, "fr/blog/index.html", "https://example.com/fr/blog/hello/"));
+    // The existing translations are used as they are
+    assert!(file_contains!(public, "fr/blog/translated/index.html", "Français"));
+}
+
+#[test]
+fn drafts_are_rendered_unlisted_under_a_secret_path() {
+    let tmp_site = TempSite::new();
+    let root = &tmp_site.root;
+    tmp_site.write(
+        "config.toml",
+        "base_url = \"https://example.com\"\ndrafts_secret = \"hunter2\"\ngenerate_feeds = true\n\
+         taxonomies = [{name = \"tags\"}]\n",
+    );
+    tmp_site.write("templates/index.html", "");
+    tmp_site
+        .write("templates/section.html", "{% for p in section.pages %}[{{ p.title }}]{% endfor %}");
+    tmp_site.write("templates/page.html", "{{ page.title }}");
+    tmp_site.write("templates/tags/list.html", "");
+    tmp_site.write("templates/tags/single.html", "");
+    tmp_site.write("content/blog/_index.md", "+++\n+++\n");
+    tmp_site.write(
+        "content/blog/published.md",
+        "+++\ntitle = \"Published\"\ndate = 2023-01-01\n[taxonomies]\ntags = [\"a\"]\n+++\n",
+    );
+    tmp_site.write(
+        "content/blog/review.md",
+        "+++\ntitle = \"Review\"\ndate = 2023-01-02\ndraft = true\naliases = [\"/old-review/\"]\n[taxonomies]\ntags = [\"b\"]\n+++\n",
+    );
+
+    let site = tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    let library = site.library.read().unwrap();
+    let draft = library.pages.get(&root.join("content/blog/review.md")).unwrap();
+    assert!(draft.unlisted);
+    assert_eq!(draft.components.len(), 1);
+    let secret_path = &draft.components[0];
+    assert_eq!(secret_path.len(), 32);
+    assert_eq!(draft.ancestors, vec!["_index.md", "blog/_index.md"]);
+
+    assert!(file_contains!(public, &format!("{}/index.html", secret_path), "Review"));
+    assert!(!file_exists!(public, "blog/review/index.html"));
+    assert!(!file_exists!(public, "old-review/index.html"));
+    assert!(file_contains!(public, "blog/index.html", "[Published]"));
+    assert!(!file_contains!(public, "blog/index.html", "Review"));
+    assert!(file_contains!(public, "atom.xml", "Published"));
+    assert!(!file_contains!(public, "atom.xml", "Review"));
+    assert!(!file_contains!(public, "sitemap.xml", secret_path));
+    assert!(file_exists!(public, "tags/a/index.html"));
+    assert!(!file_exists!(public, "tags/b/index.html"));
+}
+
+#[test]
+fn unlisted_drafts_cannot_be_linked_to() {
+    let tmp_site = TempSite::new();
+    tmp_site
+        .write("config.toml", "base_url = \"https://example.com\"\ndrafts_secret = \"hunter2\"\n");
+    tmp_site.write("templates/page.html", "{{ page.title }}");
+    tmp_site.write("content/review.md", "+++\ntitle = \"Review\"\ndraft = true\n+++\n");
+    let build = |index: &str, published: &str| {
+        tmp_site.write("templates/index.html", index);
+        tmp_site.write(
+            "content/published.md",
+            &format!("+++\ntitle = \"Published\"\n+++\n{}", published),
+        );
+        tmp_site.build()
+    };
+
+    assert!(build("", "").is_ok());
+    assert!(format!("{:?}", build("", "[review](@/review.md)").unwrap_err())
+        .contains("Broken relative link `@/review.md`"));
+    for index in ["{{ get_url(path='@/review.md') }}", "{{ get_page(path='review.md') }}"] {
+        assert!(build(index, "").is_err());
+    }
+}
+
+#[test]
+fn unlisted_drafts_are_not_related_pages_nor_unlinked() {
+    let tmp_site = TempSite::new();
+    tmp_site.write(
+        "config.toml",
+        "base_url = \"https://example.com\"\ndrafts_secret = \"hunter2\"\ntaxonomies = [{name = \"tags\"}]\n",
+    );
+    tmp_site.write("templates/index.html", "");
+    tmp_site.write(
+        "templates/page.html",
+        "{% for p in get_related_pages(path=page.relative_path) %}{{ p.permalink | safe }}{% endfor %}",
+    );
+    tmp_site.write("templates/tags/list.html", "");
+    tmp_site.write("templates/tags/single.html", "");
+    tmp_site.write("content/_index.md", "+++\n+++\n");
+    tmp_site.write("content/public.md", "+++\n[taxonomies]\ntags = [\"a\"]\n+++\n");
+    tmp_site.write("content/other.md", "+++\n[taxonomies]\ntags = [\"a\"]\n+++\n");
+    tmp_site.write("content/secret.md", "+++\ndraft = true\n[taxonomies]\ntags = [\"a\"]\n+++\n");
+
+    let site = tmp_site.build().unwrap();
+    let public = &tmp_site.public;
+
+    let library = site.library.read().unwrap();
+    let secret = library.pages.get(&tmp_site.root.join("content/secret.md")).unwrap();
+    assert!(secret.unlisted);
+    assert!(file_contains!(public, "public/index.html", "https://example.com/other/"));
+    assert!(!file_contains!(public, "public/index.html", &secret.permalink));
+    drop(library);
+    assert!(site::link_checking::find_unlinked_pages(&site).is_empty());
+}
@@ -79,6 +79,8 @@ fn can_build_multilingual_site() {
     ));
     assert!(file_exists!(public, "fr/blog/index.html"));
     assert!(file_contains!(public, "fr/blog/index.html", "Language: fr"));
+    // Aliases of a translated section redirect to the section in that language
+    assert!(file_contains!(public, "fr/vieux-blog/index.html", "https://example.com/fr/blog/"));
     assert!(file_contains!(
         public,
         "fr/blog/index.html",
@@ -5,13 +5,21 @@ use std::sync::{Arc, Mutex};
 
 use config::Config;
 
+use crate::global_fns::compute_hash;
 use libs::base64::engine::{general_purpose::STANDARD as standard_b64, Engine};
+use libs::md5::Md5;
+use libs::once_cell::sync::Lazy;
 use libs::regex::Regex;
+use libs::sha1::Sha1;
+use libs::sha2::{Sha256, Sha384, Sha512};
 use libs::tera::{
-    to_value, try_get_value, Error as TeraError, Filter as TeraFilter, Result as TeraResult, Tera,
-    Value,
+    from_value, to_value, try_get_value, Error as TeraError, Filter as TeraFilter,
+    Result as TeraResult, Tera, Value,
 };
 use markdown::{render_content, RenderContext};
+use utils::locales::find_locale;
+use utils::net::{make_absolute_url, split_base_url};
+use utils::slugs::{slugify_paths, SlugifyStrategy};
 
 #[derive(Debug)]
 pub struct MarkdownFilter {
@@ -81,6 +89,92 @@ pub fn base64_decode<S: BuildHasher>(
     Ok(to_value(as_str).unwrap())
 }
 
+/// Hashes the value with the `algo` arg, `sha256` by default, as hex unless `base64` is set
+pub fn hash<S: BuildHasher>(value: &Value, args: &HashMap<String, Value, S>) -> TeraResult<Value> {
+    let s = try_get_value!("hash", "value", String, value);
+    let algo = match args.get("algo") {
+        Some(val) => try_get_value!("hash", "algo", String, val),
+        None => "sha256".to_string(),
+    };
+    let base64 = match args.get("base64") {
+        Some(val) => try_get_value!("hash", "base64", bool, val),
+        None => false,
+    };
+
+    let hash = match algo.as_str() {
+        "md5" => compute_hash::<Md5>(s.as_bytes(), base64),
+        "sha1" => compute_hash::<Sha1>(s.as_bytes(), base64),
+        "sha256" => compute_hash::<Sha256>(s.as_bytes(), base64),
+        "sha384" => compute_hash::<Sha384>(s.as_bytes(), base64),
+        "sha512" => compute_hash::<Sha512>(s.as_bytes(), base64),
+        _ => {
+            return Err(format!(
+                "Filter `hash` received an invalid `algo`: {}, expected `md5`, `sha1`, `sha256`, `sha384` or `sha512`",
+                algo
+            )
+            .into())
+        }
+    };
+    Ok(to_value(hash).unwrap())
+}
+
+/// Returns the `page`th pager, 1-indexed, of the array split in pagers of `by` items, like the
+/// paginator of sections. A `page` out of range gives a pager without any item.
+pub fn paginate<S: BuildHasher>(
+    value: &Value,
+    args: &HashMap<String, Value, S>,
+) -> TeraResult<Value> {
+    let items = try_get_value!("paginate", "value", Vec<Value>, value);
+    let by = match args.get("by") {
+        Some(val) => try_get_value!("paginate", "by", usize, val),
+        None => return Err(TeraError::msg("Filter `paginate` expected an arg called `by`")),
+    };
+    if by == 0 {
+        return Err(TeraError::msg("Filter `paginate` received `by=0`, it needs to be positive"));
+    }
+    let page = match args.get("page") {
+        Some(val) => try_get_value!("paginate", "page", usize, val),
+        None => 1,
+    };
+
+    let number_pagers = items.len().div_ceil(by).max(1);
+    let in_range = page >= 1 && page <= number_pagers;
+    let pages: Vec<Value> =
+        if in_range { items.into_iter().skip((page - 1) * by).take(by).collect() } else { vec![] };
+    let mut pager = libs::tera::Map::new();
+    pager.insert("pages".to_string(), Value::Array(pages));
+    pager.insert("current_index".to_string(), to_value(page).unwrap());
+    pager.insert("number_pagers".to_string(), to_value(number_pagers).unwrap());
+    pager.insert(
+        "previous".to_string(),
+        if in_range && page > 1 { to_value(page - 1).unwrap() } else { Value::Null },
+    );
+    pager.insert(
+        "next".to_string(),
+        if in_range && page < number_pagers { to_value(page + 1).unwrap() } else { Value::Null },
+    );
+    Ok(Value::Object(pager))
+}
+
+/// Replaces the Tera `slugify` filter so the `mode` arg can be used to slugify the same way
+/// as the `slugify` config does. Defaults to `on`, like the Tera filter.
+pub fn slugify<S: BuildHasher>(
+    value: &Value,
+    args: &HashMap<String, Value, S>,
+) -> TeraResult<Value> {
+    let s = try_get_value!("slugify", "value", String, value);
+    let strategy = match args.get("mode") {
+        Some(val) => from_value::<SlugifyStrategy>(val.clone()).map_err(|_| {
+            TeraError::msg(format!(
+                "Filter `slugify` received an invalid `mode`: {}, expected `on`, `safe`, `off` or `unicode`",
+                val
+            ))
+        })?,
+        None => SlugifyStrategy::On,
+    };
+    Ok(to_value(slugify_paths(&s, strategy)).unwrap())
+}
+
 #[derive(Debug)]
 pub struct RegexReplaceFilter {
     re_cache: Arc<Mutex<HashMap<String, Regex>>>,
@@ -158,6 +252,121 @@ impl TeraFilter for NumFormatFilter {
     }
 }
 
+/// The Tera builtins, to format the dates with its own `date` filter
+static TERA_BUILTINS: Lazy<Tera> = Lazy::new(Tera::default);
+
+/// Replaces the Tera `date` filter so its `locale` can be a language code like `fr` or `pt-BR`
+/// as well as a locale like `fr_CA`. Without a `locale`, the output is the one of Tera.
+#[derive(Debug, Default)]
+pub struct DateFilter;
+
+impl TeraFilter for DateFilter {
+    fn filter(&self, value: &Value, args: &HashMap<String, Value>) -> TeraResult<Value> {
+        let mut args = args.clone();
+        if let Some(locale) = args.get("locale") {
+            let locale = try_get_value!("date", "locale", String, locale);
+            let locale = find_locale(&locale).ok_or_else(|| {
+                TeraError::msg(format!(
+                    "Filter `date` was called with an invalid `locale` argument: `{}`.",
+                    locale
+                ))
+            })?;
+            args.insert("locale".to_string(), to_value(locale.to_string()).unwrap());
+        }
+        TERA_BUILTINS.get_filter("date")?.filter(value, &args)
+    }
+}
+
+/// Escapes `&<>"'` for XML. This replaces the `escape_xml` filter of Tera so its output is not
+/// escaped again in the autoescaped templates, like the feeds
+#[derive(Debug, Default)]
+pub struct EscapeXmlFilter;
+
+impl TeraFilter for EscapeXmlFilter {
+    fn filter(&self, value: &Value, _: &HashMap<String, Value>) -> TeraResult<Value> {
+        let s = try_get_value!("escape_xml", "value", String, value);
+        let mut escaped = String::with_capacity(s.len());
+        for c in s.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&apos;"),
+                _ => escaped.push(c),
+            }
+        }
+        Ok(to_value(escaped).unwrap())
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+/// Wraps the value in a CDATA section. A `]]>` in the value would end the section early so the
+/// section is split in two around it
+#[derive(Debug, Default)]
+pub struct CdataFilter;
+
+impl TeraFilter for CdataFilter {
+    fn filter(&self, value: &Value, _: &HashMap<String, Value>) -> TeraResult<Value> {
+        let s = try_get_value!("cdata", "value", String, value);
+        Ok(to_value(format!("<![CDATA[{}]]>", s.replace("]]>", "]]]]><![CDATA[>"))).unwrap())
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+/// Turns the paths of the site into absolute URLs by prepending the `base_url`
+#[derive(Debug)]
+pub struct AbsoluteUrlFilter {
+    base_url: String,
+}
+
+impl AbsoluteUrlFilter {
+    pub fn new<S: Into<String>>(base_url: S) -> Self {
+        Self { base_url: base_url.into() }
+    }
+}
+
+impl TeraFilter for AbsoluteUrlFilter {
+    fn filter(&self, value: &Value, _: &HashMap<String, Value>) -> TeraResult<Value> {
+        let url = try_get_value!("absolute_url", "value", String, value);
+        Ok(to_value(make_absolute_url(&self.base_url, &url)).unwrap())
+    }
+}
+
+/// Turns the URLs of the site into root-relative ones: the scheme and host of the `base_url`
+/// are removed but its path, like `/docs` for `https://example.com/docs`, is kept
+#[derive(Debug)]
+pub struct RelativeUrlFilter {
+    base_url: String,
+}
+
+impl RelativeUrlFilter {
+    pub fn new<S: Into<String>>(base_url: S) -> Self {
+        Self { base_url: base_url.into() }
+    }
+}
+
+impl TeraFilter for RelativeUrlFilter {
+    fn filter(&self, value: &Value, _: &HashMap<String, Value>) -> TeraResult<Value> {
+        let url = try_get_value!("relative_url", "value", String, value);
+        let absolute = make_absolute_url(&self.base_url, &url);
+        let (origin, _) = split_base_url(&self.base_url);
+        let relative = match absolute.strip_prefix(origin) {
+            Some("") => "/".to_string(),
+            Some(rest) if rest.starts_with('/') => rest.to_string(),
+            Some(rest) if rest.starts_with(['?', '#']) => format!("/{}", rest),
+            _ => absolute,
+        };
+        Ok(to_value(relative).unwrap())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -165,7 +374,9 @@ mod tests {
     use libs::tera::{to_value, Filter, Tera};
 
     use super::{
-        base64_decode, base64_encode, MarkdownFilter, NumFormatFilter, RegexReplaceFilter,
+        base64_decode, base64_encode, hash, paginate, slugify, AbsoluteUrlFilter, CdataFilter,
+        DateFilter, EscapeXmlFilter, MarkdownFilter, NumFormatFilter, RegexReplaceFilter,
+        RelativeUrlFilter,
     };
     use config::Config;
 
@@ -303,6 +514,106 @@ mod tests {
         }
     }
 
+    #[test]
+    fn base64_filters_round_trip() {
+        let args = HashMap::new();
+        let input = r#"<svg xmlns="http://www.w3.org/2000/svg">héllo</svg>"#;
+        let encoded = base64_encode(&to_value(input).unwrap(), &args).unwrap();
+        let decoded = base64_decode(&encoded, &args).unwrap();
+        assert_eq!(decoded, to_value(input).unwrap());
+    }
+
+    #[test]
+    fn base64_decode_filter_errors_on_invalid_input() {
+        let args = HashMap::new();
+        let result = base64_decode(&to_value("not base64!").unwrap(), &args);
+        assert!(result.unwrap_err().to_string().contains("`base64_decode`: failed to decode"));
+    }
+
+    #[test]
+    fn paginate_filter() {
+        let items = to_value((1..=10).collect::<Vec<_>>()).unwrap();
+        let pager_at = |page: usize| {
+            let mut args = HashMap::new();
+            args.insert("by".to_string(), to_value(3).unwrap());
+            args.insert("page".to_string(), to_value(page).unwrap());
+            paginate(&items, &args).unwrap()
+        };
+
+        let pager = pager_at(2);
+        assert_eq!(pager["pages"], to_value([4, 5, 6]).unwrap());
+        assert_eq!(pager["current_index"], 2);
+        assert_eq!(pager["number_pagers"], 4);
+        assert_eq!(pager["previous"], 1);
+        assert_eq!(pager["next"], 3);
+
+        let last = pager_at(4);
+        assert_eq!(last["pages"], to_value([10]).unwrap());
+        assert!(last["next"].is_null());
+
+        for out_of_range in [0, 5] {
+            let pager = pager_at(out_of_range);
+            assert_eq!(pager["pages"], to_value(Vec::<usize>::new()).unwrap());
+            assert!(pager["previous"].is_null());
+            assert!(pager["next"].is_null());
+        }
+    }
+
+    #[test]
+    fn paginate_filter_errors_on_invalid_by() {
+        let items = to_value([1, 2]).unwrap();
+        assert!(paginate(&items, &HashMap::new()).is_err());
+        let mut args = HashMap::new();
+        args.insert("by".to_string(), to_value(0).unwrap());
+        assert!(paginate(&items, &args).is_err());
+    }
+
+    #[test]
+    fn hash_filter() {
+        let tests = vec![
+            ("md5", "b10a8db164e0754105b7a99be72e3fe5"),
+            ("sha1", "0a4d55a8d778e5022fab701977c5d840bbc486d0"),
+            ("sha256", "a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e"),
+            ("sha384", "99514329186b2f6ae4a1329e7ee6c610a729636335174ac6b740f9028396fcc803d0e93863a7c3d90f86beee782f4f3f"),
+            ("sha512", "2c74fd17edafd80e8447b0d46741ee243b7eb74dd2149a0ab1b9246fb30382f27e853d8585719e0e67cbda0daa8f51671064615d645ae27acb15bfb1447f459b"),
+        ];
+        for (algo, expected) in tests {
+            let mut args = HashMap::new();
+            args.insert("algo".to_string(), to_value(algo).unwrap());
+            let result = hash(&to_value("Hello World").unwrap(), &args);
+            assert_eq!(result.unwrap(), to_value(expected).unwrap());
+        }
+
+        // sha256 by default
+        let result = hash(&to_value("Hello World").unwrap(), &HashMap::new());
+        assert_eq!(
+            result.unwrap(),
+            to_value("a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e").unwrap()
+        );
+
+        let mut args = HashMap::new();
+        args.insert("algo".to_string(), to_value("crc32").unwrap());
+        assert!(hash(&to_value("Hello World").unwrap(), &args).is_err());
+    }
+
+    #[test]
+    fn hash_filter_base64() {
+        let tests = vec![
+            ("md5", "sQqNsWTgdUEFt6mb5y4/5Q=="),
+            ("sha1", "Ck1VqNd45QIvq3AZd8XYQLvEhtA="),
+            ("sha256", "pZGm1Av0IEBKARczz7exkNYsZb8LzaMrV7J32a2fFG4="),
+            ("sha384", "mVFDKRhrL2rkoTKefubGEKcpY2M1F0rGt0D5AoOW/MgD0Ok4Y6fD2Q+Gvu54L08/"),
+            ("sha512", "LHT9F+2v2A6ER7DUZ0HuJDt+t03SFJoKsbkkb7MDgvJ+hT2FhXGeDmfL2g2qj1FnEGRhXWRa4nrLFb+xRH9Fmw=="),
+        ];
+        for (algo, expected) in tests {
+            let mut args = HashMap::new();
+            args.insert("algo".to_string(), to_value(algo).unwrap());
+            args.insert("base64".to_string(), to_value(true).unwrap());
+            let result = hash(&to_value("Hello World").unwrap(), &args);
+            assert_eq!(result.unwrap(), to_value(expected).unwrap());
+        }
+    }
+
     #[test]
     fn regex_replace_filter() {
         let value = "Springsteen, Bruce";
@@ -358,4 +669,143 @@ mod tests {
             assert_eq!(result.unwrap(), to_value(expected).unwrap());
         }
     }
+
+    #[test]
+    fn date_filter_with_locale() {
+        let tests = vec![
+            ("fr", "%A %d %B %Y", "samedi 03 juin 2023"),
+            ("de", "%A, %d. %B %Y", "Samstag, 03. Juni 2023"),
+            ("de_AT", "%d. %B %Y", "03. Juni 2023"),
+            ("en", "%A %d %B %Y", "Saturday 03 June 2023"),
+        ];
+
+        for (locale, format, expected) in tests {
+            let mut args = HashMap::new();
+            args.insert("locale".to_string(), to_value(locale).unwrap());
+            args.insert("format".to_string(), to_value(format).unwrap());
+            let result = DateFilter.filter(&to_value("2023-06-03").unwrap(), &args);
+            assert_eq!(result.unwrap(), to_value(expected).unwrap());
+        }
+    }
+
+    #[test]
+    fn date_filter_without_locale_is_the_tera_one() {
+        let mut args = HashMap::new();
+        args.insert("format".to_string(), to_value("%d %B %Y").unwrap());
+        let date = to_value("2023-02-03T10:00:00+01:00").unwrap();
+        let result = DateFilter.filter(&date, &args);
+        assert_eq!(result.unwrap(), to_value("03 February 2023").unwrap());
+        let tera_date = Tera::default().get_filter("date").unwrap().filter(&date, &HashMap::new());
+        assert_eq!(DateFilter.filter(&date, &HashMap::new()).unwrap(), tera_date.unwrap());
+    }
+
+    #[test]
+    fn date_filter_errors_on_invalid_locale() {
+        let mut args = HashMap::new();
+        args.insert("locale".to_string(), to_value("tlh").unwrap());
+        let result = DateFilter.filter(&to_value("2023-06-03").unwrap(), &args);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn escape_xml_filter() {
+        let result = EscapeXmlFilter
+            .filter(&to_value(r#"<a href="/?a=1&b='2'">"#).unwrap(), &HashMap::new());
+        assert_eq!(
+            result.unwrap(),
+            to_value("&lt;a href=&quot;/?a=1&amp;b=&apos;2&apos;&quot;&gt;").unwrap()
+        );
+    }
+
+    #[test]
+    fn cdata_filter() {
+        let tests = vec![
+            ("<p>Hello</p>", "<![CDATA[<p>Hello</p>]]>"),
+            ("", "<![CDATA[]]>"),
+            ("a]]>b", "<![CDATA[a]]]]><![CDATA[>b]]>"),
+            ("]]>]]>", "<![CDATA[]]]]><![CDATA[>]]]]><![CDATA[>]]>"),
+        ];
+        for (input, expected) in tests {
+            let result = CdataFilter.filter(&to_value(input).unwrap(), &HashMap::new());
+            assert_eq!(result.unwrap(), to_value(expected).unwrap());
+        }
+    }
+
+    #[test]
+    fn xml_filters_are_not_escaped_again_in_feeds() {
+        let mut tera = Tera::default();
+        tera.register_filter("escape_xml", EscapeXmlFilter);
+        tera.register_filter("cdata", CdataFilter);
+        tera.add_raw_template("feed.xml", "{{ a | escape_xml }} {{ a | cdata }}").unwrap();
+        let mut context = libs::tera::Context::new();
+        context.insert("a", "1 < 2 & 3");
+        assert_eq!(
+            tera.render("feed.xml", &context).unwrap(),
+            "1 &lt; 2 &amp; 3 <![CDATA[1 < 2 & 3]]>"
+        );
+    }
+
+    #[test]
+    fn slugify_filter() {
+        let input = "Héllo Wörld: Zola?";
+        let tests = vec![
+            (None, "hello-world-zola"),
+            (Some("on"), "hello-world-zola"),
+            (Some("safe"), "Héllo Wörld Zola"),
+            (Some("off"), "Héllo Wörld: Zola?"),
+            (Some("unicode"), "héllo-wörld-zola"),
+        ];
+        for (mode, expected) in tests {
+            let mut args = HashMap::new();
+            if let Some(mode) = mode {
+                args.insert("mode".to_string(), to_value(mode).unwrap());
+            }
+            let result = slugify(&to_value(input).unwrap(), &args);
+            assert_eq!(result.unwrap(), to_value(expected).unwrap());
+        }
+
+        let mut args = HashMap::new();
+        args.insert("mode".to_string(), to_value("nope").unwrap());
+        assert!(slugify(&to_value(input).unwrap(), &args).is_err());
+    }
+
+    #[test]
+    fn absolute_url_filter() {
+        let filter = AbsoluteUrlFilter::new("https://example.com/docs/");
+        for (url, expected) in [
+            ("blog/post/", "https://example.com/docs/blog/post/"),
+            ("/images/logo.png", "https://example.com/docs/images/logo.png"),
+            ("/blog/?page=2#comments", "https://example.com/docs/blog/?page=2#comments"),
+            ("/docs/blog/", "https://example.com/docs/blog/"),
+            ("#top", "https://example.com/docs/#top"),
+            ("", "https://example.com/docs/"),
+            ("https://example.com/docs/blog/", "https://example.com/docs/blog/"),
+            ("https://other.com/", "https://other.com/"),
+            ("mailto:me@example.com", "mailto:me@example.com"),
+        ] {
+            let result = filter.filter(&to_value(url).unwrap(), &HashMap::new());
+            assert_eq!(result.unwrap(), to_value(expected).unwrap(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn relative_url_filter() {
+        let filter = RelativeUrlFilter::new("https://example.com/docs");
+        for (url, expected) in [
+            ("blog/post/", "/docs/blog/post/"),
+            ("/docs/blog/post/", "/docs/blog/post/"),
+            ("/documents/", "/docs/documents/"),
+            ("https://example.com/docs/blog/#top", "/docs/blog/#top"),
+            ("https://example.com?q=1", "/?q=1"),
+            ("https://example.com", "/"),
+            ("https://example.com.evil/", "https://example.com.evil/"),
+            ("https://other.com/blog/", "https://other.com/blog/"),
+        ] {
+            let result = filter.filter(&to_value(url).unwrap(), &HashMap::new());
+            assert_eq!(result.unwrap(), to_value(expected).unwrap(), "{}", url);
+        }
+        // Idempotent
+        let once = filter.filter(&to_value("blog/").unwrap(), &HashMap::new()).unwrap();
+        assert_eq!(filter.filter(&once, &HashMap::new()).unwrap(), once);
+    }
 }
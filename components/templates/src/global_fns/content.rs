@@ -1,14 +1,16 @@
+use config::TaxonomyConfig;
 use content::{Library, Taxonomy, TaxonomyTerm};
 use libs::tera::{from_value, to_value, Function as TeraFn, Result, Value};
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
-use utils::slugs::{slugify_paths, SlugifyStrategy};
+use utils::slugs::SlugifyStrategy;
 
 #[derive(Debug)]
 pub struct GetTaxonomyUrl {
-    taxonomies: HashMap<String, HashMap<String, String>>,
+    /// The taxonomy config, for the custom slugs, and its terms permalinks by slug
+    taxonomies: HashMap<String, (TaxonomyConfig, HashMap<String, String>)>,
     default_lang: String,
     slugify: SlugifyStrategy,
 }
@@ -19,9 +21,10 @@ impl GetTaxonomyUrl {
         for taxo in all_taxonomies {
             let mut items = HashMap::new();
             for item in &taxo.items {
-                items.insert(slugify_paths(&item.name.clone(), slugify), item.permalink.clone());
+                items.insert(item.slug.clone(), item.permalink.clone());
             }
-            taxonomies.insert(format!("{}-{}", taxo.kind.name, taxo.lang), items);
+            taxonomies
+                .insert(format!("{}-{}", taxo.kind.name, taxo.lang), (taxo.kind.clone(), items));
         }
         Self { taxonomies, default_lang: default_lang.to_string(), slugify }
     }
@@ -48,19 +51,20 @@ impl TeraFn for GetTaxonomyUrl {
         )
         .unwrap_or(true);
 
-        let container = match (self.taxonomies.get(&format!("{}-{}", kind, lang)), required) {
-            (Some(c), _) => c,
-            (None, false) => return Ok(Value::Null),
-            (None, true) => {
-                return Err(format!(
-                    "`get_taxonomy_url` received an unknown taxonomy as kind: {}",
-                    kind
-                )
-                .into());
-            }
-        };
+        let (taxo_config, container) =
+            match (self.taxonomies.get(&format!("{}-{}", kind, lang)), required) {
+                (Some(c), _) => c,
+                (None, false) => return Ok(Value::Null),
+                (None, true) => {
+                    return Err(format!(
+                        "`get_taxonomy_url` received an unknown taxonomy as kind: {}",
+                        kind
+                    )
+                    .into());
+                }
+            };
 
-        if let Some(permalink) = container.get(&slugify_paths(&name, self.slugify)) {
+        if let Some(permalink) = container.get(&taxo_config.term_slug(&name, self.slugify)) {
             return Ok(to_value(permalink).unwrap());
         }
 
@@ -136,6 +140,10 @@ impl TeraFn for GetPage {
             "`get_page` requires a `path` argument with a string value"
         );
 
+        let metadata_only = args
+            .get("metadata_only")
+            .is_some_and(|c| from_value::<bool>(c.clone()).unwrap_or(false));
+
         let lang =
             optional_arg!(String, args.get("lang"), "`get_section`: `lang` must be a string");
 
@@ -144,8 +152,15 @@ impl TeraFn for GetPage {
                 let full_path = self.base_path.join(path_with_lang.as_ref());
                 let library = self.library.read().unwrap();
 
-                match library.pages.get(&full_path) {
-                    Some(p) => Ok(to_value(p.serialize(&library)).unwrap()),
+                // The unlisted drafts are not listed anywhere, including here
+                match library.pages.get(&full_path).filter(|p| !p.unlisted) {
+                    Some(p) => {
+                        if metadata_only {
+                            Ok(to_value(p.serialize_metadata_only(&library)).unwrap())
+                        } else {
+                            Ok(to_value(p.serialize(&library)).unwrap())
+                        }
+                    }
                     None => match lang {
                         Some(lang_code) => {
                             Err(format!("Page `{}` not found for language `{}`.", path, lang_code)
@@ -159,6 +174,52 @@ impl TeraFn for GetPage {
     }
 }
 
+/// Returns the pages sharing the most taxonomy terms with a page, for a "related posts" block
+#[derive(Debug)]
+pub struct GetRelatedPages {
+    base_path: PathBuf,
+    library: Arc<RwLock<Library>>,
+}
+impl GetRelatedPages {
+    pub fn new(base_path: PathBuf, library: Arc<RwLock<Library>>) -> Self {
+        Self { base_path: base_path.join("content"), library }
+    }
+}
+impl TeraFn for GetRelatedPages {
+    fn call(&self, args: &HashMap<String, Value>) -> Result<Value> {
+        let path = required_arg!(
+            String,
+            args.get("path"),
+            "`get_related_pages` requires a `path` argument with a string value"
+        );
+        let count = optional_arg!(
+            usize,
+            args.get("count"),
+            "`get_related_pages`: `count` must be a positive number"
+        )
+        .unwrap_or(5);
+        let taxonomies = optional_arg!(
+            Vec<String>,
+            args.get("taxonomies"),
+            "`get_related_pages`: `taxonomies` must be an array of taxonomy names"
+        )
+        .unwrap_or_default();
+
+        let full_path = self.base_path.join(&path);
+        let library = self.library.read().unwrap();
+        if !library.pages.contains_key(&full_path) {
+            return Err(format!("`get_related_pages`: page `{}` not found.", path).into());
+        }
+
+        let pages: Vec<_> = library
+            .find_related_pages(&full_path, &taxonomies, count)
+            .into_iter()
+            .map(|p| p.serialize_without_siblings(&library))
+            .collect();
+        Ok(to_value(pages).unwrap())
+    }
+}
+
 #[derive(Debug)]
 pub struct GetSection {
     base_path: PathBuf,
@@ -354,7 +415,7 @@ impl TeraFn for GetTaxonomyTerm {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use config::{Config, TaxonomyConfig};
+    use config::Config;
     use content::{FileInfo, Library, Page, Section, SortBy, TaxonomyTerm};
     use std::path::Path;
     use std::sync::{Arc, RwLock};
@@ -427,6 +488,42 @@ mod tests {
         assert_eq!(res_obj["title"], to_value("Recipes").unwrap());
     }
 
+    #[test]
+    fn can_get_related_pages() {
+        let mut config = Config::default_for_test();
+        config.languages.get_mut("en").unwrap().taxonomies =
+            vec![TaxonomyConfig { name: "tags".to_string(), ..TaxonomyConfig::default() }];
+        config.slugify_taxonomies();
+        let mut library = Library::new(&config);
+        let pages = vec![
+            ("Current", "content/current.md", vec!["rust", "web"]),
+            ("One tag", "content/one-tag.md", vec!["rust"]),
+            ("Two tags", "content/two-tags.md", vec!["web", "rust"]),
+            ("Unrelated", "content/unrelated.md", vec!["python"]),
+        ];
+        for (t, f, tags) in pages {
+            let mut page = create_page(t, f, "en");
+            page.meta
+                .taxonomies
+                .insert("tags".to_string(), tags.iter().map(|t| t.to_string()).collect());
+            library.insert_page(page);
+        }
+        let static_fn =
+            GetRelatedPages::new("/test/base/path".into(), Arc::new(RwLock::new(library)));
+
+        let mut args = HashMap::new();
+        args.insert("path".to_string(), to_value("current.md").unwrap());
+        let res = static_fn.call(&args).unwrap();
+        let titles: Vec<_> = res.as_array().unwrap().iter().map(|p| p["title"].clone()).collect();
+        assert_eq!(titles, vec![to_value("Two tags").unwrap(), to_value("One tag").unwrap()]);
+
+        args.insert("count".to_string(), to_value(1).unwrap());
+        assert_eq!(static_fn.call(&args).unwrap().as_array().unwrap().len(), 1);
+
+        args.insert("path".to_string(), to_value("missing.md").unwrap());
+        assert!(static_fn.call(&args).is_err());
+    }
+
     fn create_section(title: &str, file_path: &str, lang: &str) -> Section {
         let mut section = Section { lang: lang.to_owned(), ..Section::default() };
         section.file = FileInfo::new_section(
@@ -498,6 +595,44 @@ mod tests {
         assert_eq!(res_obj["title"], to_value("Recipes").unwrap());
     }
 
+    #[test]
+    fn can_get_page_and_section_metadata_only() {
+        let mut library = Library::default();
+        let mut page = create_page("Blog post", "content/blog/post.md", "en");
+        page.content = "<p>Hello</p>".to_owned();
+        page.summary = Some("<p>Hi</p>".to_owned());
+        library.insert_page(page);
+        let mut section = create_section("Blog", "content/blog/_index.md", "en");
+        section.content = "<p>The blog</p>".to_owned();
+        library.insert_section(section);
+        let library = Arc::new(RwLock::new(library));
+        let lang_list = Arc::new(vec!["en".to_string()]);
+
+        let get_page =
+            GetPage::new("/test/base/path".into(), "en", lang_list.clone(), library.clone());
+        let mut args = HashMap::new();
+        args.insert("path".to_string(), to_value("blog/post.md").unwrap());
+        let res = get_page.call(&args).unwrap();
+        assert_eq!(res["content"], to_value("<p>Hello</p>").unwrap());
+
+        args.insert("metadata_only".to_string(), to_value(true).unwrap());
+        let res = get_page.call(&args).unwrap();
+        assert_eq!(res["title"], to_value("Blog post").unwrap());
+        assert_eq!(res["content"], to_value("").unwrap());
+        assert_eq!(res["summary"], Value::Null);
+
+        let get_section = GetSection::new("/test/base/path".into(), "en", lang_list, library);
+        let mut args = HashMap::new();
+        args.insert("path".to_string(), to_value("blog/_index.md").unwrap());
+        let res = get_section.call(&args).unwrap();
+        assert_eq!(res["content"], to_value("<p>The blog</p>").unwrap());
+
+        args.insert("metadata_only".to_string(), to_value(true).unwrap());
+        let res = get_section.call(&args).unwrap();
+        assert_eq!(res["title"], to_value("Blog").unwrap());
+        assert_eq!(res["content"], to_value("").unwrap());
+    }
+
     #[test]
     fn can_get_taxonomy() {
         let mut config = Config::default_for_test();
@@ -507,8 +642,15 @@ mod tests {
             TaxonomyConfig { name: "tags".to_string(), ..TaxonomyConfig::default() };
         config.slugify_taxonomies();
         let library = Arc::new(RwLock::new(Library::new(&config)));
-        let tag = TaxonomyTerm::new("Programming", &config.default_language, "tags", &[], &config);
-        let tag_fr = TaxonomyTerm::new("Programmation", "fr", "tags", &[], &config);
+        let tag = TaxonomyTerm::new(
+            "Programming",
+            &config.default_language,
+            "tags",
+            &[],
+            SortBy::Date,
+            &config,
+        );
+        let tag_fr = TaxonomyTerm::new("Programmation", "fr", "tags", &[], SortBy::Date, &config);
         let tags = Taxonomy {
             kind: taxo_config,
             lang: config.default_language.clone(),
@@ -578,8 +720,15 @@ mod tests {
         let taxo_config = TaxonomyConfig { name: "tags".to_string(), ..TaxonomyConfig::default() };
         let taxo_config_fr =
             TaxonomyConfig { name: "tags".to_string(), ..TaxonomyConfig::default() };
-        let tag = TaxonomyTerm::new("Programming", &config.default_language, "tags", &[], &config);
-        let tag_fr = TaxonomyTerm::new("Programmation", "fr", "tags", &[], &config);
+        let tag = TaxonomyTerm::new(
+            "Programming",
+            &config.default_language,
+            "tags",
+            &[],
+            SortBy::Date,
+            &config,
+        );
+        let tag_fr = TaxonomyTerm::new("Programmation", "fr", "tags", &[], SortBy::Date, &config);
         let tags = Taxonomy {
             kind: taxo_config,
             lang: config.default_language.clone(),
@@ -636,6 +785,143 @@ mod tests {
         assert!(static_fn.call(&args).is_err());
     }
 
+    #[test]
+    fn get_taxonomy_url_uses_the_slug_of_the_generated_term() {
+        let mut config = Config::default_for_test();
+        config.slugify.taxonomies = SlugifyStrategy::On;
+        let tag = TaxonomyTerm::new(
+            "Rôle Playing",
+            &config.default_language,
+            "tags",
+            &[],
+            SortBy::Date,
+            &config,
+        );
+        let tag_fr =
+            TaxonomyTerm::new("Éducation Populaire", "fr", "tags", &[], SortBy::Date, &config);
+        let tags = Taxonomy {
+            kind: TaxonomyConfig { name: "tags".to_string(), ..TaxonomyConfig::default() },
+            lang: config.default_language.clone(),
+            slug: "tags".to_string(),
+            path: "/tags/".to_string(),
+            permalink: "http://a-website.com/tags/".to_string(),
+            items: vec![tag.clone()],
+        };
+        let tags_fr = Taxonomy {
+            kind: TaxonomyConfig { name: "tags".to_string(), ..TaxonomyConfig::default() },
+            lang: "fr".to_owned(),
+            slug: "tags".to_string(),
+            path: "/fr/tags/".to_string(),
+            permalink: "http://a-website.com/fr/tags/".to_string(),
+            items: vec![tag_fr.clone()],
+        };
+        let static_fn = GetTaxonomyUrl::new(
+            &config.default_language,
+            &[tags, tags_fr],
+            config.slugify.taxonomies,
+        );
+
+        let mut args = HashMap::new();
+        args.insert("kind".to_string(), to_value("tags").unwrap());
+        args.insert("name".to_string(), to_value("Rôle Playing").unwrap());
+        assert_eq!(static_fn.call(&args).unwrap(), to_value(&tag.permalink).unwrap());
+        assert_eq!(tag.permalink, "http://a-website.com/tags/role-playing/");
+
+        let mut args = HashMap::new();
+        args.insert("kind".to_string(), to_value("tags").unwrap());
+        args.insert("name".to_string(), to_value("Éducation Populaire").unwrap());
+        args.insert("lang".to_string(), to_value("fr").unwrap());
+        assert_eq!(static_fn.call(&args).unwrap(), to_value(&tag_fr.permalink).unwrap());
+        assert_eq!(tag_fr.permalink, "http://a-website.com/fr/tags/education-populaire/");
+
+        // The term only exists in French
+        args.remove("lang");
+        assert!(static_fn.call(&args).is_err());
+    }
+
+    #[test]
+    fn get_taxonomy_url_uses_custom_slugs() {
+        let mut config = Config::default_for_test();
+        let taxo_config = TaxonomyConfig {
+            name: "tags".to_string(),
+            slug: "tags".to_string(),
+            slugs: HashMap::from([("c++".to_string(), "cpp".to_string())]),
+            ..TaxonomyConfig::default()
+        };
+        config.languages.get_mut("en").unwrap().taxonomies = vec![taxo_config.clone()];
+        let tag =
+            TaxonomyTerm::new("c++", &config.default_language, "tags", &[], SortBy::Date, &config);
+        assert_eq!(tag.permalink, "http://a-website.com/tags/cpp/");
+        let tags = Taxonomy {
+            kind: taxo_config,
+            lang: config.default_language.clone(),
+            slug: "tags".to_string(),
+            path: "/tags/".to_string(),
+            permalink: "http://a-website.com/tags/".to_string(),
+            items: vec![tag],
+        };
+        let static_fn =
+            GetTaxonomyUrl::new(&config.default_language, &[tags], config.slugify.taxonomies);
+
+        let mut args = HashMap::new();
+        args.insert("kind".to_string(), to_value("tags").unwrap());
+        args.insert("name".to_string(), to_value("c++").unwrap());
+        assert_eq!(
+            static_fn.call(&args).unwrap(),
+            to_value("http://a-website.com/tags/cpp/").unwrap()
+        );
+        args.insert("name".to_string(), to_value("c").unwrap());
+        assert!(static_fn.call(&args).is_err());
+    }
+
+    #[test]
+    fn get_taxonomy_url_keeps_hierarchical_terms_apart() {
+        let mut config = Config::default_for_test();
+        let taxo_config = TaxonomyConfig {
+            name: "categories".to_string(),
+            slug: "categories".to_string(),
+            hierarchical: true,
+            ..TaxonomyConfig::default()
+        };
+        config.languages.get_mut("en").unwrap().taxonomies = vec![taxo_config.clone()];
+        let items: Vec<_> = ["a/b", "a-b"]
+            .into_iter()
+            .map(|name| {
+                TaxonomyTerm::new_hierarchical(
+                    name,
+                    &config.default_language,
+                    "categories",
+                    &[],
+                    SortBy::Date,
+                    &config,
+                )
+            })
+            .collect();
+        let categories = Taxonomy {
+            kind: taxo_config,
+            lang: config.default_language.clone(),
+            slug: "categories".to_string(),
+            path: "/categories/".to_string(),
+            permalink: "http://a-website.com/categories/".to_string(),
+            items,
+        };
+        let static_fn =
+            GetTaxonomyUrl::new(&config.default_language, &[categories], config.slugify.taxonomies);
+
+        let mut args = HashMap::new();
+        args.insert("kind".to_string(), to_value("categories").unwrap());
+        args.insert("name".to_string(), to_value("a/b").unwrap());
+        assert_eq!(
+            static_fn.call(&args).unwrap(),
+            to_value("http://a-website.com/categories/a/b/").unwrap()
+        );
+        args.insert("name".to_string(), to_value("a-b").unwrap());
+        assert_eq!(
+            static_fn.call(&args).unwrap(),
+            to_value("http://a-website.com/categories/a-b/").unwrap()
+        );
+    }
+
     #[test]
     fn can_get_taxonomy_term() {
         let mut config = Config::default_for_test();
@@ -645,8 +931,15 @@ mod tests {
             TaxonomyConfig { name: "tags".to_string(), ..TaxonomyConfig::default() };
         config.slugify_taxonomies();
         let library = Arc::new(RwLock::new(Library::new(&config)));
-        let tag = TaxonomyTerm::new("Programming", &config.default_language, "tags", &[], &config);
-        let tag_fr = TaxonomyTerm::new("Programmation", "fr", "tags", &[], &config);
+        let tag = TaxonomyTerm::new(
+            "Programming",
+            &config.default_language,
+            "tags",
+            &[],
+            SortBy::Date,
+            &config,
+        );
+        let tag_fr = TaxonomyTerm::new("Programmation", "fr", "tags", &[], SortBy::Date, &config);
         let tags = Taxonomy {
             kind: taxo_config,
             lang: config.default_language.clone(),
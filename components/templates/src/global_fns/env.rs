@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::env;
+
+use libs::tera::{from_value, to_value, Function as TeraFn, Result, Value};
+
+/// Replaces the Tera `get_env` function so the variables that can be read are restricted
+/// by `allowed_env_prefixes` in the config
+#[derive(Debug)]
+pub struct GetEnv {
+    /// No restriction if `None`
+    allowed_prefixes: Option<Vec<String>>,
+}
+impl GetEnv {
+    pub fn new(allowed_prefixes: Option<Vec<String>>) -> Self {
+        Self { allowed_prefixes }
+    }
+}
+impl TeraFn for GetEnv {
+    fn call(&self, args: &HashMap<String, Value>) -> Result<Value> {
+        let name = required_arg!(String, args.get("name"), "`get_env` requires a `name` argument.");
+        let default =
+            optional_arg!(String, args.get("default"), "`get_env`: `default` must be a string.");
+        let required =
+            optional_arg!(bool, args.get("required"), "`get_env`: `required` must be a boolean.")
+                .unwrap_or(true);
+
+        if let Some(ref prefixes) = self.allowed_prefixes {
+            if !prefixes.iter().any(|prefix| name.starts_with(prefix)) {
+                return Err(format!(
+                "`get_env`: the environment variable `{}` doesn't start with one of the `allowed_env_prefixes` of the config",
+                name
+            )
+            .into());
+            }
+        }
+
+        match (env::var(&name), default) {
+            (Ok(value), _) => Ok(to_value(value).unwrap()),
+            (Err(_), Some(default)) => Ok(to_value(default).unwrap()),
+            (Err(_), None) if !required => Ok(to_value("").unwrap()),
+            (Err(_), None) => Err(format!(
+                "`get_env`: the environment variable `{}` is not set and no `default` was given",
+                name
+            )
+            .into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(static_fn: &GetEnv, values: &[(&str, Value)]) -> Result<Value> {
+        let args = values.iter().map(|(k, v)| (k.to_string(), v.clone())).collect();
+        static_fn.call(&args)
+    }
+
+    #[test]
+    fn can_get_a_set_variable() {
+        env::set_var("ZOLA_TEST_DEPLOY_ID", "abc123");
+        let static_fn = GetEnv::new(None);
+        let res = call(&static_fn, &[("name", to_value("ZOLA_TEST_DEPLOY_ID").unwrap())]);
+        assert_eq!(res.unwrap(), "abc123");
+        let res = call(
+            &static_fn,
+            &[
+                ("name", to_value("ZOLA_TEST_DEPLOY_ID").unwrap()),
+                ("default", to_value("unknown").unwrap()),
+            ],
+        );
+        assert_eq!(res.unwrap(), "abc123");
+    }
+
+    #[test]
+    fn can_use_default_for_unset_variable() {
+        let static_fn = GetEnv::new(None);
+        let res = call(
+            &static_fn,
+            &[
+                ("name", to_value("ZOLA_TEST_UNSET_WITH_DEFAULT").unwrap()),
+                ("default", to_value("unknown").unwrap()),
+            ],
+        );
+        assert_eq!(res.unwrap(), "unknown");
+        let res = call(
+            &static_fn,
+            &[
+                ("name", to_value("ZOLA_TEST_UNSET_WITH_DEFAULT").unwrap()),
+                ("required", to_value(false).unwrap()),
+            ],
+        );
+        assert_eq!(res.unwrap(), "");
+    }
+
+    #[test]
+    fn errors_on_unset_variable_without_default() {
+        let static_fn = GetEnv::new(None);
+        let res = call(&static_fn, &[("name", to_value("ZOLA_TEST_UNSET_NO_DEFAULT").unwrap())]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn errors_on_variable_not_allowed() {
+        env::set_var("ZOLA_TEST_SECRET", "hunter2");
+        let args = [
+            ("name", to_value("ZOLA_TEST_SECRET").unwrap()),
+            ("default", to_value("unknown").unwrap()),
+        ];
+        let static_fn = GetEnv::new(Some(vec!["ZOLA_TEST_PUBLIC_".to_string()]));
+        assert!(call(&static_fn, &args).is_err());
+        // An empty list allows none of them
+        let static_fn = GetEnv::new(Some(Vec::new()));
+        assert!(call(&static_fn, &args).is_err());
+    }
+}
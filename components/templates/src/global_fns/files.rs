@@ -11,7 +11,7 @@ use libs::sha2::{digest, Sha256, Sha384, Sha512};
 use libs::tera::{from_value, to_value, Function as TeraFn, Result, Value};
 use utils::site::resolve_internal_link;
 
-fn compute_hash<D: digest::Digest>(data: &[u8], as_base64: bool) -> String
+pub(crate) fn compute_hash<D: digest::Digest>(data: &[u8], as_base64: bool) -> String
 where
     digest::Output<D>: core::fmt::LowerHex,
     D: std::io::Write,
@@ -44,7 +44,12 @@ impl GetUrl {
     }
 }
 
-fn make_path_with_lang(path: String, lang: &str, config: &Config) -> Result<String> {
+fn make_path_with_lang(
+    path: String,
+    lang: &str,
+    config: &Config,
+    permalinks: &HashMap<String, String>,
+) -> Result<String> {
     if lang == config.default_language {
         return Ok(path);
     }
@@ -55,6 +60,31 @@ fn make_path_with_lang(path: String, lang: &str, config: &Config) -> Result<Stri
         );
     }
 
+    // With `internal_links_without_extension`, `@/blog/post` is `blog/post.{lang}.md`, or
+    // `blog/post/_index.{lang}.md` if there is no such page, like in `resolve_internal_link`
+    let (file, anchor) = match path.split_once('#') {
+        Some((file, anchor)) => (file, Some(anchor)),
+        None => (path.as_str(), None),
+    };
+    if config.internal_links_without_extension && !file.ends_with(".md") {
+        let file = file.trim_start_matches("@/").trim_end_matches('/');
+        let page = format!("{}.{}.md", file, lang);
+        let section = if file.is_empty() {
+            format!("_index.{}.md", lang)
+        } else {
+            format!("{}/_index.{}.md", file, lang)
+        };
+        let file = if !permalinks.contains_key(&page) && permalinks.contains_key(&section) {
+            section
+        } else {
+            page
+        };
+        return Ok(match anchor {
+            Some(anchor) => format!("@/{}#{}", file, anchor),
+            None => format!("@/{}", file),
+        });
+    }
+
     let mut split_path: Vec<String> = path.split('.').map(String::from).collect();
     let ilast = split_path.len() - 1;
     split_path[ilast] = format!("{}.{}", lang, split_path[ilast]);
@@ -85,16 +115,21 @@ impl TeraFn for GetUrl {
 
         // if it starts with @/, resolve it as an internal link
         if path.starts_with("@/") {
-            let path_with_lang = match make_path_with_lang(path, &lang, &self.config) {
-                Ok(x) => x,
-                Err(e) => return Err(e),
-            };
+            let path_with_lang =
+                match make_path_with_lang(path, &lang, &self.config, &self.permalinks) {
+                    Ok(x) => x,
+                    Err(e) => return Err(e),
+                };
 
-            match resolve_internal_link(&path_with_lang, &self.permalinks) {
+            match resolve_internal_link(
+                &path_with_lang,
+                &self.permalinks,
+                self.config.internal_links_without_extension,
+            ) {
                 Ok(resolved) => Ok(to_value(resolved.permalink).unwrap()),
-                Err(_) => Err(format!(
-                    "`get_url`: could not resolve URL for link `{}` not found.",
-                    path_with_lang
+                Err(err) => Err(format!(
+                    "`get_url`: could not resolve URL for link `{}`: {}",
+                    path_with_lang, err
                 )
                 .into()),
             }
@@ -437,6 +472,37 @@ title = "A title"
         );
     }
 
+    #[test]
+    fn can_get_url_without_extension_with_other_language() {
+        let mut config = Config::parse(CONFIG_DATA).unwrap();
+        config.internal_links_without_extension = true;
+        let mut permalinks = HashMap::new();
+        permalinks.insert(
+            "a_section/a_page.en.md".to_string(),
+            "https://remplace-par-ton-url.fr/en/a_section/a_page/".to_string(),
+        );
+        permalinks.insert(
+            "a_section/_index.en.md".to_string(),
+            "https://remplace-par-ton-url.fr/en/a_section/".to_string(),
+        );
+        permalinks
+            .insert("_index.en.md".to_string(), "https://remplace-par-ton-url.fr/en/".to_string());
+        let dir = create_temp_dir();
+        let static_fn = GetUrl::new(dir.path().to_path_buf(), config, permalinks, PathBuf::new());
+        for (path, expected) in [
+            ("@/a_section/a_page", "https://remplace-par-ton-url.fr/en/a_section/a_page/"),
+            ("@/a_section/a_page#top", "https://remplace-par-ton-url.fr/en/a_section/a_page/#top"),
+            ("@/a_section", "https://remplace-par-ton-url.fr/en/a_section/"),
+            ("@/a_section/", "https://remplace-par-ton-url.fr/en/a_section/"),
+            ("@/", "https://remplace-par-ton-url.fr/en/"),
+        ] {
+            let mut args = HashMap::new();
+            args.insert("path".to_string(), to_value(path).unwrap());
+            args.insert("lang".to_string(), to_value("en").unwrap());
+            assert_eq!(static_fn.call(&args).unwrap(), expected, "{}", path);
+        }
+    }
+
     #[test]
     fn does_not_duplicate_lang() {
         let config = Config::parse(CONFIG_DATA).unwrap();
@@ -1,9 +1,34 @@
 use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 
-use errors::{bail, Result};
+use errors::{bail, Context, Result};
+use libs::globset::GlobBuilder;
+use libs::walkdir::WalkDir;
 use utils::fs::is_path_in_directory;
 
+/// Whether that path is a glob pattern like `data/authors/*.toml` rather than a single file
+pub fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '[', '{'])
+}
+
+/// Returns `search_paths` for search_for_file
+fn get_search_paths(base_path: &Path, theme: &Option<String>, output_path: &Path) -> Vec<PathBuf> {
+    let mut search_paths =
+        vec![base_path.join("static"), base_path.join("content"), base_path.join(output_path)];
+    if let Some(t) = theme {
+        search_paths.push(base_path.join("themes").join(t).join("static"));
+    }
+    search_paths
+}
+
+fn get_actual_path(path: &str) -> Cow<'_, str> {
+    if path.starts_with("@/") {
+        Cow::Owned(path.replace("@/", "content/"))
+    } else {
+        Cow::Borrowed(path.trim_start_matches('/'))
+    }
+}
+
 /// This is used by a few Tera functions to search for files on the filesystem.
 /// This does try to find the file in 5 different spots:
 /// 1. base_path + path
@@ -21,16 +46,8 @@ pub fn search_for_file(
     theme: &Option<String>,
     output_path: &Path,
 ) -> Result<Option<(PathBuf, String)>> {
-    let mut search_paths =
-        vec![base_path.join("static"), base_path.join("content"), base_path.join(output_path)];
-    if let Some(t) = theme {
-        search_paths.push(base_path.join("themes").join(t).join("static"));
-    }
-    let actual_path = if path.starts_with("@/") {
-        Cow::Owned(path.replace("@/", "content/"))
-    } else {
-        Cow::Borrowed(path.trim_start_matches('/'))
-    };
+    let search_paths = get_search_paths(base_path, theme, output_path);
+    let actual_path = get_actual_path(path);
 
     let mut file_path = base_path.join(&*actual_path);
     let mut file_exists = file_path.exists();
@@ -57,3 +74,60 @@ pub fn search_for_file(
         Ok(None)
     }
 }
+
+/// Finds the files matching a glob pattern, looking in the same places as `search_for_file`:
+/// the first of them having some matching files is used.
+/// A `*` doesn't match the `/` so `data/*.toml` only matches the files of the `data` directory.
+/// The files are sorted by path.
+pub fn search_for_files_matching(
+    base_path: &Path,
+    pattern: &str,
+    theme: &Option<String>,
+    output_path: &Path,
+) -> Result<Vec<PathBuf>> {
+    let actual_pattern = get_actual_path(pattern);
+    // We only need to walk the directory before the first component with a glob
+    let mut directory = PathBuf::new();
+    let mut glob_components = Vec::new();
+    for component in actual_pattern.split('/') {
+        if glob_components.is_empty() && !is_glob(component) {
+            directory.push(component);
+        } else {
+            glob_components.push(component);
+        }
+    }
+    let matcher = GlobBuilder::new(&glob_components.join("/"))
+        .literal_separator(true)
+        .build()
+        .with_context(|| format!("Invalid glob {:?}", pattern))?
+        .compile_matcher();
+
+    let mut roots = vec![base_path.to_path_buf()];
+    roots.extend(get_search_paths(base_path, theme, output_path));
+    for root in roots {
+        let dir = root.join(&directory);
+        if !dir.is_dir() {
+            continue;
+        }
+        let mut files: Vec<_> = WalkDir::new(&dir)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .filter(|p| p.strip_prefix(&dir).map(|rel| matcher.is_match(rel)).unwrap_or(false))
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+        for file in &files {
+            if !is_path_in_directory(base_path, file)? {
+                bail!("{:?} is not inside the base site directory {:?}", file, base_path);
+            }
+        }
+        files.sort();
+        return Ok(files);
+    }
+
+    Ok(Vec::new())
+}
@@ -62,8 +62,22 @@ impl TeraFn for ResizeImage {
         }
         let resize_op = imageproc::ResizeOperation::from_args(&op, width, height)
             .map_err(|e| format!("`resize_image`: {}", e))?;
+        let rotate = optional_arg!(
+            u32,
+            args.get("rotate"),
+            "`resize_image`: `rotate` must be 90, 180 or 270"
+        );
+        let grayscale = optional_arg!(
+            bool,
+            args.get("grayscale"),
+            "`resize_image`: `grayscale` must be a boolean (true or false)"
+        )
+        .unwrap_or(false);
+        let blur = optional_arg!(f32, args.get("blur"), "`resize_image`: `blur` must be a number");
+        let post = imageproc::PostProcessing::from_args(rotate, grayscale, blur)
+            .map_err(|e| format!("`resize_image`: {}", e))?;
         let mut imageproc = self.imageproc.lock().unwrap();
-        let (file_path, unified_path) =
+        let (file_path, _) =
             match search_for_file(&self.base_path, &path, &self.theme, &self.output_path)
                 .map_err(|e| format!("`resize_image`: {}", e))?
             {
@@ -74,7 +88,7 @@ impl TeraFn for ResizeImage {
             };
 
         let response = imageproc
-            .enqueue(resize_op, unified_path, file_path, &format, quality)
+            .enqueue(resize_op, post, file_path, &format, quality)
             .map_err(|e| format!("`resize_image`: {}", e))?;
 
         to_value(response).map_err(Into::into)
@@ -137,9 +151,115 @@ impl TeraFn for GetImageMetadata {
     }
 }
 
+static DEFAULT_COLORS_COUNT: usize = 5;
+
+#[derive(Debug)]
+pub struct GetImageColors {
+    /// The base path of the Zola site
+    base_path: PathBuf,
+    theme: Option<String>,
+    result_cache: Arc<Mutex<HashMap<(String, usize), Value>>>,
+    output_path: PathBuf,
+}
+
+impl GetImageColors {
+    pub fn new(base_path: PathBuf, theme: Option<String>, output_path: PathBuf) -> Self {
+        Self { base_path, result_cache: Arc::new(Mutex::new(HashMap::new())), theme, output_path }
+    }
+}
+
+impl TeraFn for GetImageColors {
+    fn call(&self, args: &HashMap<String, Value>) -> Result<Value> {
+        let path = required_arg!(
+            String,
+            args.get("path"),
+            "`get_image_colors` requires a `path` argument with a string value"
+        );
+        let count = optional_arg!(
+            usize,
+            args.get("count"),
+            "`get_image_colors`: `count` must be a positive integer"
+        )
+        .unwrap_or(DEFAULT_COLORS_COUNT);
+        if count == 0 {
+            return Err("`get_image_colors`: `count` must be a positive integer".into());
+        }
+
+        let (src_path, unified_path) =
+            match search_for_file(&self.base_path, &path, &self.theme, &self.output_path)
+                .map_err(|e| format!("`get_image_colors`: {}", e))?
+            {
+                Some(f) => f,
+                None => {
+                    return Err(format!("`get_image_colors`: Cannot find path: {}", path).into());
+                }
+            };
+
+        let mut cache = self.result_cache.lock().expect("result cache lock");
+        let key = (unified_path, count);
+        if let Some(cached_result) = cache.get(&key) {
+            return Ok(cached_result.clone());
+        }
+
+        let colors = imageproc::get_image_colors(&src_path, count)
+            .map_err(|e| format!("`get_image_colors`: {}", e))?;
+        let out = to_value(colors).unwrap();
+        cache.insert(key, out.clone());
+
+        Ok(out)
+    }
+}
+
+#[derive(Debug)]
+pub struct GetImageLqip {
+    /// The base path of the Zola site
+    base_path: PathBuf,
+    theme: Option<String>,
+    result_cache: Arc<Mutex<HashMap<String, Value>>>,
+    output_path: PathBuf,
+}
+
+impl GetImageLqip {
+    pub fn new(base_path: PathBuf, theme: Option<String>, output_path: PathBuf) -> Self {
+        Self { base_path, result_cache: Arc::new(Mutex::new(HashMap::new())), theme, output_path }
+    }
+}
+
+impl TeraFn for GetImageLqip {
+    fn call(&self, args: &HashMap<String, Value>) -> Result<Value> {
+        let path = required_arg!(
+            String,
+            args.get("path"),
+            "`get_image_lqip` requires a `path` argument with a string value"
+        );
+
+        let (src_path, unified_path) =
+            match search_for_file(&self.base_path, &path, &self.theme, &self.output_path)
+                .map_err(|e| format!("`get_image_lqip`: {}", e))?
+            {
+                Some(f) => f,
+                None => {
+                    return Err(format!("`get_image_lqip`: Cannot find path: {}", path).into());
+                }
+            };
+
+        let mut cache = self.result_cache.lock().expect("result cache lock");
+        if let Some(cached_result) = cache.get(&unified_path) {
+            return Ok(cached_result.clone());
+        }
+
+        let uri =
+            imageproc::get_image_lqip(&src_path).map_err(|e| format!("`get_image_lqip`: {}", e))?;
+        let out = to_value(uri).unwrap();
+        cache.insert(unified_path, out.clone());
+
+        Ok(out)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{GetImageMetadata, ResizeImage};
+    use super::{GetImageColors, GetImageLqip, GetImageMetadata, ResizeImage};
 
     use std::collections::HashMap;
     use std::fs::{copy, create_dir_all};
@@ -184,7 +304,8 @@ mod tests {
         args.insert("height".to_string(), to_value(40).unwrap());
         args.insert("width".to_string(), to_value(40).unwrap());
 
-        // hashing is stable based on filepath and params so we can compare with hashes
+        // hashing is stable based on the image content and params so we can compare with hashes,
+        // all the images below are the same file
 
         // 1. resizing an image in static
         args.insert("path".to_string(), to_value("static/gutenberg.jpg").unwrap());
@@ -193,13 +314,11 @@ mod tests {
 
         assert_eq!(
             data["static_path"],
-            to_value(&format!("{}", static_path.join("gutenberg.da10f4be4f1c441e.jpg").display()))
-                .unwrap()
+            to_value(&format!("{}", static_path.join("e0228a8dce1c0c13.jpg").display())).unwrap()
         );
         assert_eq!(
             data["url"],
-            to_value("http://a-website.com/processed_images/gutenberg.da10f4be4f1c441e.jpg")
-                .unwrap()
+            to_value("http://a-website.com/processed_images/e0228a8dce1c0c13.jpg").unwrap()
         );
 
         // 2. resizing an image in content with a relative path
@@ -207,13 +326,11 @@ mod tests {
         let data = static_fn.call(&args).unwrap().as_object().unwrap().clone();
         assert_eq!(
             data["static_path"],
-            to_value(&format!("{}", static_path.join("gutenberg.3301b37eed389d2e.jpg").display()))
-                .unwrap()
+            to_value(&format!("{}", static_path.join("e0228a8dce1c0c13.jpg").display())).unwrap()
         );
         assert_eq!(
             data["url"],
-            to_value("http://a-website.com/processed_images/gutenberg.3301b37eed389d2e.jpg")
-                .unwrap()
+            to_value("http://a-website.com/processed_images/e0228a8dce1c0c13.jpg").unwrap()
         );
 
         // 3. resizing with an absolute path is the same as the above
@@ -231,12 +348,11 @@ mod tests {
         let data = static_fn.call(&args).unwrap().as_object().unwrap().clone();
         assert_eq!(
             data["static_path"],
-            to_value(&format!("{}", static_path.join("asset.d2fde9a750b68471.jpg").display()))
-                .unwrap()
+            to_value(&format!("{}", static_path.join("e0228a8dce1c0c13.jpg").display())).unwrap()
         );
         assert_eq!(
             data["url"],
-            to_value("http://a-website.com/processed_images/asset.d2fde9a750b68471.jpg").unwrap()
+            to_value("http://a-website.com/processed_images/e0228a8dce1c0c13.jpg").unwrap()
         );
 
         // 6. Looking up a file in the theme
@@ -244,13 +360,11 @@ mod tests {
         let data = static_fn.call(&args).unwrap().as_object().unwrap().clone();
         assert_eq!(
             data["static_path"],
-            to_value(&format!("{}", static_path.join("in-theme.9b0d29e07d588b60.jpg").display()))
-                .unwrap()
+            to_value(&format!("{}", static_path.join("e0228a8dce1c0c13.jpg").display())).unwrap()
         );
         assert_eq!(
             data["url"],
-            to_value("http://a-website.com/processed_images/in-theme.9b0d29e07d588b60.jpg")
-                .unwrap()
+            to_value("http://a-website.com/processed_images/e0228a8dce1c0c13.jpg").unwrap()
         );
     }
 
@@ -299,4 +413,41 @@ mod tests {
         assert_eq!(data["format"], to_value("jpg").unwrap());
         assert_eq!(data["mime"], to_value("image/jpeg").unwrap());
     }
+
+    #[test]
+    fn can_get_image_colors() {
+        let dir = create_dir_with_image();
+        let static_fn = GetImageColors::new(dir.path().to_path_buf(), None, PathBuf::new());
+
+        let mut args = HashMap::new();
+        args.insert("path".to_string(), to_value("static/gutenberg.jpg").unwrap());
+        let data = static_fn.call(&args).unwrap();
+        assert_eq!(data.as_array().unwrap().len(), 5);
+        // Same result with the same file through a different path
+        args.insert("path".to_string(), to_value("/static/gutenberg.jpg").unwrap());
+        assert_eq!(static_fn.call(&args).unwrap(), data);
+
+        args.insert("count".to_string(), to_value(2).unwrap());
+        let data2 = static_fn.call(&args).unwrap();
+        assert_eq!(data2.as_array().unwrap()[..], data.as_array().unwrap()[..2]);
+
+        args.insert("count".to_string(), to_value(0).unwrap());
+        assert!(static_fn.call(&args).is_err());
+    }
+
+    #[test]
+    fn can_get_image_lqip() {
+        let dir = create_dir_with_image();
+        let static_fn = GetImageLqip::new(dir.path().to_path_buf(), None, PathBuf::new());
+
+        let mut args = HashMap::new();
+        args.insert("path".to_string(), to_value("content/gutenberg.jpg").unwrap());
+        let data = static_fn.call(&args).unwrap();
+        let uri = data.as_str().unwrap();
+        assert!(uri.starts_with("data:image/webp;base64,"));
+        assert!(uri.len() <= imageproc::MAX_LQIP_SIZE);
+
+        args.insert("path".to_string(), to_value("content/missing.jpg").unwrap());
+        assert!(static_fn.call(&args).is_err());
+    }
 }
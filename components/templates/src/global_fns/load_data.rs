@@ -8,6 +8,7 @@ use std::sync::{Arc, Mutex};
 use libs::csv::Reader;
 use libs::reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
 use libs::reqwest::{blocking::Client, header};
+use libs::sha2::Sha256;
 use libs::tera::{
     from_value, to_value, Error, Error as TeraError, Function as TeraFn, Map, Result, Value,
 };
@@ -16,7 +17,8 @@ use libs::{nom_bibtex, serde_json, serde_yaml, toml};
 use utils::de::fix_toml_dates;
 use utils::fs::{get_file_time, read_file};
 
-use crate::global_fns::helpers::search_for_file;
+use crate::global_fns::compute_hash;
+use crate::global_fns::helpers::{is_glob, search_for_file, search_for_files_matching};
 
 static GET_DATA_ARGUMENT_ERROR_MESSAGE: &str =
     "`load_data`: requires EITHER a `path`, `url`, or `literal` argument";
@@ -140,6 +142,7 @@ impl DataSource {
         post_body: &Option<String>,
         post_content_type: &Option<String>,
         headers: &Option<Vec<String>>,
+        sha256: &Option<String>,
     ) -> u64 {
         let mut hasher = DefaultHasher::new();
         format.hash(&mut hasher);
@@ -147,6 +150,7 @@ impl DataSource {
         post_body.hash(&mut hasher);
         post_content_type.hash(&mut hasher);
         headers.hash(&mut hasher);
+        sha256.hash(&mut hasher);
         self.hash(&mut hasher);
         hasher.finish()
     }
@@ -252,6 +256,18 @@ impl TeraFn for LoadData {
             "`load_data`: `required` must be a boolean (true or false)"
         )
         .unwrap_or(true);
+        let sha256 = optional_arg!(
+            String,
+            args.get("sha256"),
+            "`load_data`: `sha256` must be the hex-encoded SHA-256 hash of the data"
+        )
+        .map(|hash| hash.to_lowercase());
+        let use_cache = optional_arg!(
+            bool,
+            args.get("cache"),
+            "`load_data`: `cache` must be a boolean (true or false)"
+        )
+        .unwrap_or(true);
         // Remote URL parameters only
         let post_body_arg =
             optional_arg!(String, args.get("body"), "`load_data` body must be a string, if set.");
@@ -279,6 +295,26 @@ impl TeraFn for LoadData {
             "`load_data`: `headers` needs to be an argument with a list of strings of format <name>=<value>."
         );
 
+        if let Some(ref path) = path_arg {
+            // A file can have glob characters in its name, eg `data/[draft].toml`
+            let is_file = matches!(
+                search_for_file(&self.base_path, path, &self.theme, &self.output_path),
+                Ok(Some(_))
+            );
+            if is_glob(path) && !is_file {
+                if url_arg.is_some() || literal_arg.is_some() {
+                    return Err(GET_DATA_ARGUMENT_ERROR_MESSAGE.into());
+                }
+                let as_map = optional_arg!(
+                    bool,
+                    args.get("as_map"),
+                    "`load_data`: `as_map` must be a boolean (true or false)"
+                )
+                .unwrap_or(false);
+                return self.load_glob(path, format_arg, required, as_map);
+            }
+        }
+
         // If the file doesn't exist, source is None
         let data_source = match (
             DataSource::from_args(
@@ -317,11 +353,14 @@ impl TeraFn for LoadData {
             &post_body_arg,
             &post_content_type,
             &headers,
+            &sha256,
         );
 
         let mut cache = self.result_cache.lock().expect("result cache lock");
-        if let Some(cached_result) = cache.get(&cache_key) {
-            return Ok(cached_result.clone());
+        if use_cache {
+            if let Some(cached_result) = cache.get(&cache_key) {
+                return Ok(cached_result.clone());
+            }
         }
 
         let data = match data_source {
@@ -385,24 +424,109 @@ impl TeraFn for LoadData {
             DataSource::Literal(string_literal) => Ok(string_literal),
         }?;
 
-        let result_value: Result<Value> = match file_format {
-            OutputFormat::Toml => load_toml(data),
-            OutputFormat::Csv => load_csv(data),
-            OutputFormat::Json => load_json(data),
-            OutputFormat::Bibtex => load_bibtex(data),
-            OutputFormat::Xml => load_xml(data),
-            OutputFormat::Yaml => load_yaml(data),
-            OutputFormat::Plain => to_value(data).map_err(|e| e.into()),
-        };
+        if let Some(expected) = sha256 {
+            let actual = compute_hash::<Sha256>(data.as_bytes(), false);
+            if actual != expected {
+                return Err(format!(
+                    "`load_data`: the SHA-256 hash of the data is {} but {} was expected",
+                    actual, expected
+                )
+                .into());
+            }
+        }
 
-        if let Ok(data_result) = &result_value {
-            cache.insert(cache_key, data_result.clone());
+        let result_value = parse_data(data, file_format);
+
+        if use_cache {
+            if let Ok(data_result) = &result_value {
+                cache.insert(cache_key, data_result.clone());
+            }
         }
 
         result_value
     }
 }
 
+impl LoadData {
+    /// Loads all the files matching a glob given as `path`, each parsed according to its extension
+    /// unless `format` is given. They are returned as an array sorted by path or, with `as_map`,
+    /// as a map keyed by their filename without the extension.
+    fn load_glob(
+        &self,
+        pattern: &str,
+        format_arg: Option<String>,
+        required: bool,
+        as_map: bool,
+    ) -> Result<Value> {
+        let files =
+            search_for_files_matching(&self.base_path, pattern, &self.theme, &self.output_path)
+                .map_err(|e| format!("`load_data`: {}", e))?;
+        if files.is_empty() {
+            if !required {
+                return Ok(Value::Null);
+            }
+            return Err(format!("`load_data`: no files match {}", pattern).into());
+        }
+
+        let mut values = Vec::with_capacity(files.len());
+        let mut map = Map::new();
+        for path in files {
+            let data_source = DataSource::Path(path.clone());
+            let file_format = get_output_format_from_args(format_arg.clone(), &data_source)?;
+            let cache_key =
+                data_source.get_cache_key(&file_format, Method::Get, &None, &None, &None, &None);
+
+            let mut cache = self.result_cache.lock().expect("result cache lock");
+            let value = match cache.get(&cache_key) {
+                Some(cached_result) => cached_result.clone(),
+                None => {
+                    let data = read_file(&path).map_err(|e| {
+                        format!("`load_data`: error reading file {:?}: {}", path, e)
+                    })?;
+                    let value = parse_data(data, file_format).map_err(|e| {
+                        Error::chain(format!("`load_data`: failed to load {:?}", path), e)
+                    })?;
+                    cache.insert(cache_key, value.clone());
+                    value
+                }
+            };
+
+            if as_map {
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+                if map.contains_key(&stem) {
+                    return Err(format!(
+                        "`load_data`: several files matching {} are named {}, they can't be loaded with `as_map`",
+                        pattern, stem
+                    )
+                    .into());
+                }
+                map.insert(stem, value);
+            } else {
+                values.push(value);
+            }
+        }
+
+        if as_map {
+            Ok(Value::Object(map))
+        } else {
+            Ok(Value::Array(values))
+        }
+    }
+}
+
+/// Parse the data according to its format and convert it to a Tera Value
+fn parse_data(data: String, format: OutputFormat) -> Result<Value> {
+    match format {
+        OutputFormat::Toml => load_toml(data),
+        OutputFormat::Csv => load_csv(data),
+        OutputFormat::Json => load_json(data),
+        OutputFormat::Bibtex => load_bibtex(data),
+        OutputFormat::Xml => load_xml(data),
+        OutputFormat::Yaml => load_yaml(data),
+        OutputFormat::Plain => to_value(data).map_err(|e| e.into()),
+    }
+}
+
 /// Parse a JSON string and convert it to a Tera Value
 fn load_json(json_data: String) -> Result<Value> {
     let json_content: Value =
@@ -770,6 +894,7 @@ mod tests {
             &None,
             &None,
             &Some(vec![]),
+            &None,
         );
         let cache_key_2 = DataSource::Path(get_test_file("test.toml")).get_cache_key(
             &OutputFormat::Toml,
@@ -777,6 +902,7 @@ mod tests {
             &None,
             &None,
             &Some(vec![]),
+            &None,
         );
         assert_eq!(cache_key, cache_key_2);
     }
@@ -789,6 +915,7 @@ mod tests {
             &None,
             &None,
             &Some(vec![]),
+            &None,
         );
         let json_cache_key = DataSource::Path(get_test_file("test.json")).get_cache_key(
             &OutputFormat::Toml,
@@ -796,6 +923,7 @@ mod tests {
             &None,
             &None,
             &Some(vec![]),
+            &None,
         );
         assert_ne!(toml_cache_key, json_cache_key);
     }
@@ -808,6 +936,7 @@ mod tests {
             &None,
             &None,
             &Some(vec![]),
+            &None,
         );
         let json_cache_key = DataSource::Path(get_test_file("test.toml")).get_cache_key(
             &OutputFormat::Json,
@@ -815,6 +944,7 @@ mod tests {
             &None,
             &None,
             &Some(vec![]),
+            &None,
         );
         assert_ne!(toml_cache_key, json_cache_key);
     }
@@ -827,6 +957,7 @@ mod tests {
             &None,
             &None,
             &Some(vec!["a=b".to_string()]),
+            &None,
         );
         let header2_cache_key = DataSource::Path(get_test_file("test.toml")).get_cache_key(
             &OutputFormat::Json,
@@ -834,6 +965,7 @@ mod tests {
             &None,
             &None,
             &Some(vec![]),
+            &None,
         );
         assert_ne!(header1_cache_key, header2_cache_key);
     }
@@ -946,6 +1078,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_load_glob_of_files_sorted_by_path() {
+        let static_fn = LoadData::new(PathBuf::from("../utils/test-files"), None, PathBuf::new());
+        let mut args = HashMap::new();
+        args.insert("path".to_string(), to_value("authors/*.toml").unwrap());
+        let result = static_fn.call(&args).unwrap();
+
+        assert_eq!(
+            result,
+            json!([
+                {"name": "Alice", "role": "editor"},
+                {"name": "Bob", "role": "writer"},
+                {"name": "Carol", "role": "illustrator"},
+            ])
+        );
+    }
+
+    #[test]
+    fn can_load_glob_of_files_as_map() {
+        let static_fn = LoadData::new(PathBuf::from("../utils/test-files"), None, PathBuf::new());
+        let mut args = HashMap::new();
+        args.insert("path".to_string(), to_value("/authors/[ab]*.toml").unwrap());
+        args.insert("as_map".to_string(), to_value(true).unwrap());
+        let result = static_fn.call(&args).unwrap();
+
+        assert_eq!(
+            result,
+            json!({
+                "alice": {"name": "Alice", "role": "editor"},
+                "bob": {"name": "Bob", "role": "writer"},
+            })
+        );
+    }
+
+    #[test]
+    fn glob_without_matches_errors_unless_not_required() {
+        let static_fn = LoadData::new(PathBuf::from("../utils/test-files"), None, PathBuf::new());
+        let mut args = HashMap::new();
+        args.insert("path".to_string(), to_value("authors/*.json").unwrap());
+        let result = static_fn.call(&args);
+        assert_eq!(result.unwrap_err().to_string(), "`load_data`: no files match authors/*.json");
+
+        args.insert("required".to_string(), to_value(false).unwrap());
+        assert_eq!(static_fn.call(&args).unwrap(), json!(null));
+    }
+
+    #[test]
+    fn loads_the_file_named_like_a_glob_if_it_exists() {
+        let dir = tempdir().unwrap();
+        create_dir_all(dir.path().join("data")).unwrap();
+        std::fs::write(dir.path().join("data").join("[draft].toml"), "name = \"Draft\"").unwrap();
+        std::fs::write(dir.path().join("data").join("d.toml"), "name = \"D\"").unwrap();
+
+        let static_fn = LoadData::new(dir.path().to_path_buf(), None, PathBuf::new());
+        let mut args = HashMap::new();
+        args.insert("path".to_string(), to_value("data/[draft].toml").unwrap());
+        assert_eq!(static_fn.call(&args).unwrap(), json!({"name": "Draft"}));
+        // It's still a glob if there is no such file
+        args.insert("path".to_string(), to_value("data/[d].toml").unwrap());
+        assert_eq!(static_fn.call(&args).unwrap(), json!([{"name": "D"}]));
+    }
+
     #[test]
     fn unknown_extension_defaults_to_plain() {
         let static_fn = LoadData::new(PathBuf::from("../utils/test-files"), None, PathBuf::new());
@@ -1177,6 +1371,69 @@ mod tests {
         _mjson.assert();
     }
 
+    #[test]
+    fn can_verify_sha256_of_remote_data() {
+        let _m = mock("GET", "/u8vbq3kfnt")
+            .with_header("content-type", "text/plain")
+            .with_body("Hello World")
+            .expect(1)
+            .create();
+        let url = format!("{}{}", mockito::server_url(), "/u8vbq3kfnt");
+
+        let static_fn = LoadData::new(PathBuf::from("../utils"), None, PathBuf::new());
+        let mut args = HashMap::new();
+        args.insert("url".to_string(), to_value(&url).unwrap());
+        args.insert(
+            "sha256".to_string(),
+            to_value("A591A6D40BF420404A011733CFB7B190D62C65BF0BCDA32B57B277D9AD9F146E").unwrap(),
+        );
+        assert_eq!(static_fn.call(&args).unwrap(), "Hello World");
+        // The verified data is cached
+        assert_eq!(static_fn.call(&args).unwrap(), "Hello World");
+
+        _m.assert();
+    }
+
+    #[test]
+    fn fails_when_sha256_of_remote_data_does_not_match() {
+        let _m = mock("GET", "/p5xcr7hmwe")
+            .with_header("content-type", "text/plain")
+            .with_body("Hello World, changed")
+            .create();
+        let url = format!("{}{}", mockito::server_url(), "/p5xcr7hmwe");
+
+        let static_fn = LoadData::new(PathBuf::from("../utils"), None, PathBuf::new());
+        let mut args = HashMap::new();
+        args.insert("url".to_string(), to_value(&url).unwrap());
+        args.insert(
+            "sha256".to_string(),
+            to_value("a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e").unwrap(),
+        );
+        let result = static_fn.call(&args);
+        assert!(result.unwrap_err().to_string().ends_with(
+            "but a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e was expected"
+        ));
+    }
+
+    #[test]
+    fn can_skip_cache_of_remote_data() {
+        let _m = mock("GET", "/g2mzt8wqlo")
+            .with_header("content-type", "text/plain")
+            .with_body("Hello World")
+            .expect(2)
+            .create();
+        let url = format!("{}{}", mockito::server_url(), "/g2mzt8wqlo");
+
+        let static_fn = LoadData::new(PathBuf::from("../utils"), None, PathBuf::new());
+        let mut args = HashMap::new();
+        args.insert("url".to_string(), to_value(&url).unwrap());
+        args.insert("cache".to_string(), to_value(false).unwrap());
+        assert!(static_fn.call(&args).is_ok());
+        assert!(static_fn.call(&args).is_ok());
+
+        _m.assert();
+    }
+
     #[test]
     fn is_custom_headers_working() {
         let _mjson = mock("POST", "/kr1zdgbm4y4")
@@ -2,14 +2,23 @@
 mod macros;
 
 mod content;
+mod env;
 mod files;
 mod helpers;
 mod i18n;
 mod images;
 mod load_data;
+mod now;
+mod site_config;
 
-pub use self::content::{GetPage, GetSection, GetTaxonomy, GetTaxonomyTerm, GetTaxonomyUrl};
+pub use self::content::{
+    GetPage, GetRelatedPages, GetSection, GetTaxonomy, GetTaxonomyTerm, GetTaxonomyUrl,
+};
+pub use self::env::GetEnv;
+pub(crate) use self::files::compute_hash;
 pub use self::files::{GetHash, GetUrl};
 pub use self::i18n::Trans;
-pub use self::images::{GetImageMetadata, ResizeImage};
+pub use self::images::{GetImageColors, GetImageLqip, GetImageMetadata, ResizeImage};
 pub use self::load_data::LoadData;
+pub use self::now::Now;
+pub use self::site_config::GetConfig;
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use libs::tera::{from_value, to_value, Function as TeraFn, Result, Value};
+use libs::time::format_description::well_known::Rfc3339;
+use libs::time::OffsetDateTime;
+use utils::source_date;
+
+/// Replaces the Tera `now` function so it returns the `SOURCE_DATE_EPOCH` time if it is set,
+/// making the builds using it reproducible
+#[derive(Debug, Default)]
+pub struct Now {
+    source_date_epoch: Option<OffsetDateTime>,
+}
+impl Now {
+    pub fn new(source_date_epoch: Option<OffsetDateTime>) -> Self {
+        Self { source_date_epoch }
+    }
+}
+
+impl TeraFn for Now {
+    fn call(&self, args: &HashMap<String, Value>) -> Result<Value> {
+        let utc = optional_arg!(bool, args.get("utc"), "`now`: `utc` must be a boolean.")
+            .unwrap_or(false);
+        let timestamp =
+            optional_arg!(bool, args.get("timestamp"), "`now`: `timestamp` must be a boolean.")
+                .unwrap_or(false);
+
+        let now = source_date::now(self.source_date_epoch);
+        if timestamp {
+            return Ok(to_value(now.unix_timestamp()).unwrap());
+        }
+        let now = if utc { now } else { source_date::to_local(now) };
+        let formatted = now.format(&Rfc3339).map_err(|e| format!("`now`: {}", e))?;
+        Ok(to_value(formatted).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn now_honours_source_date_epoch() {
+        let now = Now::new(Some(OffsetDateTime::from_unix_timestamp(1700000000).unwrap()));
+        let mut args = HashMap::new();
+        args.insert("utc".to_string(), to_value(true).unwrap());
+        let utc = now.call(&args);
+        args.insert("timestamp".to_string(), to_value(true).unwrap());
+        let timestamp = now.call(&args);
+
+        assert_eq!(utc.unwrap(), to_value("2023-11-14T22:13:20Z").unwrap());
+        assert_eq!(timestamp.unwrap(), to_value(1700000000).unwrap());
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use config::Config;
+use libs::tera::{from_value, to_value, Function as TeraFn, Result, Value};
+
+/// Gets a value of the config, like `config` in the templates, from a dotted path such as
+/// `extra.analytics.id`, with a default for when it is not set
+#[derive(Debug)]
+pub struct GetConfig {
+    default_language: String,
+    /// The config serialized for each language
+    configs: HashMap<String, Value>,
+}
+impl GetConfig {
+    pub fn new(config: &Config) -> Self {
+        let configs = config
+            .languages
+            .keys()
+            .map(|lang| (lang.clone(), to_value(config.serialize(lang)).unwrap()))
+            .collect();
+        Self { default_language: config.default_language.clone(), configs }
+    }
+}
+
+/// Follows the dotted path in the value, the parts being keys of objects or indices of arrays
+fn get_by_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, part| match value {
+        Value::Object(map) => map.get(part),
+        Value::Array(items) => part.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+impl TeraFn for GetConfig {
+    fn call(&self, args: &HashMap<String, Value>) -> Result<Value> {
+        let key = required_arg!(String, args.get("key"), "`get_config` requires a `key` argument.");
+        let lang =
+            optional_arg!(String, args.get("lang"), "`get_config`: `lang` must be a string.")
+                .unwrap_or_else(|| self.default_language.clone());
+        let config = match self.configs.get(&lang) {
+            Some(c) => c,
+            None => {
+                return Err(format!("`get_config`: `{}` is not a language of the site", lang).into())
+            }
+        };
+
+        match (get_by_path(config, &key), args.get("default")) {
+            (Some(value), _) if !value.is_null() => Ok(value.clone()),
+            (_, Some(default)) => Ok(default.clone()),
+            _ => Err(format!(
+                "`get_config`: `{}` is not set in the config and no `default` was given",
+                key
+            )
+            .into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+base_url = "https://example.com"
+title = "My site"
+
+[extra.analytics]
+id = "UA-1234"
+hosts = ["a.example.com", "b.example.com"]
+
+[languages.fr]
+title = "Mon site"
+"#;
+
+    fn call(static_fn: &GetConfig, values: &[(&str, Value)]) -> Result<Value> {
+        let args = values.iter().map(|(k, v)| (k.to_string(), v.clone())).collect();
+        static_fn.call(&args)
+    }
+
+    #[test]
+    fn can_get_a_nested_value() {
+        let static_fn = GetConfig::new(&Config::parse(CONFIG).unwrap());
+        let res = call(&static_fn, &[("key", to_value("extra.analytics.id").unwrap())]);
+        assert_eq!(res.unwrap(), "UA-1234");
+        let res = call(
+            &static_fn,
+            &[
+                ("key", to_value("extra.analytics.hosts.1").unwrap()),
+                ("default", to_value("").unwrap()),
+            ],
+        );
+        assert_eq!(res.unwrap(), "b.example.com");
+        let res = call(
+            &static_fn,
+            &[("key", to_value("title").unwrap()), ("lang", to_value("fr").unwrap())],
+        );
+        assert_eq!(res.unwrap(), "Mon site");
+    }
+
+    #[test]
+    fn uses_the_default_for_missing_values() {
+        let static_fn = GetConfig::new(&Config::parse(CONFIG).unwrap());
+        for key in
+            ["extra.analytics.missing", "extra.analytics.id.nested", "extra.analytics.hosts.5"]
+        {
+            let res = call(
+                &static_fn,
+                &[("key", to_value(key).unwrap()), ("default", to_value(false).unwrap())],
+            );
+            assert_eq!(res.unwrap(), false);
+        }
+        let res = call(&static_fn, &[("key", to_value("extra.analytics.missing").unwrap())]);
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "`get_config`: `extra.analytics.missing` is not set in the config and no `default` was given"
+        );
+    }
+}
@@ -1,14 +1,14 @@
 pub mod filters;
 pub mod global_fns;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use config::Config;
 use libs::once_cell::sync::Lazy;
 use libs::tera::{Context, Tera};
 
 use errors::{bail, Context as ErrorContext, Result};
-use utils::templates::rewrite_theme_paths;
+use utils::templates::{rewrite_theme_paths, TEMPLATE_EXTENSIONS_GLOB};
 
 pub static ZOLA_TERA: Lazy<Tera> = Lazy::new(|| {
     let mut tera = Tera::default();
@@ -28,6 +28,8 @@ pub static ZOLA_TERA: Lazy<Tera> = Lazy::new(|| {
     .unwrap();
     tera.register_filter("base64_encode", filters::base64_encode);
     tera.register_filter("base64_decode", filters::base64_decode);
+    tera.register_filter("hash", filters::hash);
+    tera.register_filter("paginate", filters::paginate);
     tera.register_filter("regex_replace", filters::RegexReplaceFilter::new());
     tera
 });
@@ -43,8 +45,11 @@ pub fn render_redirect_template(url: &str, tera: &Tera) -> Result<String> {
 }
 
 pub fn load_tera(path: &Path, config: &Config) -> Result<Tera> {
-    let tpl_glob =
-        format!("{}/{}", path.to_string_lossy().replace('\\', "/"), "templates/**/*.{*ml,md}");
+    let tpl_glob = format!(
+        "{}/templates/**/*.{}",
+        path.to_string_lossy().replace('\\', "/"),
+        TEMPLATE_EXTENSIONS_GLOB
+    );
 
     // Only parsing as we might be extending templates from themes and that would error
     // as we haven't loaded them yet
@@ -59,9 +64,10 @@ pub fn load_tera(path: &Path, config: &Config) -> Result<Tera> {
         }
 
         let theme_tpl_glob = format!(
-            "{}/themes/{}/templates/**/*.{{*ml,md}}",
+            "{}/themes/{}/templates/**/*.{}",
             path.to_string_lossy().replace('\\', "/"),
-            theme
+            theme,
+            TEMPLATE_EXTENSIONS_GLOB
         );
         let mut tera_theme =
             Tera::parse(&theme_tpl_glob).context("Error parsing templates from themes")?;
@@ -85,3 +91,27 @@ pub fn load_tera(path: &Path, config: &Config) -> Result<Tera> {
 
     Ok(tera)
 }
+
+/// Makes the templates in `templates/variants/{variant}/` replace the templates of the same name,
+/// e.g. `templates/variants/compact/page.html` is used instead of `page.html`, so a site can be
+/// rendered with several variants of its templates to compare them
+pub fn apply_template_variant(tera: &mut Tera, variant: &str) -> Result<()> {
+    // The variant is also the name of the output directory of its site
+    if variant.is_empty() || variant.contains(['/', '\\']) || variant.contains("..") {
+        bail!("The template variant `{}` should be a name, not a path", variant);
+    }
+    let prefix = format!("variants/{}/", variant);
+    let overrides: Vec<(PathBuf, String)> = tera
+        .templates
+        .iter()
+        .filter_map(|(name, tpl)| {
+            let name = name.strip_prefix(&prefix)?;
+            Some((PathBuf::from(tpl.path.as_ref()?), name.to_string()))
+        })
+        .collect();
+    if overrides.is_empty() {
+        bail!("The template variant `{}` has no templates in `templates/{}`", variant, prefix);
+    }
+    tera.add_template_files(overrides.iter().map(|(path, name)| (path, Some(name))))
+        .with_context(|| format!("Failed to apply the template variant `{}`", variant))
+}
@@ -55,13 +55,14 @@ pub fn parse_yaml_datetime(date_string: &str) -> Result<time::OffsetDateTime> {
 
 /// Used as an attribute when we want to convert from TOML to a string date
 /// If a TOML datetime isn't present, it will accept a string and push it through
-/// TOML's date time parser to ensure only valid dates are accepted.
+/// TOML's date time parser to normalize it.
+/// Other strings are kept as is so they can be parsed with the `date_formats` of the config:
+/// the caller is responsible for rejecting them if they can't.
 /// Inspired by this proposal: <https://github.com/alexcrichton/toml-rs/issues/269>
 pub fn from_unknown_datetime<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    use serde::de::Error;
     use std::str::FromStr;
 
     #[derive(Deserialize)]
@@ -83,7 +84,7 @@ where
                 // _to_string.
                 Ok(Some(d.format(&Rfc3339).unwrap()))
             } else {
-                Err(D::Error::custom("Unable to parse datetime"))
+                Ok(Some(s))
             }
         }
     }
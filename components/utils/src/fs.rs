@@ -1,6 +1,8 @@
 use libs::filetime::{set_file_mtime, FileTime};
 use libs::globset::GlobSet;
 use libs::walkdir::WalkDir;
+
+use crate::globs::NestedGitignore;
 use std::fs::{copy, create_dir_all, metadata, remove_dir_all, remove_file, File};
 use std::io::prelude::*;
 use std::path::Path;
@@ -114,6 +116,7 @@ pub fn copy_directory(
     dest: &Path,
     hard_link: bool,
     ignore_globset: Option<&GlobSet>,
+    gitignore: Option<&NestedGitignore>,
 ) -> Result<()> {
     for entry in
         WalkDir::new(src).follow_links(true).into_iter().filter_map(std::result::Result::ok)
@@ -125,6 +128,11 @@ pub fn copy_directory(
                 continue;
             }
         }
+        if let Some(gitignore) = gitignore {
+            if gitignore.is_ignored(entry.path(), entry.file_type().is_dir()) {
+                continue;
+            }
+        }
 
         let target_path = dest.join(relative_path);
 
@@ -1,4 +1,8 @@
+use std::path::{Path, PathBuf};
+
 use libs::globset::{Glob, GlobSet, GlobSetBuilder};
+use libs::ignore::gitignore::{Gitignore, GitignoreBuilder};
+use libs::ignore::WalkBuilder;
 
 use errors::{bail, Result};
 
@@ -18,3 +22,115 @@ pub fn build_ignore_glob_set(ignore: &Vec<String>, name: &str) -> Result<GlobSet
     }
     Ok(glob_set_builder.build()?)
 }
+
+/// Compiles gitignore-style patterns, eg `node_modules/` or `!keep.swp`.
+/// The matcher expects paths relative to the directory the patterns apply to.
+pub fn build_ignore_gitignore(ignore: &[String], name: &str) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(".");
+    for pat in ignore {
+        if let Err(e) = builder.add_line(None, pat) {
+            bail!("Invalid ignored_{} pattern: {}, error = {}", name, pat, e);
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// All the `.gitignore` files applying to a directory: the ones in it and its subdirectories and
+/// the ones in its parents, up to the root of the git repository.
+/// Like with git, the `.gitignore` of the deepest directory takes precedence.
+#[derive(Clone, Debug, Default)]
+pub struct NestedGitignore {
+    /// `(directory, its .gitignore)`, sorted from the shallowest to the deepest directory
+    gitignores: Vec<(PathBuf, Gitignore)>,
+}
+
+impl NestedGitignore {
+    pub fn new(root: &Path) -> Result<Self> {
+        let mut paths = Vec::new();
+        if !root.join(".git").exists() {
+            for dir in root.ancestors().skip(1) {
+                if dir.join(".gitignore").is_file() {
+                    paths.push(dir.join(".gitignore"));
+                }
+                if dir.join(".git").exists() {
+                    break;
+                }
+            }
+        }
+
+        // No need to look for .gitignore files in ignored directories
+        let walker = WalkBuilder::new(root)
+            .standard_filters(false)
+            .git_ignore(true)
+            .require_git(false)
+            .filter_entry(|e| e.file_name() != ".git")
+            .build();
+        for entry in walker.filter_map(std::result::Result::ok) {
+            if entry.file_name() == ".gitignore" && entry.path().is_file() {
+                paths.push(entry.into_path());
+            }
+        }
+
+        let mut gitignores = Vec::with_capacity(paths.len());
+        for path in paths {
+            let dir = path.parent().unwrap().to_path_buf();
+            let mut builder = GitignoreBuilder::new(&dir);
+            if let Some(e) = builder.add(&path) {
+                bail!("Invalid .gitignore file {}: {}", path.display(), e);
+            }
+            gitignores.push((dir, builder.build()?));
+        }
+        gitignores.sort_by_key(|(dir, _)| dir.components().count());
+
+        Ok(Self { gitignores })
+    }
+
+    /// Whether git would ignore that path, which needs to be prefixed like the root directory
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        for (dir, gitignore) in self.gitignores.iter().rev() {
+            if !path.starts_with(dir) {
+                continue;
+            }
+            let m = gitignore.matched_path_or_any_parents(path, is_dir);
+            if m.is_ignore() {
+                return true;
+            }
+            if m.is_whitelist() {
+                return false;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::NestedGitignore;
+
+    #[test]
+    fn can_match_nested_gitignores() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let root = tmp_dir.path().join("site");
+        fs::create_dir_all(tmp_dir.path().join(".git")).unwrap();
+        fs::create_dir_all(root.join("content").join("blog")).unwrap();
+        fs::create_dir_all(root.join("node_modules")).unwrap();
+        fs::write(tmp_dir.path().join(".gitignore"), "*.log\n").unwrap();
+        fs::write(root.join(".gitignore"), "node_modules/\ndraft-*.md\n").unwrap();
+        fs::write(root.join("node_modules").join(".gitignore"), "!*.md\n").unwrap();
+        fs::write(root.join("content").join("blog").join(".gitignore"), "!draft-kept.md\n")
+            .unwrap();
+
+        let gitignore = NestedGitignore::new(&root).unwrap();
+        let content = root.join("content");
+        assert!(gitignore.is_ignored(&content.join("build.log"), false));
+        assert!(gitignore.is_ignored(&content.join("draft-post.md"), false));
+        assert!(gitignore.is_ignored(&content.join("blog").join("draft-post.md"), false));
+        assert!(!gitignore.is_ignored(&content.join("blog").join("draft-kept.md"), false));
+        assert!(!gitignore.is_ignored(&content.join("blog").join("post.md"), false));
+        assert!(gitignore.is_ignored(&root.join("node_modules"), true));
+        // The .gitignore files in ignored directories are not read
+        assert!(gitignore.is_ignored(&root.join("node_modules").join("README.md"), false));
+    }
+}
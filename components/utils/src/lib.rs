@@ -2,9 +2,11 @@ pub mod anchors;
 pub mod de;
 pub mod fs;
 pub mod globs;
+pub mod locales;
 pub mod net;
 pub mod site;
 pub mod slugs;
+pub mod source_date;
 pub mod table_of_contents;
 pub mod templates;
 pub mod types;
@@ -0,0 +1,52 @@
+use std::convert::TryFrom;
+
+use libs::pure_rust_locales::Locale;
+
+/// The locales to use for the languages whose country code is not the language code
+const LOCALES_BY_LANGUAGE: &[(&str, &str)] = &[
+    ("cs", "cs_CZ"),
+    ("da", "da_DK"),
+    ("el", "el_GR"),
+    ("en", "en_US"),
+    ("he", "he_IL"),
+    ("ja", "ja_JP"),
+    ("ko", "ko_KR"),
+    ("nb", "nb_NO"),
+    ("sv", "sv_SE"),
+    ("uk", "uk_UA"),
+    ("zh", "zh_CN"),
+];
+
+/// Finds the locale of a language code like `fr` or `pt-BR`
+pub fn find_locale(lang: &str) -> Option<Locale> {
+    let lang = lang.replace('-', "_");
+    if let Ok(locale) = Locale::try_from(lang.as_str()) {
+        return Some(locale);
+    }
+    if let Ok(locale) = Locale::try_from(format!("{}_{}", lang, lang.to_uppercase()).as_str()) {
+        return Some(locale);
+    }
+    LOCALES_BY_LANGUAGE
+        .iter()
+        .find(|(l, _)| *l == lang)
+        .and_then(|(_, locale)| Locale::try_from(*locale).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_find_locale_of_language_codes() {
+        for (lang, expected) in [
+            ("fr", Some("fr_FR")),
+            ("de", Some("de_DE")),
+            ("pt-BR", Some("pt_BR")),
+            ("en", Some("en_US")),
+            ("de_AT", Some("de_AT")),
+            ("tlh", None),
+        ] {
+            assert_eq!(find_locale(lang).map(|l| l.to_string()).as_deref(), expected);
+        }
+    }
+}
@@ -1,5 +1,13 @@
 use std::net::TcpListener;
 
+use errors::{bail, Result};
+use libs::once_cell::sync::Lazy;
+use libs::regex::{Captures, Regex};
+use libs::url::Url;
+
+static URL_ATTRIBUTE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(\s(?i:src|href)\s*=\s*)(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
 pub fn get_available_port(avoid: u16) -> Option<u16> {
     // Start after "well-known" ports (0–1023) as they require superuser
     // privileges on UNIX-like operating systems.
@@ -10,7 +18,124 @@ pub fn port_is_available(port: u16) -> bool {
     TcpListener::bind(("127.0.0.1", port)).is_ok()
 }
 
+/// Returns the port to use: the requested one if it's available.
+/// If the default port was requested and `scan` is set, another available port is used instead.
+pub fn resolve_port(port: u16, default_port: u16, scan: bool) -> Result<u16> {
+    if port_is_available(port) {
+        return Ok(port);
+    }
+    if port != default_port || !scan {
+        bail!("The requested port {} is not available", port);
+    }
+    match get_available_port(default_port) {
+        Some(p) => Ok(p),
+        None => bail!("No port available"),
+    }
+}
+
 /// Returns whether a link starts with an HTTP(s) scheme.
 pub fn is_external_link(link: &str) -> bool {
     link.starts_with("http:") || link.starts_with("https:")
 }
+
+/// Whether the URL starts with a scheme like `https:` or `mailto:`, or is protocol-relative
+pub fn has_scheme(url: &str) -> bool {
+    if url.starts_with("//") {
+        return true;
+    }
+    match url.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        }
+        None => false,
+    }
+}
+
+/// Splits the `base_url` into its scheme and host, and its path without the trailing slash
+pub fn split_base_url(base_url: &str) -> (&str, &str) {
+    let host_start = base_url.find("://").map_or(0, |i| i + 3);
+    let path_start = base_url[host_start..].find('/').map_or(base_url.len(), |i| host_start + i);
+    (&base_url[..path_start], base_url[path_start..].trim_end_matches('/'))
+}
+
+/// Whether the root-relative URL is in the path of the `base_url`, like `/docs/blog/` for
+/// `https://example.com/docs`
+fn is_in_base_path(url: &str, base_path: &str) -> bool {
+    !base_path.is_empty()
+        && url
+            .strip_prefix(base_path)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?', '#']))
+}
+
+/// Prepends the `base_url` to a path of the site, leaving the URLs with a scheme and the
+/// root-relative ones already in the path of the `base_url` as they are
+pub fn make_absolute_url(base_url: &str, url: &str) -> String {
+    if has_scheme(url) {
+        return url.to_string();
+    }
+    let (origin, base_path) = split_base_url(base_url);
+    if is_in_base_path(url, base_path) {
+        return format!("{}{}", origin, url);
+    }
+    format!("{}/{}", base_url.trim_end_matches('/'), url.trim_start_matches('/'))
+}
+
+/// Rewrites the relative `src` and `href` attributes of the HTML into absolute URLs, for when it
+/// is displayed outside of the site like in feed readers. The root-relative URLs are treated like
+/// `make_absolute_url` does and the others are resolved against `page_url`.
+/// The URLs with a scheme, including the `data:` ones, are left as they are.
+pub fn make_html_urls_absolute(html: &str, base_url: &str, page_url: &str) -> String {
+    let page_url = Url::parse(page_url).ok();
+    URL_ATTRIBUTE_RE
+        .replace_all(html, |caps: &Captures| {
+            let (url, quote) = match caps.get(2) {
+                Some(url) => (url.as_str(), '"'),
+                None => (caps.get(3).unwrap().as_str(), '\''),
+            };
+            let absolute = if url.is_empty() || has_scheme(url) {
+                url.to_string()
+            } else if url.starts_with('/') {
+                make_absolute_url(base_url, url)
+            } else {
+                match page_url.as_ref().and_then(|p| p.join(url).ok()) {
+                    Some(joined) => joined.to_string(),
+                    None => url.to_string(),
+                }
+            };
+            format!("{}{}{}{}", &caps[1], quote, absolute, quote)
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_fail_instead_of_scanning_for_a_port() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let res = resolve_port(port, port, false);
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            format!("The requested port {} is not available", port)
+        );
+
+        let other_port = resolve_port(port, port, true).unwrap();
+        assert_ne!(other_port, port);
+
+        // A port that isn't the default one is never replaced
+        assert!(resolve_port(port, 1111, true).is_err());
+    }
+
+    #[test]
+    fn can_make_html_urls_absolute() {
+        let html = r#"<img src="cat.png"><a href='/about/'>About</a><a href="../other/#top">Other</a><img src="data:image/png;base64,AAAA"><a href="https://example.org">Out</a><a href="mailto:me@example.com">Me</a>"#;
+        assert_eq!(
+            make_html_urls_absolute(html, "https://example.com", "https://example.com/blog/post/"),
+            r#"<img src="https://example.com/blog/post/cat.png"><a href='https://example.com/about/'>About</a><a href="https://example.com/blog/other/#top">Other</a><img src="data:image/png;base64,AAAA"><a href="https://example.org">Out</a><a href="mailto:me@example.com">Me</a>"#
+        );
+    }
+}
@@ -15,11 +15,39 @@ pub struct ResolvedInternalLink {
     pub anchor: Option<String>,
 }
 
+/// Finds the key of `permalinks` the path of an internal link is for: the path itself or, with
+/// a stray trailing slash like `blog/post.md/`, the path without it.
+/// With `without_extension`, `blog/post` and `blog` can also be used for `blog/post.md` and
+/// `blog/_index.md`.
+fn find_permalink_key(
+    path: &str,
+    permalinks: &HashMap<String, String>,
+    without_extension: bool,
+) -> Option<String> {
+    if permalinks.contains_key(path) {
+        return Some(path.to_string());
+    }
+    let path = path.strip_suffix('/').unwrap_or(path);
+    let mut candidates = vec![path.to_string()];
+    if without_extension {
+        candidates.push(format!("{}.md", path));
+        candidates.push(if path.is_empty() {
+            "_index.md".to_string()
+        } else {
+            format!("{}/_index.md", path)
+        });
+    }
+    candidates.into_iter().find(|key| permalinks.contains_key(key))
+}
+
 /// Resolves an internal link (of the `@/posts/something.md#hey` sort) to its absolute link and
-/// returns the path + anchor as well
+/// returns the path + anchor as well.
+/// `without_extension` allows links like `@/posts/something` and `@/posts`, see
+/// `Config::internal_links_without_extension`.
 pub fn resolve_internal_link(
     link: &str,
     permalinks: &HashMap<String, String>,
+    without_extension: bool,
 ) -> Result<ResolvedInternalLink> {
     // First we remove the ./ since that's zola specific
     let clean_link = link.replacen("@/", "", 1);
@@ -29,8 +57,13 @@ pub fn resolve_internal_link(
     // If we have slugification turned off, we might end up with some escaped characters so we need
     // to decode them first
     let decoded = percent_decode(parts[0].as_bytes()).decode_utf8_lossy().to_string();
-    let target =
-        permalinks.get(&decoded).ok_or_else(|| anyhow!("Relative link {} not found.", link))?;
+    let decoded = find_permalink_key(&decoded, permalinks, without_extension).ok_or_else(|| {
+        match suggest_internal_links(link, permalinks) {
+            Some(suggestion) => anyhow!("Relative link {} not found. {}", link, suggestion),
+            None => anyhow!("Relative link {} not found.", link),
+        }
+    })?;
+    let target = &permalinks[&decoded];
     if parts.len() > 1 {
         Ok(ResolvedInternalLink {
             permalink: format!("{}#{}", target, parts[1]),
@@ -42,17 +75,56 @@ pub fn resolve_internal_link(
     }
 }
 
+/// The Levenshtein distance between two strings, in chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// A "Did you mean" message with the up to 3 files of the site closest to the file of that
+/// internal link, if some are close enough for the link to be a typo
+fn suggest_internal_links(link: &str, permalinks: &HashMap<String, String>) -> Option<String> {
+    let path = link.trim_start_matches("@/").split('#').next().unwrap();
+    let path = percent_decode(path.as_bytes()).decode_utf8_lossy();
+    let path = path.strip_suffix('/').unwrap_or(&path);
+    // Lower for short paths so we don't suggest every short file
+    let max_distance = (path.chars().count() / 3).clamp(1, 3);
+
+    let mut candidates: Vec<_> = permalinks
+        .keys()
+        .map(|key| (edit_distance(path, key), key.as_str()))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    candidates.sort_unstable();
+    let suggestions: Vec<_> =
+        candidates.iter().take(3).map(|(_, key)| format!("`@/{}`", key)).collect();
+    match suggestions.as_slice() {
+        [] => None,
+        [one] => Some(format!("Did you mean {}?", one)),
+        [rest @ .., last] => Some(format!("Did you mean {} or {}?", rest.join(", "), last)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
-    use super::resolve_internal_link;
+    use super::{edit_distance, resolve_internal_link, suggest_internal_links};
 
     #[test]
     fn can_resolve_valid_internal_link() {
         let mut permalinks = HashMap::new();
         permalinks.insert("pages/about.md".to_string(), "https://vincent.is/about".to_string());
-        let res = resolve_internal_link("@/pages/about.md", &permalinks).unwrap();
+        let res = resolve_internal_link("@/pages/about.md", &permalinks, false).unwrap();
         assert_eq!(res.permalink, "https://vincent.is/about");
     }
 
@@ -60,7 +132,7 @@ mod tests {
     fn can_resolve_valid_root_internal_link() {
         let mut permalinks = HashMap::new();
         permalinks.insert("about.md".to_string(), "https://vincent.is/about".to_string());
-        let res = resolve_internal_link("@/about.md", &permalinks).unwrap();
+        let res = resolve_internal_link("@/about.md", &permalinks, false).unwrap();
         assert_eq!(res.permalink, "https://vincent.is/about");
     }
 
@@ -68,12 +140,58 @@ mod tests {
     fn can_resolve_internal_links_with_anchors() {
         let mut permalinks = HashMap::new();
         permalinks.insert("pages/about.md".to_string(), "https://vincent.is/about".to_string());
-        let res = resolve_internal_link("@/pages/about.md#hello", &permalinks).unwrap();
+        let res = resolve_internal_link("@/pages/about.md#hello", &permalinks, false).unwrap();
         assert_eq!(res.permalink, "https://vincent.is/about#hello");
         assert_eq!(res.md_path, "pages/about.md".to_string());
         assert_eq!(res.anchor, Some("hello".to_string()));
     }
 
+    #[test]
+    fn can_resolve_internal_links_with_a_trailing_slash() {
+        let mut permalinks = HashMap::new();
+        permalinks.insert("blog/post.md".to_string(), "https://vincent.is/blog/post/".to_string());
+        permalinks.insert("blog/post.md.bak".to_string(), "https://vincent.is/bak/".to_string());
+
+        let res = resolve_internal_link("@/blog/post.md/", &permalinks, false).unwrap();
+        assert_eq!(res.permalink, "https://vincent.is/blog/post/");
+        assert_eq!(res.md_path, "blog/post.md");
+        assert_eq!(res.anchor, None);
+
+        let res = resolve_internal_link("@/blog/post.md/#anchor", &permalinks, false).unwrap();
+        assert_eq!(res.permalink, "https://vincent.is/blog/post/#anchor");
+        assert_eq!(res.md_path, "blog/post.md");
+        assert_eq!(res.anchor, Some("anchor".to_string()));
+
+        let res = resolve_internal_link("@/blog/post.md.bak", &permalinks, false).unwrap();
+        assert_eq!(res.permalink, "https://vincent.is/bak/");
+        // Only a single slash is ignored
+        assert!(resolve_internal_link("@/blog/post.md//", &permalinks, false).is_err());
+    }
+
+    #[test]
+    fn can_resolve_internal_links_without_extension() {
+        let mut permalinks = HashMap::new();
+        permalinks.insert("blog/post.md".to_string(), "https://vincent.is/blog/post/".to_string());
+        permalinks.insert("blog/_index.md".to_string(), "https://vincent.is/blog/".to_string());
+        permalinks.insert("_index.md".to_string(), "https://vincent.is/".to_string());
+
+        for (link, permalink, md_path) in [
+            ("@/blog/post", "https://vincent.is/blog/post/", "blog/post.md"),
+            ("@/blog/post#top", "https://vincent.is/blog/post/#top", "blog/post.md"),
+            ("@/blog", "https://vincent.is/blog/", "blog/_index.md"),
+            ("@/blog/", "https://vincent.is/blog/", "blog/_index.md"),
+            ("@/", "https://vincent.is/", "_index.md"),
+            ("@/blog/post.md", "https://vincent.is/blog/post/", "blog/post.md"),
+        ] {
+            let res = resolve_internal_link(link, &permalinks, true).unwrap();
+            assert_eq!(res.permalink, permalink);
+            assert_eq!(res.md_path, md_path);
+        }
+        // Still an error if not enabled
+        assert!(resolve_internal_link("@/blog/post", &permalinks, false).is_err());
+        assert!(resolve_internal_link("@/blog", &permalinks, false).is_err());
+    }
+
     #[test]
     fn can_resolve_escaped_internal_links() {
         let mut permalinks = HashMap::new();
@@ -81,15 +199,80 @@ mod tests {
             "pages/about space.md".to_string(),
             "https://vincent.is/about%20space/".to_string(),
         );
-        let res = resolve_internal_link("@/pages/about%20space.md#hello", &permalinks).unwrap();
+        let res =
+            resolve_internal_link("@/pages/about%20space.md#hello", &permalinks, false).unwrap();
         assert_eq!(res.permalink, "https://vincent.is/about%20space/#hello");
         assert_eq!(res.md_path, "pages/about space.md".to_string());
         assert_eq!(res.anchor, Some("hello".to_string()));
     }
 
+    #[test]
+    fn can_resolve_unicode_internal_links() {
+        let mut permalinks = HashMap::new();
+        permalinks.insert(
+            "posts/記事タイトル.md".to_string(),
+            "https://vincent.is/posts/記事タイトル/".to_string(),
+        );
+        permalinks
+            .insert("posts/привет.md".to_string(), "https://vincent.is/posts/привет/".to_string());
+        let res = resolve_internal_link("@/posts/記事タイトル.md", &permalinks, false).unwrap();
+        assert_eq!(res.permalink, "https://vincent.is/posts/記事タイトル/");
+        // Percent-encoded like some editors do
+        let res = resolve_internal_link(
+            "@/posts/%E8%A8%98%E4%BA%8B%E3%82%BF%E3%82%A4%E3%83%88%E3%83%AB.md#hello",
+            &permalinks,
+            false,
+        )
+        .unwrap();
+        assert_eq!(res.permalink, "https://vincent.is/posts/記事タイトル/#hello");
+        assert_eq!(res.md_path, "posts/記事タイトル.md");
+        let res = resolve_internal_link(
+            "@/posts/%D0%BF%D1%80%D0%B8%D0%B2%D0%B5%D1%82.md",
+            &permalinks,
+            false,
+        )
+        .unwrap();
+        assert_eq!(res.permalink, "https://vincent.is/posts/привет/");
+    }
+
+    #[test]
+    fn can_compute_edit_distance() {
+        assert_eq!(edit_distance("blog", "blog"), 0);
+        assert_eq!(edit_distance("blgo", "blog"), 2);
+        assert_eq!(edit_distance("post.md", "posts.md"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("記事", "記"), 1);
+    }
+
+    #[test]
+    fn suggests_close_files_for_broken_internal_links() {
+        let mut permalinks = HashMap::new();
+        for key in ["blog/post.md", "blog/posts.md", "blog/_index.md", "about.md"] {
+            permalinks.insert(key.to_string(), format!("https://vincent.is/{}", key));
+        }
+
+        let err = resolve_internal_link("@/blgo/post.md#hello", &permalinks, false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Relative link @/blgo/post.md#hello not found. Did you mean `@/blog/post.md` or `@/blog/posts.md`?"
+        );
+        assert_eq!(
+            suggest_internal_links("@/blog/pots.md", &permalinks).unwrap(),
+            "Did you mean `@/blog/posts.md` or `@/blog/post.md`?"
+        );
+        assert_eq!(
+            suggest_internal_links("@/abot.md", &permalinks).unwrap(),
+            "Did you mean `@/about.md`?"
+        );
+        // Wildly different paths are not suggested
+        assert_eq!(suggest_internal_links("@/contact.md", &permalinks), None);
+        let err = resolve_internal_link("@/contact.md", &permalinks, false).unwrap_err();
+        assert_eq!(err.to_string(), "Relative link @/contact.md not found.");
+    }
+
     #[test]
     fn errors_resolve_inexistant_internal_link() {
-        let res = resolve_internal_link("@/pages/about.md#hello", &HashMap::new());
+        let res = resolve_internal_link("@/pages/about.md#hello", &HashMap::new(), false);
         assert!(res.is_err());
     }
 }
@@ -12,6 +12,9 @@ pub enum SlugifyStrategy {
     Safe,
     /// Nothing is changed, hope for the best!
     Off,
+    /// Like `On` but keeps the non-ASCII letters instead of transliterating them.
+    /// Useful for languages not using the latin alphabet
+    Unicode,
 }
 
 fn strip_chars(s: &str, chars: &str) -> String {
@@ -27,17 +30,59 @@ fn strip_invalid_paths_chars(s: &str) -> String {
     strip_chars(trimmed, r#"<>:"/\|?*"#)
 }
 
+fn is_unicode_separator(c: char) -> bool {
+    if c.is_alphanumeric() {
+        return false;
+    }
+    // We only want to get rid of whitespace and punctuation: some scripts rely on
+    // combining marks which are not alphanumeric
+    c.is_ascii()
+        || c.is_whitespace()
+        || matches!(c,
+            // General punctuation
+            '\u{2000}'..='\u{206F}'
+            // CJK symbols and punctuation
+            | '\u{3000}'..='\u{303F}'
+            // Fullwidth ASCII punctuation
+            | '\u{FF01}'..='\u{FF0F}'
+            | '\u{FF1A}'..='\u{FF20}'
+            | '\u{FF3B}'..='\u{FF40}'
+            | '\u{FF5B}'..='\u{FF65}'
+        )
+}
+
+fn unicode_slugify(s: &str) -> String {
+    let mut slug = String::with_capacity(s.len());
+    let mut needs_dash = false;
+
+    for c in s.chars() {
+        if is_unicode_separator(c) {
+            needs_dash = true;
+            continue;
+        }
+        if needs_dash && !slug.is_empty() {
+            slug.push('-');
+        }
+        needs_dash = false;
+        slug.extend(c.to_lowercase());
+    }
+
+    slug
+}
+
 pub fn slugify_paths(s: &str, strategy: SlugifyStrategy) -> String {
     match strategy {
         SlugifyStrategy::On => slug::slugify(s),
         SlugifyStrategy::Safe => strip_invalid_paths_chars(s),
         SlugifyStrategy::Off => s.to_string(),
+        SlugifyStrategy::Unicode => unicode_slugify(s),
     }
 }
 
 pub fn slugify_anchors(s: &str, strategy: SlugifyStrategy) -> String {
     match strategy {
         SlugifyStrategy::On => slug::slugify(s),
+        SlugifyStrategy::Unicode => unicode_slugify(s),
         SlugifyStrategy::Safe | SlugifyStrategy::Off => {
             s.replace(|c: char| c.is_ascii_whitespace(), "_")
         }
@@ -69,6 +114,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn can_slugify_unicode() {
+        let tests = vec![
+            ("input", "input"),
+            ("Hello World", "hello-world"),
+            ("  test (hey)!  ", "test-hey"),
+            ("héhé", "héhé"),
+            ("記事タイトル", "記事タイトル"),
+            ("日本語の記事、その2。", "日本語の記事-その2"),
+            ("【お知らせ】 新しい記事", "お知らせ-新しい記事"),
+            ("Привет, Мир!", "привет-мир"),
+            ("Статья о Rust", "статья-о-rust"),
+            // Combining marks are kept
+            ("हिन्दी लेख", "हिन्दी-लेख"),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(expected, slugify_paths(input, SlugifyStrategy::Unicode));
+            assert_eq!(expected, slugify_anchors(input, SlugifyStrategy::Unicode));
+        }
+    }
+
     #[test]
     fn can_slugify_anchors() {
         let tests = vec![
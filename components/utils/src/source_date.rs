@@ -0,0 +1,64 @@
+//! The single source of the current time in Zola, so builds can be made reproducible by setting
+//! the `SOURCE_DATE_EPOCH` environment variable: <https://reproducible-builds.org/specs/source-date-epoch/>
+use errors::{anyhow, Result};
+use libs::chrono::{Local, Offset, TimeZone};
+use libs::time::{OffsetDateTime, UtcOffset};
+
+/// The time of the `SOURCE_DATE_EPOCH` environment variable, if it is set
+pub fn source_date_epoch() -> Result<Option<OffsetDateTime>> {
+    let epoch = match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch,
+        Err(_) => return Ok(None),
+    };
+    epoch
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .and_then(|e| OffsetDateTime::from_unix_timestamp(e).ok())
+        .map(Some)
+        .ok_or_else(|| {
+            anyhow!("`SOURCE_DATE_EPOCH` should be a number of seconds, got `{}`", epoch)
+        })
+}
+
+/// The current time, or the `SOURCE_DATE_EPOCH` one if there is one
+pub fn now(source_date_epoch: Option<OffsetDateTime>) -> OffsetDateTime {
+    source_date_epoch.unwrap_or_else(OffsetDateTime::now_utc)
+}
+
+/// Clamps a time, like the modification time of a file, so it is not later than `SOURCE_DATE_EPOCH`
+pub fn clamp(time: OffsetDateTime, source_date_epoch: Option<OffsetDateTime>) -> OffsetDateTime {
+    match source_date_epoch {
+        Some(epoch) if epoch < time => epoch,
+        _ => time,
+    }
+}
+
+/// Converts a time to the local timezone.
+/// This goes through chrono like Tera does: `UtcOffset::current_local_offset` always fails once
+/// the process has several threads, which is the case as soon as the site is rendered.
+pub fn to_local(time: OffsetDateTime) -> OffsetDateTime {
+    let offset = Local
+        .timestamp_opt(time.unix_timestamp(), 0)
+        .single()
+        .and_then(|local| {
+            UtcOffset::from_whole_seconds(local.offset().fix().local_minus_utc()).ok()
+        })
+        .unwrap_or(UtcOffset::UTC);
+    time.to_offset(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamps_to_source_date_epoch() {
+        let epoch = OffsetDateTime::from_unix_timestamp(1700000000).unwrap();
+        let before = OffsetDateTime::from_unix_timestamp(1600000000).unwrap();
+        let after = OffsetDateTime::from_unix_timestamp(1800000000).unwrap();
+        assert_eq!(clamp(before, Some(epoch)), before);
+        assert_eq!(clamp(after, Some(epoch)), epoch);
+        assert_eq!(clamp(after, None), after);
+    }
+}
@@ -16,6 +16,26 @@ impl Heading {
     }
 }
 
+/// A heading of the flat table of contents, in the order of the document
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize)]
+pub struct FlatHeading {
+    pub level: u32,
+    pub id: String,
+    pub permalink: String,
+    pub title: String,
+}
+
+impl From<&Heading> for FlatHeading {
+    fn from(heading: &Heading) -> Self {
+        FlatHeading {
+            level: heading.level,
+            id: heading.id.clone(),
+            permalink: heading.permalink.clone(),
+            title: heading.title.clone(),
+        }
+    }
+}
+
 // Takes a potential (mutable) parent and a heading to try and insert into
 // Returns true when it performed the insertion, false otherwise
 fn insert_into_parent(potential_parent: Option<&mut Heading>, heading: &Heading) -> bool {
@@ -34,6 +34,15 @@ impl ShortcodeDefinition {
     }
 }
 
+/// The glob of the extensions of the files loaded from the `templates` directories,
+/// `*ml` being for `html`, `xml` and the like
+pub const TEMPLATE_EXTENSIONS_GLOB: &str = "{*ml,md,txt,json}";
+
+/// Whether the files with that extension are loaded as templates, see `TEMPLATE_EXTENSIONS_GLOB`
+pub fn is_template_extension(extension: &str) -> bool {
+    extension.ends_with("ml") || ["md", "txt", "json"].contains(&extension)
+}
+
 /// Fetches all the shortcodes from the Tera instances
 pub fn get_shortcodes(tera: &Tera) -> HashMap<String, ShortcodeDefinition> {
     let mut shortcode_definitions = HashMap::new();
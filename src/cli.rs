@@ -4,6 +4,8 @@ use std::path::PathBuf;
 use clap::{Parser, Subcommand};
 use clap_complete::Shell;
 
+use crate::cmd::ImportSource;
+
 #[derive(Parser)]
 #[clap(version, author, about)]
 pub struct Cli {
@@ -49,6 +51,19 @@ pub enum Command {
         /// Include drafts when loading the site
         #[clap(long)]
         drafts: bool,
+
+        /// Rebuild the site on change, without serving it
+        #[clap(short = 'w', long)]
+        watch: bool,
+
+        /// Make the links to the site relative to each page, to browse it without a server
+        #[clap(long)]
+        relative_urls: bool,
+
+        /// Build the site once per variant of the templates, in `templates/variants/{name}/`,
+        /// into a `{name}` subdirectory of the output directory to compare them
+        #[clap(long, value_delimiter = ',', conflicts_with = "watch")]
+        template_variants: Vec<String>,
     },
 
     /// Serve the site. Rebuild and reload on change automatically
@@ -89,6 +104,10 @@ pub enum Command {
         /// Default append port to the base url.
         #[clap(long)]
         no_port_append: bool,
+
+        /// Fail if the port is not available instead of using another one
+        #[clap(long)]
+        no_port_scan: bool,
     },
 
     /// Try to build the project without rendering it. Checks links
@@ -96,6 +115,33 @@ pub enum Command {
         /// Include drafts when loading the site
         #[clap(long)]
         drafts: bool,
+
+        /// Only check the internal links, without any network access
+        #[clap(long, conflicts_with = "external_only")]
+        internal_only: bool,
+
+        /// Only check the external links
+        #[clap(long)]
+        external_only: bool,
+
+        /// Write a JSON file listing the external links of the content and the pages linking to them
+        #[clap(long)]
+        external_links_report: Option<PathBuf>,
+    },
+
+    /// Convert the content of a Hugo or Jekyll site to Zola pages and sections, in the `content`
+    /// directory of the project
+    Import {
+        /// The static site generator the site was made with
+        #[clap(long, value_enum)]
+        from: ImportSource,
+
+        /// The directory of the site to import
+        source: PathBuf,
+
+        /// Overwrite the files that already exist in the `content` directory
+        #[clap(short = 'f', long)]
+        force: bool,
     },
 
     /// Generate shell completion
@@ -1,19 +1,27 @@
 use std::path::Path;
+use std::time::Instant;
+
+use time::UtcOffset;
 
 use errors::{Error, Result};
 use site::Site;
 
+use crate::cmd::watch::SiteWatcher;
 use crate::messages;
 
-pub fn build(
+#[allow(clippy::too_many_arguments)]
+fn build_site(
     root_dir: &Path,
     config_file: &Path,
     base_url: Option<&str>,
     output_dir: Option<&Path>,
     force: bool,
     include_drafts: bool,
-) -> Result<()> {
+    relative_urls: bool,
+    template_variant: Option<&str>,
+) -> Result<Site> {
     let mut site = Site::new(root_dir, config_file)?;
+    site.set_zola_version(env!("CARGO_PKG_VERSION"));
     if let Some(output_dir) = output_dir {
         if !force && output_dir.exists() {
             return Err(Error::msg(format!(
@@ -27,11 +35,215 @@ pub fn build(
     if let Some(b) = base_url {
         site.set_base_url(b.to_string());
     }
+    // Each variant is a full site in its own subdirectory of the output and of the base URL
+    if let Some(variant) = template_variant {
+        site.set_template_variant(variant)?;
+        let output_path = site.output_path.join(variant);
+        site.set_output_path(output_path);
+        let base_url = format!("{}/{}", site.config.base_url.trim_end_matches('/'), variant);
+        site.set_base_url(base_url);
+    }
     if include_drafts {
         site.include_drafts();
     }
+    if relative_urls {
+        site.enable_relative_urls();
+    }
     site.load()?;
     messages::notify_site_size(&site);
     messages::warn_about_ignored_pages(&site);
-    site.build()
+    site.build()?;
+    Ok(site)
+}
+
+/// Builds the site, or builds it once per template variant in `{output_dir}/{variant}` if there
+/// are any, to compare them
+#[allow(clippy::too_many_arguments)]
+pub fn build(
+    root_dir: &Path,
+    config_file: &Path,
+    base_url: Option<&str>,
+    output_dir: Option<&Path>,
+    force: bool,
+    include_drafts: bool,
+    relative_urls: bool,
+    template_variants: &[String],
+) -> Result<()> {
+    if template_variants.is_empty() {
+        return build_site(
+            root_dir,
+            config_file,
+            base_url,
+            output_dir,
+            force,
+            include_drafts,
+            relative_urls,
+            None,
+        )
+        .map(|_| ());
+    }
+
+    if let Some(output_dir) = output_dir {
+        if !force && output_dir.exists() {
+            return Err(Error::msg(format!(
+                "Directory '{}' already exists. Use --force to overwrite.",
+                output_dir.display(),
+            )));
+        }
+    }
+    for variant in template_variants {
+        // The output directory was checked above and gets created by the first variant
+        build_site(
+            root_dir,
+            config_file,
+            base_url,
+            output_dir,
+            true,
+            include_drafts,
+            relative_urls,
+            Some(variant),
+        )?;
+    }
+    Ok(())
+}
+
+/// Builds the site and then rebuilds it to disk on every change, without serving it.
+/// Only returns if the first build fails.
+#[allow(clippy::too_many_arguments)]
+pub fn build_and_watch(
+    root_dir: &Path,
+    config_file: &Path,
+    base_url: Option<&str>,
+    output_dir: Option<&Path>,
+    force: bool,
+    include_drafts: bool,
+    relative_urls: bool,
+    utc_offset: UtcOffset,
+) -> Result<()> {
+    let start = Instant::now();
+    let site = build_site(
+        root_dir,
+        config_file,
+        base_url,
+        output_dir,
+        force,
+        include_drafts,
+        relative_urls,
+        None,
+    )?;
+    messages::report_elapsed_time(start);
+
+    let watcher = SiteWatcher::new(root_dir, config_file, &site)?;
+    watcher.print_watch_list();
+    println!("Press Ctrl+C to stop\n");
+
+    // The output directory is ours after the first build so there's no need to force it again
+    let recreate_site = || {
+        build_site(
+            root_dir,
+            config_file,
+            base_url,
+            output_dir,
+            true,
+            include_drafts,
+            relative_urls,
+            None,
+        )
+    };
+    watcher.run(site, false, utc_offset, recreate_site, |res, _| {
+        if let Err(e) = res {
+            messages::unravel_errors("Failed to build the site", &e);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::super::write_test_files;
+    use super::build;
+
+    #[test]
+    fn can_build_one_site_per_template_variant() {
+        let tmp_dir = tempdir().unwrap();
+        let root_dir = tmp_dir.path().canonicalize().unwrap();
+        write_test_files(
+            &root_dir,
+            &[
+                ("config.toml", "base_url = \"https://example.com\"\n"),
+                ("content/_index.md", "+++\n+++\nHome"),
+                ("content/post.md", "+++\ntitle = \"Post\"\n+++\nHello"),
+                ("static/style.css", "body {}"),
+                ("templates/index.html", "{{ section.content | safe }}"),
+                ("templates/page.html", "<article>{{ page.content | safe }}</article>"),
+                (
+                    "templates/variants/wide/page.html",
+                    "<main class=\"wide\">{{ page.content | safe }}</main>",
+                ),
+                (
+                    "templates/variants/narrow/page.html",
+                    "<main class=\"narrow\">{{ page.content | safe }}</main>",
+                ),
+            ],
+        );
+
+        let output_dir = root_dir.join("out");
+        let variants = vec!["wide".to_string(), "narrow".to_string()];
+        build(
+            &root_dir,
+            &root_dir.join("config.toml"),
+            None,
+            Some(&output_dir),
+            false,
+            false,
+            false,
+            &variants,
+        )
+        .unwrap();
+
+        let read = |path: &str| fs::read_to_string(output_dir.join(path)).unwrap();
+        assert_eq!(read("wide/post/index.html"), "<main class=\"wide\"><p>Hello</p>\n</main>");
+        assert_eq!(read("narrow/post/index.html"), "<main class=\"narrow\"><p>Hello</p>\n</main>");
+        assert_eq!(read("wide/index.html"), read("narrow/index.html"));
+        assert_eq!(read("wide/style.css"), read("narrow/style.css"));
+        assert!(read("wide/sitemap.xml").contains("https://example.com/wide/post/"));
+        assert!(!output_dir.join("post").exists());
+    }
+
+    #[test]
+    fn rejects_template_variants_that_are_paths() {
+        let tmp_dir = tempdir().unwrap();
+        let root_dir = tmp_dir.path().canonicalize().unwrap();
+        write_test_files(
+            &root_dir,
+            &[
+                ("config.toml", "base_url = \"https://example.com\"\n"),
+                ("templates/variants/wide/page.html", ""),
+            ],
+        );
+
+        let output_dir = root_dir.join("out");
+        for variant in ["../wide", "variants/../wide", "a\\b", ""] {
+            let err = build(
+                &root_dir,
+                &root_dir.join("config.toml"),
+                None,
+                Some(&output_dir),
+                false,
+                false,
+                false,
+                &[variant.to_string()],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("The template variant `{}` should be a name, not a path", variant)
+            );
+        }
+        assert!(!root_dir.join("wide").exists());
+    }
 }
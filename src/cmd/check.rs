@@ -1,7 +1,10 @@
 use std::path::{Path, PathBuf};
 
 use errors::Result;
-use site::Site;
+use libs::serde_json;
+use site::link_checking::external_links_report;
+use site::{CheckScope, Site};
+use utils::fs::create_file;
 
 use crate::messages;
 
@@ -11,9 +14,12 @@ pub fn check(
     base_path: Option<&str>,
     base_url: Option<&str>,
     include_drafts: bool,
+    scope: CheckScope,
+    external_links_report_path: Option<&Path>,
 ) -> Result<()> {
     let bp = base_path.map(PathBuf::from).unwrap_or_else(|| PathBuf::from(root_dir));
     let mut site = Site::new(bp, config_file)?;
+    site.set_zola_version(env!("CARGO_PKG_VERSION"));
     // Force the checking of external links
     site.config.enable_check_mode();
     if let Some(b) = base_url {
@@ -22,8 +28,14 @@ pub fn check(
     if include_drafts {
         site.include_drafts();
     }
+    site.set_check_scope(scope);
     site.load()?;
+    if let Some(path) = external_links_report_path {
+        let report = serde_json::to_string_pretty(&external_links_report(&site))?;
+        create_file(path, report)?;
+    }
     messages::check_site_summary(&site);
     messages::warn_about_ignored_pages(&site);
+    messages::warn_about_unknown_code_languages(&site);
     Ok(())
 }
@@ -0,0 +1,524 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use content::{has_front_matter, split_content, RawFrontMatter};
+use errors::{bail, Context, Result};
+use libs::once_cell::sync::Lazy;
+use libs::regex::Regex;
+use libs::serde_yaml;
+use libs::toml::{self, Table, Value};
+use libs::walkdir::{DirEntry, WalkDir};
+use utils::fs::{copy_file, create_file, read_file};
+
+/// The static site generators we can import the content of
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ImportSource {
+    Hugo,
+    Jekyll,
+}
+
+/// The front matter fields of pages that have an equivalent in Zola, with the name of that field
+const PAGE_FIELDS: &[(&str, &str)] = &[
+    ("title", "title"),
+    ("description", "description"),
+    ("date", "date"),
+    ("lastmod", "updated"),
+    ("updated", "updated"),
+    ("draft", "draft"),
+    ("slug", "slug"),
+    ("url", "path"),
+    ("permalink", "path"),
+    ("weight", "weight"),
+    ("aliases", "aliases"),
+    ("redirect_from", "aliases"),
+    ("author", "authors"),
+    ("authors", "authors"),
+];
+
+/// Same for the sections, the `_index.md` of Hugo
+const SECTION_FIELDS: &[(&str, &str)] = &[
+    ("title", "title"),
+    ("description", "description"),
+    ("weight", "weight"),
+    ("draft", "draft"),
+    ("aliases", "aliases"),
+];
+
+/// The taxonomies Hugo and Jekyll have by default
+const TAXONOMIES: &[&str] = &["tags", "categories"];
+
+/// Matches the dates of Jekyll, like `2023-06-03 10:00:00 +0200`, that can't be parsed as is
+static DATETIME_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(\d{4}-\d{2}-\d{2})[ T](\d{2}:\d{2})(:\d{2})?(\.\d+)?\s*(?:(Z)|([+-]\d{2}):?(\d{2}))?$",
+    )
+    .unwrap()
+});
+
+/// Matches the shortcodes of Hugo and the tags of Liquid, which are not converted
+static TEMPLATE_TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{[<%]|\{%").unwrap());
+
+/// A file converted to a Zola page or section
+#[derive(Debug, PartialEq)]
+struct Converted {
+    content: String,
+    /// The fields of the front matter without an equivalent in Zola, moved to `[extra]`
+    unconverted: Vec<String>,
+    /// The default taxonomies the page is in
+    taxonomies: Vec<String>,
+    /// Whether the content contains shortcodes or Liquid tags, left as is
+    has_template_tags: bool,
+}
+
+/// Converts the content of a Hugo or Jekyll site in `source_dir` to a Zola one in the `content`
+/// directory of `root_dir`, and prints what couldn't be converted
+pub fn import(source: ImportSource, source_dir: &Path, root_dir: &Path, force: bool) -> Result<()> {
+    if !source_dir.is_dir() {
+        bail!("`{}` is not a directory", source_dir.display());
+    }
+    let content_dir = root_dir.join("content");
+    let files = match source {
+        ImportSource::Hugo => hugo_files(source_dir)?,
+        ImportSource::Jekyll => jekyll_files(source_dir),
+    };
+
+    let files: Vec<_> =
+        files.into_iter().map(|(src, dest, draft)| (src, content_dir.join(dest), draft)).collect();
+    // Checked before writing anything so a failed import doesn't leave half of the files
+    if let Some((_, dest, _)) = files.iter().find(|(_, dest, _)| dest.exists() && !force) {
+        bail!("`{}` already exists, use `--force` to overwrite it", dest.display());
+    }
+
+    let mut taxonomies = BTreeSet::new();
+    let mut num_files = 0;
+    for (src, dest, draft) in files {
+        // The sections added to the directories without one have no source file
+        let source_content = if src.as_os_str().is_empty() {
+            String::new()
+        } else if is_markdown(&src) {
+            read_file(&src)?
+        } else {
+            copy_file(&src, dest.parent().unwrap(), src.parent().unwrap(), false)?;
+            continue;
+        };
+
+        let is_section = dest.file_name().is_some_and(|f| f == "_index.md");
+        let converted = convert_file(&src, &source_content, is_section, draft)
+            .with_context(|| format!("Failed to convert `{}`", src.display()))?;
+        if !converted.unconverted.is_empty() {
+            console::warn(&format!(
+                "{}: moved to [extra]: {}",
+                dest.display(),
+                converted.unconverted.join(", ")
+            ));
+        }
+        if converted.has_template_tags {
+            console::warn(&format!(
+                "{}: contains shortcodes or Liquid tags, they need to be converted by hand",
+                dest.display()
+            ));
+        }
+        taxonomies.extend(converted.taxonomies);
+        create_file(&dest, converted.content)?;
+        num_files += 1;
+    }
+
+    console::info(&format!("Imported {} files in {}", num_files, content_dir.display()));
+    if !taxonomies.is_empty() {
+        let names: Vec<_> = taxonomies.iter().map(|t| format!("{{ name = \"{}\" }}", t)).collect();
+        console::info(&format!(
+            "Add `taxonomies = [{}]` to the config.toml of the site",
+            names.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "md" || e == "markdown")
+}
+
+fn is_hidden(entry: &DirEntry) -> bool {
+    entry.depth() > 0 && entry.file_name().to_str().is_some_and(|s| s.starts_with('.'))
+}
+
+/// The files of the `content` directory of a Hugo site, which has the same layout as the Zola one
+/// except that sections don't need an `_index.md`: one is added in the directories without one,
+/// unless they are in a page bundle, with an `index.md`
+fn hugo_files(source_dir: &Path) -> Result<Vec<(PathBuf, PathBuf, bool)>> {
+    let hugo_content = source_dir.join("content");
+    if !hugo_content.is_dir() {
+        bail!("`{}` doesn't have a `content` directory", source_dir.display());
+    }
+
+    let mut files = Vec::new();
+    for entry in WalkDir::new(&hugo_content).into_iter().filter_entry(|e| !is_hidden(e)) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(&hugo_content).unwrap().to_path_buf();
+        if entry.file_type().is_file() {
+            files.push((entry.path().to_path_buf(), relative, false));
+            continue;
+        }
+        let in_bundle =
+            entry.path().ancestors().take(entry.depth()).any(|a| a.join("index.md").exists());
+        if entry.depth() > 0 && !in_bundle && !entry.path().join("_index.md").exists() {
+            files.push((PathBuf::new(), relative.join("_index.md"), false));
+        }
+    }
+    Ok(files)
+}
+
+/// The posts and drafts of a Jekyll site go in a `posts` section, the other files with a front
+/// matter outside of the `_` directories are pages
+fn jekyll_files(source_dir: &Path) -> Vec<(PathBuf, PathBuf, bool)> {
+    let mut files = vec![(PathBuf::new(), PathBuf::from("posts/_index.md"), false)];
+    for (dir, draft) in [("_posts", false), ("_drafts", true)] {
+        let dir = source_dir.join(dir);
+        for entry in WalkDir::new(&dir).into_iter().filter_map(|e| e.ok()) {
+            if entry.file_type().is_file() && is_markdown(entry.path()) {
+                let name = entry.path().with_extension("md");
+                let name = PathBuf::from("posts").join(name.file_name().unwrap());
+                files.push((entry.path().to_path_buf(), name, draft));
+            }
+        }
+    }
+
+    let is_ignored = |e: &DirEntry| {
+        is_hidden(e)
+            || (e.depth() > 0
+                && e.file_name()
+                    .to_str()
+                    .is_some_and(|s| s.starts_with('_') || s == "node_modules" || s == "vendor"))
+    };
+    for entry in WalkDir::new(source_dir).into_iter().filter_entry(|e| !is_ignored(e)) {
+        let entry = match entry {
+            Ok(e) if e.file_type().is_file() => e,
+            _ => continue,
+        };
+        let path = entry.path();
+        // The index of the site is the template of the Zola `index.html`, not a page
+        let is_index = entry.depth() == 1 && path.file_stem().is_some_and(|s| s == "index");
+        if !is_markdown(path)
+            || is_index
+            || !read_file(path).map(|c| has_front_matter(&c)).unwrap_or(false)
+        {
+            continue;
+        }
+        let relative = path.strip_prefix(source_dir).unwrap().with_extension("md");
+        files.push((path.to_path_buf(), relative, false));
+    }
+    files
+}
+
+/// Converts a YAML value to a TOML one, `null` has no equivalent and is removed
+fn yaml_to_toml(value: serde_yaml::Value) -> Option<Value> {
+    match value {
+        serde_yaml::Value::Null => None,
+        serde_yaml::Value::Bool(b) => Some(Value::Boolean(b)),
+        serde_yaml::Value::Number(n) => match n.as_i64() {
+            Some(i) => Some(Value::Integer(i)),
+            None => n.as_f64().map(Value::Float),
+        },
+        serde_yaml::Value::String(s) => Some(Value::String(s)),
+        serde_yaml::Value::Sequence(seq) => {
+            Some(Value::Array(seq.into_iter().filter_map(yaml_to_toml).collect()))
+        }
+        serde_yaml::Value::Mapping(map) => Some(Value::Table(
+            map.into_iter()
+                .filter_map(|(k, v)| {
+                    let key = match k {
+                        serde_yaml::Value::String(s) => s,
+                        k => serde_yaml::to_string(&k).ok()?.trim().to_string(),
+                    };
+                    Some((key, yaml_to_toml(v)?))
+                })
+                .collect(),
+        )),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_toml(tagged.value),
+    }
+}
+
+/// A list from a value that can be a single string, split on whitespaces for the taxonomies
+/// as in Jekyll
+fn to_list(value: Value, split: bool) -> Value {
+    match value {
+        Value::String(s) if split => {
+            Value::Array(s.split_whitespace().map(|s| Value::String(s.to_string())).collect())
+        }
+        Value::Array(_) => value,
+        v => Value::Array(vec![v]),
+    }
+}
+
+/// Whether the value is a string or a list of strings, like the `authors` of Zola
+fn is_string_or_strings(value: &Value) -> bool {
+    match value {
+        Value::String(_) => true,
+        Value::Array(values) => values.iter().all(|v| v.is_str()),
+        _ => false,
+    }
+}
+
+/// Rewrites the dates Zola can't parse, like `2023-06-03 10:00 +0200`, in RFC3339
+fn to_date(value: Value) -> Value {
+    let date = match value {
+        Value::String(ref s) => s,
+        _ => return value,
+    };
+    match DATETIME_RE.captures(date.trim()) {
+        Some(caps) => {
+            let offset = match (caps.get(5), caps.get(6), caps.get(7)) {
+                (Some(_), _, _) => "Z".to_string(),
+                (_, Some(h), Some(m)) => format!("{}:{}", h.as_str(), m.as_str()),
+                _ => String::new(),
+            };
+            Value::String(format!(
+                "{}T{}{}{}{}",
+                &caps[1],
+                &caps[2],
+                caps.get(3).map_or(":00", |m| m.as_str()),
+                caps.get(4).map_or("", |m| m.as_str()),
+                offset
+            ))
+        }
+        None => value,
+    }
+}
+
+/// Converts the front matter of a Hugo or Jekyll file to a Zola one, in TOML
+fn convert_file(path: &Path, content: &str, is_section: bool, draft: bool) -> Result<Converted> {
+    let (table, body) = if path.as_os_str().is_empty() || !has_front_matter(content) {
+        (Table::new(), content)
+    } else {
+        let (raw, body) = split_content(path, content)?;
+        let table = match raw {
+            RawFrontMatter::Toml(_) => raw.deserialize::<Table>()?,
+            RawFrontMatter::Yaml(_) => match yaml_to_toml(raw.deserialize()?) {
+                Some(Value::Table(t)) => t,
+                _ => Table::new(),
+            },
+        };
+        (table, body)
+    };
+
+    let fields = if is_section { SECTION_FIELDS } else { PAGE_FIELDS };
+    let mut front_matter = Table::new();
+    let mut taxonomies = Table::new();
+    let mut extra = Table::new();
+    let mut unconverted = Vec::new();
+    for (key, value) in table {
+        let lower = key.to_lowercase();
+        let field = fields.iter().find(|(k, _)| *k == lower).map(|(_, name)| *name);
+        // The authors of Hugo can be maps with a name, an email etc. which Zola doesn't have
+        let field = field.filter(|name| *name != "authors" || is_string_or_strings(&value));
+        if let Some(name) = field {
+            let value = match name {
+                "date" | "updated" => to_date(value),
+                "aliases" | "authors" => to_list(value, false),
+                _ => value,
+            };
+            front_matter.insert(name.to_string(), value);
+        } else if !is_section && TAXONOMIES.contains(&lower.as_str()) {
+            taxonomies.insert(lower, to_list(value, true));
+        } else if let ("published", Value::Boolean(published)) = (lower.as_str(), &value) {
+            front_matter.insert("draft".to_string(), Value::Boolean(!published));
+        } else {
+            unconverted.push(key.clone());
+            extra.insert(key, value);
+        }
+    }
+    if draft {
+        front_matter.insert("draft".to_string(), Value::Boolean(true));
+    }
+    let taxonomy_names = taxonomies.keys().cloned().collect();
+    if !taxonomies.is_empty() {
+        front_matter.insert("taxonomies".to_string(), Value::Table(taxonomies));
+    }
+    if !extra.is_empty() {
+        front_matter.insert("extra".to_string(), Value::Table(extra));
+    }
+
+    Ok(Converted {
+        content: format!("+++\n{}+++\n{}", toml::to_string(&front_matter)?, body),
+        unconverted,
+        taxonomies: taxonomy_names,
+        has_template_tags: TEMPLATE_TAG_RE.is_match(body),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::write_test_files;
+    use super::*;
+    use config::Config;
+    use content::Page;
+
+    #[test]
+    fn can_convert_a_hugo_post_to_a_zola_page() {
+        let hugo = r#"---
+title: "Hello"
+date: 2023-06-03T10:00:00+02:00
+lastmod: 2023-06-04
+draft: false
+tags: ["rust", "web"]
+categories: news
+author: Vincent
+aliases: /old-hello/
+layout: post
+toc: true
+---
+Some content with {{< figure src="a.png" >}}
+"#;
+        let converted = convert_file(Path::new("hello.md"), hugo, false, false).unwrap();
+        assert_eq!(converted.unconverted, vec!["layout", "toc"]);
+        assert_eq!(converted.taxonomies, vec!["categories", "tags"]);
+        assert!(converted.has_template_tags);
+
+        let base_path = Path::new("");
+        let page = Page::parse(
+            Path::new("content/posts/hello.md"),
+            &converted.content,
+            &Config::default(),
+            base_path,
+        )
+        .unwrap();
+        assert_eq!(page.meta.title.as_deref(), Some("Hello"));
+        assert_eq!(page.meta.date.as_deref(), Some("2023-06-03T10:00:00+02:00"));
+        assert_eq!(page.meta.updated.as_deref(), Some("2023-06-04"));
+        assert!(!page.meta.draft);
+        assert_eq!(page.meta.taxonomies["tags"], vec!["rust", "web"]);
+        assert_eq!(page.meta.taxonomies["categories"], vec!["news"]);
+        assert_eq!(page.meta.authors, vec!["Vincent"]);
+        assert_eq!(page.meta.aliases, vec!["/old-hello/"]);
+        assert_eq!(page.meta.extra["layout"], "post");
+        assert_eq!(page.raw_content, "Some content with {{< figure src=\"a.png\" >}}\n");
+    }
+
+    #[test]
+    fn can_convert_jekyll_front_matter() {
+        let jekyll = "---\ntitle: Hello\ndate: 2023-06-03 10:00:00 +0200\ntags: rust web\n\
+                      published: false\nimage: ~\n---\nHello\n";
+        let converted = convert_file(Path::new("hello.md"), jekyll, false, false).unwrap();
+        assert_eq!(
+            converted.content,
+            "+++\ndate = \"2023-06-03T10:00:00+02:00\"\ndraft = true\ntitle = \"Hello\"\n\n\
+             [taxonomies]\ntags = [\"rust\", \"web\"]\n+++\nHello\n"
+        );
+        assert!(converted.unconverted.is_empty());
+        assert!(!converted.has_template_tags);
+    }
+
+    #[test]
+    fn keeps_the_authors_maps_in_extra() {
+        let hugo = "---\ntitle: Hello\nauthor:\n  name: Vincent\n  email: v@example.com\n---\n";
+        let converted = convert_file(Path::new("hello.md"), hugo, false, false).unwrap();
+        assert_eq!(converted.unconverted, vec!["author"]);
+        assert_eq!(
+            converted.content,
+            "+++\ntitle = \"Hello\"\n\n[extra.author]\nemail = \"v@example.com\"\nname = \"Vincent\"\n+++\n"
+        );
+    }
+
+    #[test]
+    fn can_convert_sections_and_files_without_front_matter() {
+        let converted = convert_file(
+            Path::new("_index.md"),
+            "+++\ntitle = \"Blog\"\nmenu = \"main\"\n+++\n",
+            true,
+            false,
+        )
+        .unwrap();
+        assert_eq!(converted.content, "+++\ntitle = \"Blog\"\n\n[extra]\nmenu = \"main\"\n+++\n");
+
+        let converted = convert_file(Path::new(""), "", true, false).unwrap();
+        assert_eq!(converted.content, "+++\n+++\n");
+        let converted = convert_file(Path::new("draft.md"), "Hello", false, true).unwrap();
+        assert_eq!(converted.content, "+++\ndraft = true\n+++\nHello");
+    }
+
+    #[test]
+    fn can_import_the_layout_of_a_hugo_site() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let source = tmp_dir.path().join("hugo");
+        let root = tmp_dir.path().join("zola");
+        write_test_files(
+            &source,
+            &[
+                ("content/about.md", "+++\ntitle = \"About\"\n+++\n"),
+                ("content/posts/hello.md", "---\ntitle: Hello\n---\n"),
+                ("content/docs/_index.md", "+++\ntitle = \"Docs\"\n+++\n"),
+                ("content/posts/bundle/index.md", "---\ntitle: Bundle\n---\n"),
+                ("content/posts/bundle/images/cover.png", ""),
+            ],
+        );
+
+        import(ImportSource::Hugo, &source, &root, false).unwrap();
+        let content = root.join("content");
+        assert!(content.join("about.md").exists());
+        assert!(content.join("posts/hello.md").exists());
+        assert_eq!(read_file(&content.join("posts/_index.md")).unwrap(), "+++\n+++\n");
+        assert!(read_file(&content.join("docs/_index.md")).unwrap().contains("Docs"));
+        assert!(content.join("posts/bundle/index.md").exists());
+        assert!(content.join("posts/bundle/images/cover.png").exists());
+        assert!(!content.join("posts/bundle/_index.md").exists());
+        assert!(!content.join("posts/bundle/images/_index.md").exists());
+        assert!(!content.join("_index.md").exists());
+    }
+
+    #[test]
+    fn doesnt_write_anything_if_a_file_already_exists() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let source = tmp_dir.path().join("hugo");
+        let root = tmp_dir.path().join("zola");
+        write_test_files(
+            &source,
+            &[
+                ("content/about.md", "+++\ntitle = \"About\"\n+++\n"),
+                ("content/posts/hello.md", "---\ntitle: Hello\n---\n"),
+                ("content/posts/world.md", "---\ntitle: World\n---\n"),
+            ],
+        );
+        let content = root.join("content");
+        create_file(&content.join("posts/hello.md"), "Mine").unwrap();
+
+        let err = import(ImportSource::Hugo, &source, &root, false).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        assert!(!content.join("about.md").exists());
+        assert!(!content.join("posts/_index.md").exists());
+        assert!(!content.join("posts/world.md").exists());
+        assert_eq!(read_file(&content.join("posts/hello.md")).unwrap(), "Mine");
+
+        import(ImportSource::Hugo, &source, &root, true).unwrap();
+        assert!(content.join("about.md").exists());
+        assert!(read_file(&content.join("posts/hello.md")).unwrap().contains("Hello"));
+    }
+
+    #[test]
+    fn can_import_the_layout_of_a_jekyll_site() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let source = tmp_dir.path().join("jekyll");
+        let root = tmp_dir.path().join("zola");
+        write_test_files(
+            &source,
+            &[
+                ("_posts/2023-06-03-hello.markdown", "---\ntitle: Hello\n---\nHello\n"),
+                ("_drafts/wip.md", "---\ntitle: WIP\n---\n"),
+                ("_layouts/post.html", ""),
+                ("index.md", "---\nlayout: home\n---\n"),
+                ("about.md", "---\ntitle: About\n---\n"),
+                ("README.md", "No front matter so not a page"),
+            ],
+        );
+
+        import(ImportSource::Jekyll, &source, &root, false).unwrap();
+        let content = root.join("content");
+        assert!(content.join("posts/_index.md").exists());
+        assert!(read_file(&content.join("posts/2023-06-03-hello.md")).unwrap().contains("Hello"));
+        assert!(read_file(&content.join("posts/wip.md")).unwrap().contains("draft = true"));
+        assert!(content.join("about.md").exists());
+        assert!(!content.join("index.md").exists());
+        assert!(!content.join("README.md").exists());
+        assert!(import(ImportSource::Jekyll, &source, &root, false).is_err());
+    }
+}
@@ -1,9 +1,20 @@
 mod build;
 mod check;
+mod import;
 mod init;
 mod serve;
+mod watch;
 
-pub use self::build::build;
+pub use self::build::{build, build_and_watch};
 pub use self::check::check;
+pub use self::import::{import, ImportSource};
 pub use self::init::create_new_project;
 pub use self::serve::serve;
+
+/// Writes the given `(path, content)` files under `root`, creating the folders as needed
+#[cfg(test)]
+fn write_test_files(root: &std::path::Path, files: &[(&str, &str)]) {
+    for (path, content) in files {
+        utils::fs::create_file(&root.join(path), content).unwrap();
+    }
+}
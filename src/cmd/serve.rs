@@ -24,11 +24,10 @@
 use std::cell::Cell;
 use std::future::IntoFuture;
 use std::net::{IpAddr, SocketAddr, TcpListener};
-use std::path::{Path, PathBuf, MAIN_SEPARATOR};
-use std::sync::mpsc::channel;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 use hyper::http::HeaderValue;
 use hyper::server::Server;
@@ -36,30 +35,21 @@ use hyper::service::{make_service_fn, service_fn};
 use hyper::{body, header};
 use hyper::{Body, Method, Request, Response, StatusCode};
 use mime_guess::from_path as mimetype_from_path;
-use time::macros::format_description;
+use time::format_description::well_known::Rfc3339;
 use time::{OffsetDateTime, UtcOffset};
 
 use libs::percent_encoding;
 use libs::relative_path::{RelativePath, RelativePathBuf};
 use libs::serde_json;
-use notify_debouncer_full::{new_debouncer, notify::RecursiveMode, notify::Watcher};
 use ws::{Message, Sender, WebSocket};
 
-use errors::{anyhow, Context, Error, Result};
-use site::sass::compile_sass;
+use config::Serve;
+use errors::{anyhow, Error, Result};
 use site::{Site, SITE_CONTENT};
-use utils::fs::{clean_site_output_folder, copy_file, create_directory};
+use utils::fs::{clean_site_output_folder, create_directory};
 
-use crate::fs_utils::{filter_events, ChangeKind, SimpleFileSystemEventKind};
+use crate::cmd::watch::SiteWatcher;
 use crate::messages;
-use std::ffi::OsStr;
-
-#[derive(Debug, PartialEq)]
-enum WatchMode {
-    Required,
-    Optional,
-    Condition(bool),
-}
 
 static METHOD_NOT_ALLOWED_TEXT: &[u8] = b"Method Not Allowed";
 static NOT_FOUND_TEXT: &[u8] = b"Not Found";
@@ -67,14 +57,49 @@ static NOT_FOUND_TEXT: &[u8] = b"Not Found";
 // This is dist/livereload.min.js from the LiveReload.js v3.2.4 release
 const LIVE_RELOAD: &str = include_str!("livereload.js");
 
+// Paths starting with that prefix are handled by the server itself and never by the site
+const RESERVED_PREFIX: &str = "__zola";
+
+// The encodings of the precompressed siblings of the files, like `app.js.br`, by preference
+const PRECOMPRESSED_ENCODINGS: &[(&str, &str)] = &[("br", "br"), ("gzip", "gz")];
+
 static SERVE_ERROR: Mutex<Cell<Option<(&'static str, errors::Error)>>> =
     Mutex::new(Cell::new(None));
 
+/// The result of the last build, served as JSON at `/__zola/status`
+#[derive(Debug, Default)]
+struct BuildStatus {
+    /// When the last build finished, `None` until the first build is done
+    last_build: Option<OffsetDateTime>,
+    success: bool,
+    errors: Vec<String>,
+}
+
+static BUILD_STATUS: Mutex<BuildStatus> =
+    Mutex::new(BuildStatus { last_build: None, success: false, errors: Vec::new() });
+
+fn set_build_status(errors: Vec<String>) {
+    if let Ok(mut status) = BUILD_STATUS.lock() {
+        status.last_build = Some(OffsetDateTime::now_utc());
+        status.success = errors.is_empty();
+        status.errors = errors;
+    }
+}
+
 fn clear_serve_error() {
     let _ = SERVE_ERROR.lock().map(|error| error.swap(&Cell::new(None)));
+    set_build_status(Vec::new());
 }
 
 fn set_serve_error(msg: &'static str, e: errors::Error) {
+    let mut errors = vec![msg.to_string(), e.to_string()];
+    let mut cause = e.source();
+    while let Some(e) = cause {
+        errors.push(e.to_string());
+        cause = e.source();
+    }
+    set_build_status(errors);
+
     if let Ok(serve_error) = SERVE_ERROR.lock() {
         serve_error.swap(&Cell::new(Some((msg, e))));
     }
@@ -84,7 +109,14 @@ async fn handle_request(
     req: Request<Body>,
     mut root: PathBuf,
     base_path: String,
+    serve_config: Arc<Serve>,
 ) -> Result<Response<Body>> {
+    let accept_encoding = req
+        .headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
     let path_str = req.uri().path();
     if !path_str.starts_with(&base_path) {
         return Ok(not_found());
@@ -111,6 +143,18 @@ async fn handle_request(
         path.push(c);
     }
 
+    // Nothing from the site is served under the reserved prefix
+    if path.starts_with(RESERVED_PREFIX) {
+        if path != RelativePath::new(RESERVED_PREFIX).join("status") {
+            return Ok(not_found());
+        }
+        if req.method() == Method::GET {
+            return Ok(build_status());
+        } else {
+            return Ok(method_not_allowed());
+        }
+    }
+
     // livereload.js is served using the LIVE_RELOAD str, not a file
     if path == "livereload.js" {
         if req.method() == Method::GET {
@@ -161,22 +205,71 @@ async fn handle_request(
         root.push("index.html");
     };
 
-    let result = tokio::fs::read(&root).await;
-
-    let contents = match result {
-        Err(err) => return Ok(io_error(err)),
-        Ok(contents) => contents,
+    let (contents, content_encoding) = match read_precompressed(&root, &accept_encoding).await {
+        Some((contents, encoding)) => (contents, Some(encoding)),
+        None => match tokio::fs::read(&root).await {
+            Err(err) => return Ok(io_error(err)),
+            Ok(contents) => (contents, None),
+        },
     };
 
-    Ok(Response::builder()
+    let mut response = Response::builder()
         .status(StatusCode::OK)
-        .header(
-            header::CONTENT_TYPE,
-            mimetype_from_path(&root).first_or_octet_stream().essence_str(),
-        )
-        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
-        .body(Body::from(contents))
-        .unwrap())
+        .header(header::CONTENT_TYPE, content_type(&root, &serve_config))
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+    if let Some(encoding) = content_encoding {
+        response = response
+            .header(header::CONTENT_ENCODING, encoding)
+            .header(header::VARY, "accept-encoding");
+    }
+    Ok(response.body(Body::from(contents)).unwrap())
+}
+
+/// Reads the precompressed sibling of the file, like `app.js.br`, in the first of the
+/// encodings accepted by the client that has one
+async fn read_precompressed(path: &Path, accept_encoding: &str) -> Option<(Vec<u8>, &'static str)> {
+    for (encoding, extension) in PRECOMPRESSED_ENCODINGS {
+        if !accepts_encoding(accept_encoding, encoding) {
+            continue;
+        }
+        let mut compressed = path.as_os_str().to_owned();
+        compressed.push(".");
+        compressed.push(extension);
+        if let Ok(contents) = tokio::fs::read(&compressed).await {
+            return Some((contents, encoding));
+        }
+    }
+    None
+}
+
+/// Whether the `Accept-Encoding` header of a request accepts the encoding,
+/// eg `gzip` for `gzip, deflate, br;q=0.8` but not for `*, gzip;q=0`
+fn accepts_encoding(accept_encoding: &str, encoding: &str) -> bool {
+    let mut accepted = false;
+    for item in accept_encoding.split(',') {
+        let mut parts = item.split(';').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        if !(name.eq_ignore_ascii_case(encoding) || name == "*") {
+            continue;
+        }
+        let quality = parts.find_map(|p| p.strip_prefix("q=")).and_then(|q| q.parse::<f32>().ok());
+        let is_accepted = quality.is_none_or(|q| q > 0.0);
+        // The encoding itself takes precedence over `*`
+        if name != "*" {
+            return is_accepted;
+        }
+        accepted = is_accepted;
+    }
+    accepted
+}
+
+/// The content type of a file of the output directory, from the `mime_types` of the config first
+fn content_type(path: &Path, serve_config: &Serve) -> String {
+    let custom = path.extension().and_then(|ext| serve_config.mime_type(&ext.to_string_lossy()));
+    match custom {
+        Some(mime_type) => mime_type.to_string(),
+        None => mimetype_from_path(path).first_or_octet_stream().essence_str().to_string(),
+    }
 }
 
 /// Inserts build error message boxes into HTML responses when needed.
@@ -185,7 +278,8 @@ async fn response_error_injector(
 ) -> Result<Response<Body>> {
     let req = req.await;
 
-    // return req as-is if the request is Err(), not HTML, or if there are no error messages.
+    // return req as-is if the request is Err(), not HTML, precompressed, or if there are no
+    // error messages.
     if req
         .as_ref()
         .map(|req| {
@@ -193,6 +287,7 @@ async fn response_error_injector(
                 .get(header::CONTENT_TYPE)
                 .map(|val| val != HeaderValue::from_static("text/html"))
                 .unwrap_or(true)
+                || req.headers().contains_key(header::CONTENT_ENCODING)
         })
         .unwrap_or(true)
         || SERVE_ERROR.lock().unwrap().get_mut().is_none()
@@ -242,6 +337,21 @@ fn livereload_js() -> Response<Body> {
         .expect("Could not build livereload.js response")
 }
 
+fn build_status() -> Response<Body> {
+    let status = BUILD_STATUS.lock().unwrap();
+    let body = serde_json::json!({
+        "last_build": status.last_build.and_then(|d| d.format(&Rfc3339).ok()),
+        "success": status.success,
+        "errors": status.errors,
+    });
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::CACHE_CONTROL, "no-store")
+        .status(StatusCode::OK)
+        .body(body.to_string().into())
+        .expect("Could not build build status response")
+}
+
 fn in_memory_content(path: &RelativePathBuf, content: &str) -> Response<Body> {
     let content_type = match path.extension() {
         Some(ext) => match ext {
@@ -373,6 +483,7 @@ fn create_new_site(
     SITE_CONTENT.write().unwrap().clear();
 
     let mut site = Site::new(root_dir, config_file)?;
+    site.set_zola_version(env!("CARGO_PKG_VERSION"));
     let address = SocketAddr::new(interface, interface_port);
 
     // if no base URL provided, use socket address
@@ -432,7 +543,7 @@ pub fn serve(
     utc_offset: UtcOffset,
 ) -> Result<()> {
     let start = Instant::now();
-    let (mut site, bind_address, constructed_base_url) = create_new_site(
+    let (site, bind_address, constructed_base_url) = create_new_site(
         root_dir,
         interface,
         interface_port,
@@ -450,54 +561,14 @@ pub fn serve(
     };
 
     messages::report_elapsed_time(start);
+    set_build_status(Vec::new());
 
     // Stop right there if we can't bind to the address
     if (TcpListener::bind(bind_address)).is_err() {
         return Err(anyhow!("Cannot start server on address {}.", bind_address));
     }
 
-    let config_path = PathBuf::from(config_file);
-    let root_dir_str = root_dir.to_str().expect("Project root dir is not valid UTF-8.");
-
-    // An array of (path, WatchMode, RecursiveMode) where the path is watched for changes,
-    // the WatchMode value indicates whether this path must exist for zola serve to operate,
-    // and the RecursiveMode value indicates whether to watch nested directories.
-    let watch_this = vec![
-        // The first entry is ultimtely to watch config.toml in a more robust manner on Linux when
-        // the file changes by way of a caching strategy used by editors such as vim.
-        // https://github.com/getzola/zola/issues/2266
-        (root_dir_str, WatchMode::Required, RecursiveMode::NonRecursive),
-        ("content", WatchMode::Required, RecursiveMode::Recursive),
-        ("sass", WatchMode::Condition(site.config.compile_sass), RecursiveMode::Recursive),
-        ("static", WatchMode::Optional, RecursiveMode::Recursive),
-        ("templates", WatchMode::Optional, RecursiveMode::Recursive),
-        ("themes", WatchMode::Condition(site.config.theme.is_some()), RecursiveMode::Recursive),
-    ];
-
-    // Setup watchers
-    let (tx, rx) = channel();
-    let mut debouncer = new_debouncer(Duration::from_secs(1), /*tick_rate=*/ None, tx).unwrap();
-
-    // We watch for changes on the filesystem for every entry in watch_this
-    // Will fail if either:
-    //   - the path is mandatory but does not exist (eg. config.toml)
-    //   - the path exists but has incorrect permissions
-    // watchers will contain the paths we're actually watching
-    let mut watchers = Vec::new();
-    for (entry, watch_mode, recursive_mode) in watch_this {
-        let watch_path = root_dir.join(entry);
-        let should_watch = match watch_mode {
-            WatchMode::Required => true,
-            WatchMode::Optional => watch_path.exists(),
-            WatchMode::Condition(b) => b && watch_path.exists(),
-        };
-        if should_watch {
-            debouncer.watcher()
-                .watch(&root_dir.join(entry), recursive_mode)
-                .with_context(|| format!("Can't watch `{}` for changes in folder `{}`. Does it exist, and do you have correct permissions?", entry, root_dir.display()))?;
-            watchers.push(entry.to_string());
-        }
-    }
+    let watcher = SiteWatcher::new(root_dir, config_file, &site)?;
 
     let ws_port = site.live_reload;
     let ws_address = format!("{}:{}", interface, ws_port.unwrap());
@@ -506,6 +577,8 @@ pub fn serve(
 
     // static_root needs to be canonicalized because we do the same for the http server.
     let static_root = std::fs::canonicalize(&output_path).unwrap();
+    // Changes to it are only taken into account when restarting `zola serve`, like the base path
+    let serve_config = Arc::new(site.config.serve.clone());
 
     let broadcaster = {
         thread::spawn(move || {
@@ -518,6 +591,7 @@ pub fn serve(
                 let make_service = make_service_fn(move |_| {
                     let static_root = static_root.clone();
                     let base_path = base_path.clone();
+                    let serve_config = serve_config.clone();
 
                     async {
                         Ok::<_, hyper::Error>(service_fn(move |req| {
@@ -525,6 +599,7 @@ pub fn serve(
                                 req,
                                 static_root.clone(),
                                 base_path.clone(),
+                                serve_config.clone(),
                             ))
                         }))
                     }
@@ -578,17 +653,7 @@ pub fn serve(
         broadcaster
     };
 
-    // We watch for changes in the config by monitoring its parent directory, but we ignore all
-    // ordinary peer files. Map the parent directory back to the config file name to not confuse
-    // the end user.
-    let config_name =
-        config_path.file_name().unwrap().to_str().expect("Config name is not valid UTF-8.");
-    let watch_list = watchers
-        .iter()
-        .map(|w| if w == root_dir_str { config_name } else { w })
-        .collect::<Vec<&str>>()
-        .join(",");
-    println!("Listening for changes in {}{}{{{}}}", root_dir.display(), MAIN_SEPARATOR, watch_list);
+    watcher.print_watch_list();
 
     let preserve_dotfiles_in_output = site.config.preserve_dotfiles_in_output;
 
@@ -603,231 +668,232 @@ pub fn serve(
     })
     .expect("Error setting Ctrl-C handler");
 
-    let reload_sass = |site: &Site, paths: &Vec<&PathBuf>| {
-        let combined_paths =
-            paths.iter().map(|p| p.display().to_string()).collect::<Vec<String>>().join(", ");
-        let msg = format!("-> Sass file(s) changed {}", combined_paths);
-        console::info(&msg);
-        rebuild_done_handling(
-            &broadcaster,
-            compile_sass(&site.base_path, &site.output_path),
-            &site.sass_path.to_string_lossy(),
-        );
+    let recreate_site = || {
+        create_new_site(
+            root_dir,
+            interface,
+            interface_port,
+            output_dir,
+            force,
+            base_url,
+            config_file,
+            include_drafts,
+            no_port_append,
+            ws_port,
+        )
+        .map(|(s, _, _)| s)
     };
 
-    let reload_templates = |site: &mut Site| {
-        rebuild_done_handling(
-            &broadcaster,
-            site.reload_templates(),
-            &site.templates_path.to_string_lossy(),
-        );
+    watcher.run(site, fast_rebuild, utc_offset, recreate_site, |res, reload_path| {
+        rebuild_done_handling(&broadcaster, res, reload_path)
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        clear_serve_error, construct_url, create_new_site, handle_request, response_error_injector,
+        set_serve_error,
     };
+    use crate::get_config_file_path;
+    use config::Serve;
+    use hyper::{body, header, Body, Request, StatusCode};
+    use libs::relative_path::RelativePathBuf;
+    use libs::serde_json::{self, Value};
+    use libs::tera::{Context, Tera};
+    use libs::url::Url;
+    use site::SITE_CONTENT;
+    use std::net::{IpAddr, SocketAddr};
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex, MutexGuard};
 
-    let copy_static = |site: &Site, path: &Path, partial_path: &Path| {
-        // Do nothing if the file/dir is on the ignore list
-        if let Some(gs) = &site.config.ignored_static_globset {
-            if gs.is_match(partial_path) {
-                return;
-            }
-        }
-        // Do nothing if the file/dir was deleted
-        if !path.exists() {
-            return;
-        }
+    /// The tests building a site run in parallel but share the `SITE_CONTENT` of `zola serve`
+    static SITE_CONTENT_LOCK: Mutex<()> = Mutex::new(());
 
-        let msg = if path.is_dir() {
-            format!("-> Directory in `static` folder changed {}", path.display())
-        } else {
-            format!("-> Static file changed {}", path.display())
-        };
+    fn lock_site_content() -> MutexGuard<'static, ()> {
+        SITE_CONTENT_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
 
-        console::info(&msg);
-        if path.is_dir() {
-            rebuild_done_handling(
-                &broadcaster,
-                site.copy_static_directories(),
-                &path.to_string_lossy(),
-            );
-        } else {
-            rebuild_done_handling(
-                &broadcaster,
-                copy_file(path, &site.output_path, &site.static_path, site.config.hard_link_static),
-                &partial_path.to_string_lossy(),
-            );
-        }
-    };
+    /// Same for the build error of `zola serve`
+    static SERVE_ERROR_LOCK: Mutex<()> = Mutex::new(());
 
-    let recreate_site = || match create_new_site(
-        root_dir,
-        interface,
-        interface_port,
-        output_dir,
-        force,
-        base_url,
-        config_file,
-        include_drafts,
-        no_port_append,
-        ws_port,
-    ) {
-        Ok((s, _, _)) => {
-            clear_serve_error();
-            rebuild_done_handling(&broadcaster, Ok(()), "/x.js");
+    fn lock_serve_error() -> MutexGuard<'static, ()> {
+        SERVE_ERROR_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
 
-            Some(s)
-        }
-        Err(e) => {
-            let msg = "Failed to build the site";
+    fn get_build_status(base_path: &str) -> (StatusCode, Option<Value>) {
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        rt.block_on(async {
+            let req = Request::get(format!("{}__zola/status", base_path)).body(Body::empty());
+            let res = handle_request(
+                req.unwrap(),
+                PathBuf::new(),
+                base_path.to_string(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+            let status = res.status();
+            let bytes = body::to_bytes(res.into_body()).await.unwrap();
+            (status, serde_json::from_slice(&bytes).ok())
+        })
+    }
 
-            messages::unravel_errors(msg, &e);
-            set_serve_error(msg, e);
+    #[test]
+    fn status_endpoint_reports_build_failures() {
+        let _lock = lock_serve_error();
+        let err = Tera::one_off("{{ hello", &Context::new(), false).unwrap_err();
+        set_serve_error("Failed to build the site", err.into());
+
+        let (status, body) = get_build_status("/blog/");
+        assert_eq!(status, StatusCode::OK);
+        let body = body.unwrap();
+        assert_eq!(body["success"], Value::Bool(false));
+        assert!(body["last_build"].is_string());
+        let errors = body["errors"].as_array().unwrap();
+        assert_eq!(errors[0], "Failed to build the site");
+        assert!(errors.len() > 1);
+
+        clear_serve_error();
+        let (_, body) = get_build_status("/");
+        let body = body.unwrap();
+        assert_eq!(body["success"], Value::Bool(true));
+        assert_eq!(body["errors"], Value::Array(vec![]));
+    }
 
-            None
-        }
-    };
+    #[test]
+    fn build_errors_are_not_injected_in_precompressed_html() {
+        let _lock = lock_serve_error();
+        let err = Tera::one_off("{{ hello", &Context::new(), false).unwrap_err();
+        set_serve_error("Failed to build the site", err.into());
+
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let respond = |encoding: Option<&str>| {
+            let mut res = hyper::Response::builder().header(header::CONTENT_TYPE, "text/html");
+            if let Some(encoding) = encoding {
+                res = res.header(header::CONTENT_ENCODING, encoding);
+            }
+            let res = res.body(Body::from("<html></html>")).unwrap();
+            rt.block_on(async {
+                let res = response_error_injector(async { Ok(res) }).await.unwrap();
+                body::to_bytes(res.into_body()).await.unwrap()
+            })
+        };
+        let compressed = respond(Some("br"));
+        let plain = respond(None);
+        clear_serve_error();
 
-    loop {
-        match rx.recv() {
-            Ok(Ok(events)) => {
-                let changes = filter_events(
-                    events,
-                    root_dir,
-                    &config_path,
-                    &site.config.ignored_content_globset,
-                );
-                if changes.is_empty() {
-                    continue;
-                }
-                let format = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
-
-                for (change_kind, change_group) in changes.iter() {
-                    let current_time =
-                        OffsetDateTime::now_utc().to_offset(utc_offset).format(&format);
-                    if let Ok(time_str) = current_time {
-                        println!("Change detected @ {}", time_str);
-                    } else {
-                        // if formatting fails for some reason
-                        println!("Change detected");
-                    };
-
-                    let start = Instant::now();
-                    match change_kind {
-                        ChangeKind::Content => {
-                            for (_, full_path, event_kind) in change_group.iter() {
-                                console::info(&format!(
-                                    "-> Content changed {}",
-                                    full_path.display()
-                                ));
-
-                                let can_do_fast_reload =
-                                    *event_kind != SimpleFileSystemEventKind::Remove;
-
-                                if fast_rebuild {
-                                    if can_do_fast_reload {
-                                        let filename = full_path
-                                            .file_name()
-                                            .unwrap_or_else(|| OsStr::new(""))
-                                            .to_string_lossy();
-                                        let res = if filename == "_index.md" {
-                                            site.add_and_render_section(full_path)
-                                        } else if filename.ends_with(".md") {
-                                            site.add_and_render_page(full_path)
-                                        } else {
-                                            // an asset changed? a folder renamed?
-                                            // should we make it smarter so it doesn't reload the whole site?
-                                            Err(anyhow!("dummy"))
-                                        };
-
-                                        if res.is_err() {
-                                            if let Some(s) = recreate_site() {
-                                                site = s;
-                                            }
-                                        } else {
-                                            rebuild_done_handling(
-                                                &broadcaster,
-                                                res,
-                                                &full_path.to_string_lossy(),
-                                            );
-                                        }
-                                    } else {
-                                        // Should we be smarter than that? Is it worth it?
-                                        if let Some(s) = recreate_site() {
-                                            site = s;
-                                        }
-                                    }
-                                } else if let Some(s) = recreate_site() {
-                                    site = s;
-                                }
-                            }
-                        }
-                        ChangeKind::Templates => {
-                            let partial_paths: Vec<&PathBuf> =
-                                change_group.iter().map(|(p, _, _)| p).collect();
-                            let full_paths: Vec<&PathBuf> =
-                                change_group.iter().map(|(_, p, _)| p).collect();
-                            let combined_paths = full_paths
-                                .iter()
-                                .map(|p| p.display().to_string())
-                                .collect::<Vec<String>>()
-                                .join(", ");
-                            let msg = format!("-> Template file(s) changed {}", combined_paths);
-                            console::info(&msg);
-
-                            let shortcodes_updated = partial_paths
-                                .iter()
-                                .any(|p| p.starts_with("/templates/shortcodes"));
-                            // Rebuild site if shortcodes change; otherwise, just update template.
-                            if shortcodes_updated {
-                                if let Some(s) = recreate_site() {
-                                    site = s;
-                                }
-                            } else {
-                                println!("Reloading only template");
-                                reload_templates(&mut site)
-                            }
-                        }
-                        ChangeKind::StaticFiles => {
-                            for (partial_path, full_path, _) in change_group.iter() {
-                                copy_static(&site, full_path, partial_path);
-                            }
-                        }
-                        ChangeKind::Sass => {
-                            let full_paths = change_group.iter().map(|(_, p, _)| p).collect();
-                            reload_sass(&site, &full_paths);
-                        }
-                        ChangeKind::Themes => {
-                            // No need to iterate over change group since we're rebuilding the site.
-                            console::info("-> Themes changed.");
+        assert_eq!(compressed, "<html></html>");
+        assert!(plain.len() > "<html></html>".len());
+    }
 
-                            if let Some(s) = recreate_site() {
-                                site = s;
-                            }
-                        }
-                        ChangeKind::Config => {
-                            // No need to iterate over change group since we're rebuilding the site.
-                            console::info("-> Config changed. The browser needs to be refreshed to make the changes visible.");
+    #[test]
+    fn reserved_prefix_is_not_served_from_the_site() {
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let res = rt.block_on(handle_request(
+            Request::get("/__zola/index.html").body(Body::empty()).unwrap(),
+            PathBuf::new(),
+            "/".to_string(),
+            Default::default(),
+        ));
+        assert_eq!(res.unwrap().status(), StatusCode::NOT_FOUND);
+    }
 
-                            if let Some(s) = recreate_site() {
-                                site = s;
-                            }
-                        }
-                    };
-                    messages::report_elapsed_time(start);
-                }
-            }
-            Ok(Err(e)) => console::error(&format!("File system event errors: {:?}", e)),
-            Err(e) => console::error(&format!("File system event receiver errors: {:?}", e)),
+    #[test]
+    fn unknown_paths_are_served_the_404_page() {
+        let _lock = lock_site_content();
+        let not_found_path = RelativePathBuf::from("404.html");
+        SITE_CONTENT.write().unwrap().insert(not_found_path.clone(), "Nothing here".to_string());
+
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let (status, body) = rt.block_on(async {
+            let res = handle_request(
+                Request::get("/missing/page/").body(Body::empty()).unwrap(),
+                std::env::current_dir().unwrap(),
+                "/".to_string(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+            (res.status(), body::to_bytes(res.into_body()).await.unwrap())
+        });
+        SITE_CONTENT.write().unwrap().remove(&not_found_path);
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body, "Nothing here");
+    }
+
+    #[test]
+    fn static_files_are_served_with_their_content_type() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let root = tmp_dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("app.wasm"), b"\0asm").unwrap();
+        std::fs::write(root.join("data.custom"), "data").unwrap();
+        std::fs::write(root.join("site.webmanifest"), "{}").unwrap();
+        let mut serve_config = Serve::default();
+        serve_config.mime_types.insert("CUSTOM".to_string(), "application/x-custom".to_string());
+        let serve_config = Arc::new(serve_config);
+
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let content_type = |path: &str| {
+            let res = rt
+                .block_on(handle_request(
+                    Request::get(path).body(Body::empty()).unwrap(),
+                    root.clone(),
+                    "/".to_string(),
+                    serve_config.clone(),
+                ))
+                .unwrap();
+            res.headers()[header::CONTENT_TYPE].to_str().unwrap().to_string()
         };
+        assert_eq!(content_type("/app.wasm"), "application/wasm");
+        assert_eq!(content_type("/site.webmanifest"), "application/manifest+json");
+        assert_eq!(content_type("/data.custom"), "application/x-custom");
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::{construct_url, create_new_site};
-    use crate::get_config_file_path;
-    use libs::url::Url;
-    use std::net::{IpAddr, SocketAddr};
-    use std::path::{Path, PathBuf};
-    use std::str::FromStr;
+    #[test]
+    fn can_serve_precompressed_files() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let root = tmp_dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("app.js"), "plain").unwrap();
+        std::fs::write(root.join("app.js.br"), "brotli").unwrap();
+        std::fs::write(root.join("app.js.gz"), "gzip").unwrap();
+        std::fs::write(root.join("other.js"), "plain").unwrap();
+
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let get = |path: &str, accept_encoding: &str| {
+            rt.block_on(async {
+                let req = Request::get(path)
+                    .header(header::ACCEPT_ENCODING, accept_encoding)
+                    .body(Body::empty())
+                    .unwrap();
+                let res = handle_request(req, root.clone(), "/".to_string(), Default::default())
+                    .await
+                    .unwrap();
+                let encoding = res
+                    .headers()
+                    .get(header::CONTENT_ENCODING)
+                    .map(|v| v.to_str().unwrap().to_string());
+                let content_type =
+                    res.headers()[header::CONTENT_TYPE].to_str().unwrap().to_string();
+                let body = body::to_bytes(res.into_body()).await.unwrap();
+                (encoding, content_type, String::from_utf8(body.to_vec()).unwrap())
+            })
+        };
+
+        let (encoding, content_type, body) = get("/app.js", "gzip, deflate, br");
+        assert_eq!(encoding.as_deref(), Some("br"));
+        assert_eq!(content_type, "application/javascript");
+        assert_eq!(body, "brotli");
+        assert_eq!(get("/app.js", "gzip, br;q=0").2, "gzip");
+        assert_eq!(get("/app.js", "identity").2, "plain");
+        // No precompressed sibling
+        let (encoding, _, body) = get("/other.js", "br");
+        assert_eq!(encoding, None);
+        assert_eq!(body, "plain");
+    }
 
     #[test]
     fn test_construct_url_base_url_is_slash() {
@@ -874,6 +940,7 @@ mod tests {
         ws_port: Option<u16>,
         expected_base_url: String,
     ) {
+        let _lock = lock_site_content();
         let cli_dir = Path::new("./test_site").canonicalize().unwrap();
         let cli_config = Path::new("./test_site/config.toml").canonicalize().unwrap();
 
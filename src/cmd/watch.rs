@@ -0,0 +1,399 @@
+//! The file watching and rebuild loop shared by `zola serve` and `zola build --watch`.
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{Duration, Instant};
+
+use notify_debouncer_full::notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, FileIdMap};
+use time::macros::format_description;
+use time::{OffsetDateTime, UtcOffset};
+
+use errors::{anyhow, Context, Result};
+use site::sass::compile_sass;
+use site::Site;
+use utils::fs::copy_file;
+
+use crate::fs_utils::{filter_events, ChangeKind, SimpleFileSystemEventKind};
+use crate::messages;
+
+#[derive(Debug, PartialEq)]
+enum WatchMode {
+    Required,
+    Optional,
+    Condition(bool),
+}
+
+pub struct SiteWatcher {
+    root_dir: PathBuf,
+    config_path: PathBuf,
+    /// The paths we're actually watching
+    watchers: Vec<String>,
+    rx: Receiver<DebounceEventResult>,
+    // Dropping it would stop the watching, `None` when the tests send the events themselves
+    _debouncer: Option<Debouncer<RecommendedWatcher, FileIdMap>>,
+}
+
+impl SiteWatcher {
+    /// Starts watching the directories of the site for changes
+    pub fn new(root_dir: &Path, config_file: &Path, site: &Site) -> Result<Self> {
+        let root_dir_str = root_dir.to_str().expect("Project root dir is not valid UTF-8.");
+
+        // An array of (path, WatchMode, RecursiveMode) where the path is watched for changes,
+        // the WatchMode value indicates whether this path must exist for zola to operate,
+        // and the RecursiveMode value indicates whether to watch nested directories.
+        let watch_this = vec![
+            // The first entry is ultimtely to watch config.toml in a more robust manner on Linux when
+            // the file changes by way of a caching strategy used by editors such as vim.
+            // https://github.com/getzola/zola/issues/2266
+            (root_dir_str, WatchMode::Required, RecursiveMode::NonRecursive),
+            ("content", WatchMode::Required, RecursiveMode::Recursive),
+            ("sass", WatchMode::Condition(site.config.compile_sass), RecursiveMode::Recursive),
+            ("static", WatchMode::Optional, RecursiveMode::Recursive),
+            ("templates", WatchMode::Optional, RecursiveMode::Recursive),
+            ("themes", WatchMode::Condition(site.config.theme.is_some()), RecursiveMode::Recursive),
+        ];
+
+        // Setup watchers
+        let (tx, rx) = channel();
+        let mut debouncer = new_debouncer(Duration::from_secs(1), /*tick_rate=*/ None, tx).unwrap();
+
+        // We watch for changes on the filesystem for every entry in watch_this
+        // Will fail if either:
+        //   - the path is mandatory but does not exist (eg. config.toml)
+        //   - the path exists but has incorrect permissions
+        let mut watchers = Vec::new();
+        for (entry, watch_mode, recursive_mode) in watch_this {
+            let watch_path = root_dir.join(entry);
+            let should_watch = match watch_mode {
+                WatchMode::Required => true,
+                WatchMode::Optional => watch_path.exists(),
+                WatchMode::Condition(b) => b && watch_path.exists(),
+            };
+            if should_watch {
+                debouncer.watcher()
+                    .watch(&root_dir.join(entry), recursive_mode)
+                    .with_context(|| format!("Can't watch `{}` for changes in folder `{}`. Does it exist, and do you have correct permissions?", entry, root_dir.display()))?;
+                watchers.push(entry.to_string());
+            }
+        }
+
+        Ok(Self {
+            root_dir: root_dir.to_path_buf(),
+            config_path: PathBuf::from(config_file),
+            watchers,
+            rx,
+            _debouncer: Some(debouncer),
+        })
+    }
+
+    /// A watcher getting its events from `rx` instead of the file system
+    #[cfg(test)]
+    fn with_receiver(
+        root_dir: &Path,
+        config_file: &Path,
+        rx: Receiver<DebounceEventResult>,
+    ) -> Self {
+        Self {
+            root_dir: root_dir.to_path_buf(),
+            config_path: PathBuf::from(config_file),
+            watchers: Vec::new(),
+            rx,
+            _debouncer: None,
+        }
+    }
+
+    /// Prints which paths are watched
+    pub fn print_watch_list(&self) {
+        // We watch for changes in the config by monitoring its parent directory, but we ignore all
+        // ordinary peer files. Map the parent directory back to the config file name to not confuse
+        // the end user.
+        let root_dir_str = self.root_dir.to_str().expect("Project root dir is not valid UTF-8.");
+        let config_name = self
+            .config_path
+            .file_name()
+            .unwrap()
+            .to_str()
+            .expect("Config name is not valid UTF-8.");
+        let watch_list = self
+            .watchers
+            .iter()
+            .map(|w| if w == root_dir_str { config_name } else { w })
+            .collect::<Vec<&str>>()
+            .join(",");
+        println!(
+            "Listening for changes in {}{}{{{}}}",
+            self.root_dir.display(),
+            MAIN_SEPARATOR,
+            watch_list
+        );
+    }
+
+    /// Rebuilds the site on every change, until the sender of the events is dropped.
+    /// `recreate_site` is called when the whole site needs to be rebuilt and `rebuild_done` is
+    /// called with the result of every rebuild, along with the path to reload.
+    pub fn run(
+        self,
+        mut site: Site,
+        fast_rebuild: bool,
+        utc_offset: UtcOffset,
+        recreate_site: impl Fn() -> Result<Site>,
+        rebuild_done: impl Fn(Result<()>, &str),
+    ) {
+        let recreate_site = || match recreate_site() {
+            Ok(s) => {
+                rebuild_done(Ok(()), "/x.js");
+                Some(s)
+            }
+            Err(e) => {
+                rebuild_done(Err(e), "");
+                None
+            }
+        };
+
+        let reload_sass = |site: &Site, paths: &Vec<&PathBuf>| {
+            let combined_paths =
+                paths.iter().map(|p| p.display().to_string()).collect::<Vec<String>>().join(", ");
+            let msg = format!("-> Sass file(s) changed {}", combined_paths);
+            console::info(&msg);
+            rebuild_done(
+                compile_sass(&site.base_path, &site.output_path),
+                &site.sass_path.to_string_lossy(),
+            );
+        };
+
+        let reload_templates = |site: &mut Site| {
+            rebuild_done(site.reload_templates(), &site.templates_path.to_string_lossy());
+        };
+
+        let copy_static = |site: &Site, path: &Path, partial_path: &Path| {
+            // Do nothing if the file/dir is on the ignore list
+            if let Some(gs) = &site.config.ignored_static_globset {
+                if gs.is_match(partial_path) {
+                    return;
+                }
+            }
+            // Do nothing if the file/dir was deleted
+            if !path.exists() {
+                return;
+            }
+
+            let msg = if path.is_dir() {
+                format!("-> Directory in `static` folder changed {}", path.display())
+            } else {
+                format!("-> Static file changed {}", path.display())
+            };
+
+            console::info(&msg);
+            if path.is_dir() {
+                rebuild_done(site.copy_static_directories(), &path.to_string_lossy());
+            } else {
+                rebuild_done(
+                    copy_file(
+                        path,
+                        &site.output_path,
+                        &site.static_path,
+                        site.config.hard_link_static,
+                    ),
+                    &partial_path.to_string_lossy(),
+                );
+            }
+        };
+
+        loop {
+            match self.rx.recv() {
+                Ok(Ok(events)) => {
+                    let changes = filter_events(
+                        events,
+                        &self.root_dir,
+                        &self.config_path,
+                        &site.config.ignored_content_globset,
+                        &site.config.ignored_watch_gitignore,
+                        &site.config.gitignore,
+                    );
+                    if changes.is_empty() {
+                        continue;
+                    }
+                    let format =
+                        format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+
+                    for (change_kind, change_group) in changes.iter() {
+                        let current_time =
+                            OffsetDateTime::now_utc().to_offset(utc_offset).format(&format);
+                        if let Ok(time_str) = current_time {
+                            println!("Change detected @ {}", time_str);
+                        } else {
+                            // if formatting fails for some reason
+                            println!("Change detected");
+                        };
+
+                        let start = Instant::now();
+                        match change_kind {
+                            ChangeKind::Content => {
+                                for (_, full_path, event_kind) in change_group.iter() {
+                                    console::info(&format!(
+                                        "-> Content changed {}",
+                                        full_path.display()
+                                    ));
+
+                                    let can_do_fast_reload =
+                                        *event_kind != SimpleFileSystemEventKind::Remove;
+
+                                    if fast_rebuild {
+                                        if can_do_fast_reload {
+                                            let filename = full_path
+                                                .file_name()
+                                                .unwrap_or_else(|| OsStr::new(""))
+                                                .to_string_lossy();
+                                            let res = if filename == "_index.md" {
+                                                site.add_and_render_section(full_path)
+                                            } else if filename.ends_with(".md") {
+                                                site.add_and_render_page(full_path)
+                                            } else {
+                                                // an asset changed? a folder renamed?
+                                                // should we make it smarter so it doesn't reload the whole site?
+                                                Err(anyhow!("dummy"))
+                                            };
+
+                                            if res.is_err() {
+                                                if let Some(s) = recreate_site() {
+                                                    site = s;
+                                                }
+                                            } else {
+                                                rebuild_done(res, &full_path.to_string_lossy());
+                                            }
+                                        } else {
+                                            // Should we be smarter than that? Is it worth it?
+                                            if let Some(s) = recreate_site() {
+                                                site = s;
+                                            }
+                                        }
+                                    } else if let Some(s) = recreate_site() {
+                                        site = s;
+                                    }
+                                }
+                            }
+                            ChangeKind::Templates => {
+                                let partial_paths: Vec<&PathBuf> =
+                                    change_group.iter().map(|(p, _, _)| p).collect();
+                                let full_paths: Vec<&PathBuf> =
+                                    change_group.iter().map(|(_, p, _)| p).collect();
+                                let combined_paths = full_paths
+                                    .iter()
+                                    .map(|p| p.display().to_string())
+                                    .collect::<Vec<String>>()
+                                    .join(", ");
+                                let msg = format!("-> Template file(s) changed {}", combined_paths);
+                                console::info(&msg);
+
+                                let shortcodes_updated = partial_paths
+                                    .iter()
+                                    .any(|p| p.starts_with("/templates/shortcodes"));
+                                // Rebuild site if shortcodes change; otherwise, just update template.
+                                if shortcodes_updated {
+                                    if let Some(s) = recreate_site() {
+                                        site = s;
+                                    }
+                                } else {
+                                    println!("Reloading only template");
+                                    reload_templates(&mut site)
+                                }
+                            }
+                            ChangeKind::StaticFiles => {
+                                for (partial_path, full_path, _) in change_group.iter() {
+                                    copy_static(&site, full_path, partial_path);
+                                }
+                            }
+                            ChangeKind::Sass => {
+                                let full_paths = change_group.iter().map(|(_, p, _)| p).collect();
+                                reload_sass(&site, &full_paths);
+                            }
+                            ChangeKind::Themes => {
+                                // No need to iterate over change group since we're rebuilding the site.
+                                console::info("-> Themes changed.");
+
+                                if let Some(s) = recreate_site() {
+                                    site = s;
+                                }
+                            }
+                            ChangeKind::Config => {
+                                // No need to iterate over change group since we're rebuilding the site.
+                                if site.live_reload.is_some() {
+                                    console::info("-> Config changed. The browser needs to be refreshed to make the changes visible.");
+                                } else {
+                                    console::info("-> Config changed.");
+                                }
+
+                                if let Some(s) = recreate_site() {
+                                    site = s;
+                                }
+                            }
+                        };
+                        messages::report_elapsed_time(start);
+                    }
+                }
+                Ok(Err(e)) => console::error(&format!("File system event errors: {:?}", e)),
+                // No event can come anymore
+                Err(_) => return,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::mpsc::channel;
+    use std::thread;
+
+    use notify_debouncer_full::notify::event::{DataChange, ModifyKind};
+    use notify_debouncer_full::notify::{Event, EventKind};
+    use notify_debouncer_full::DebouncedEvent;
+    use site::Site;
+    use tempfile::tempdir;
+    use time::UtcOffset;
+
+    use super::super::write_test_files;
+    use super::SiteWatcher;
+
+    #[test]
+    fn rebuilds_the_site_on_content_change() {
+        let tmp_dir = tempdir().unwrap();
+        let root_dir = tmp_dir.path().canonicalize().unwrap();
+        write_test_files(
+            &root_dir,
+            &[
+                ("config.toml", "base_url = \"https://example.com\"\n"),
+                ("templates/index.html", "{{ section.content | safe }}"),
+                ("content/_index.md", "+++\n+++\nHello"),
+            ],
+        );
+        let config_file = root_dir.join("config.toml");
+        let index_path = root_dir.join("content").join("_index.md");
+        let build = || {
+            let mut site = Site::new(&root_dir, &config_file)?;
+            site.load()?;
+            site.build()?;
+            Ok(site)
+        };
+        let site = build().unwrap();
+
+        let (tx, rx) = channel();
+        let watcher = SiteWatcher::with_receiver(&root_dir, &config_file, rx);
+        thread::scope(|s| {
+            let handle = s.spawn(move || {
+                watcher.run(site, false, UtcOffset::UTC, build, |res, _| res.unwrap())
+            });
+            fs::write(&index_path, "+++\n+++\nGoodbye").unwrap();
+            let event = Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content)))
+                .add_path(index_path.clone());
+            tx.send(Ok(vec![DebouncedEvent::from(event)])).unwrap();
+            // Stops the watcher once it has handled the event
+            drop(tx);
+            handle.join().unwrap();
+        });
+
+        let output = fs::read_to_string(root_dir.join("public").join("index.html")).unwrap();
+        assert!(output.contains("Goodbye"));
+    }
+}
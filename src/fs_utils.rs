@@ -3,11 +3,13 @@
 
 use libs::ahash::HashMap;
 use libs::globset::GlobSet;
+use libs::ignore::gitignore::Gitignore;
 use notify_debouncer_full::notify::event::*;
 use notify_debouncer_full::DebouncedEvent;
 use std::fs::read_dir;
 use std::path::{Path, PathBuf};
 use utils::fs::is_temp_file;
+use utils::globs::NestedGitignore;
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub enum ChangeKind {
@@ -59,6 +61,8 @@ pub fn filter_events(
     root_dir: &Path,
     config_path: &Path,
     ignored_content_globset: &Option<GlobSet>,
+    ignored_watch: &Option<Gitignore>,
+    gitignore: &Option<NestedGitignore>,
 ) -> HashMap<ChangeKind, Vec<MeaningfulEvent>> {
     // Arrange events from oldest to newest.
     events.sort_by(|e1, e2| e1.time.cmp(&e2.time));
@@ -94,6 +98,16 @@ pub fn filter_events(
             continue;
         }
 
+        if is_ignored_by_watch(ignored_watch, root_dir, &path) {
+            continue;
+        }
+
+        if let Some(gitignore) = gitignore {
+            if gitignore.is_ignored(&path, path.is_dir()) {
+                continue;
+            }
+        }
+
         if is_temp_file(&path) {
             continue;
         }
@@ -130,6 +144,16 @@ fn is_ignored_file(ignored_content_globset: &Option<GlobSet>, path: &Path) -> bo
     }
 }
 
+/// Check if the path, or any of its parents, matches the `ignored_watch` patterns
+fn is_ignored_by_watch(ignored_watch: &Option<Gitignore>, root_dir: &Path, path: &Path) -> bool {
+    match (ignored_watch, path.strip_prefix(root_dir)) {
+        (Some(gi), Ok(relative)) => {
+            gi.matched_path_or_any_parents(relative, path.is_dir()).is_ignore()
+        }
+        _ => false,
+    }
+}
+
 /// Check if the directory at path contains any file
 fn is_folder_empty(dir: &Path) -> bool {
     // Can panic if we don't have the rights I guess?
@@ -164,11 +188,14 @@ fn detect_change_kind(pwd: &Path, path: &Path, config_path: &Path) -> (ChangeKin
 
 #[cfg(test)]
 mod tests {
+    use libs::ignore::gitignore::GitignoreBuilder;
     use notify_debouncer_full::notify::event::*;
+    use notify_debouncer_full::DebouncedEvent;
+    use std::fs;
     use std::path::{Path, PathBuf};
 
     use super::{
-        detect_change_kind, get_relevant_event_kind, is_temp_file, ChangeKind,
+        detect_change_kind, filter_events, get_relevant_event_kind, is_temp_file, ChangeKind,
         SimpleFileSystemEventKind,
     };
 
@@ -313,4 +340,51 @@ mod tests {
         let config_filename = Path::new("config.toml");
         assert_eq!(expected, detect_change_kind(pwd, path, config_filename));
     }
+
+    #[test]
+    fn ignored_watch_paths_do_not_trigger_rebuilds() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let root_dir = tmp_dir.path();
+        let config_path = root_dir.join("config.toml");
+        fs::write(&config_path, "").unwrap();
+        let post_path = root_dir.join("content").join("post.md");
+        let module_path = root_dir.join("static").join("node_modules").join("lib.js");
+        fs::create_dir_all(module_path.parent().unwrap()).unwrap();
+        fs::create_dir_all(post_path.parent().unwrap()).unwrap();
+        fs::write(&post_path, "").unwrap();
+        fs::write(&module_path, "").unwrap();
+
+        let mut builder = GitignoreBuilder::new(".");
+        builder.add_line(None, "node_modules/").unwrap();
+        let ignored_watch = Some(builder.build().unwrap());
+
+        let modified = |path: &Path| {
+            DebouncedEvent::from(
+                Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content)))
+                    .add_path(path.to_path_buf()),
+            )
+        };
+        let changes = filter_events(
+            vec![modified(&module_path), modified(&post_path)],
+            root_dir,
+            &config_path,
+            &None,
+            &ignored_watch,
+            &None,
+        );
+        assert_eq!(changes.len(), 1);
+        let content_changes = &changes[&ChangeKind::Content];
+        assert_eq!(content_changes.len(), 1);
+        assert_eq!(content_changes[0].1, post_path);
+
+        let changes = filter_events(
+            vec![modified(&module_path)],
+            root_dir,
+            &config_path,
+            &None,
+            &None,
+            &None,
+        );
+        assert!(changes.contains_key(&ChangeKind::StaticFiles));
+    }
 }
@@ -3,7 +3,8 @@ use std::time::Instant;
 
 use cli::{Cli, Command};
 use errors::anyhow;
-use utils::net::{get_available_port, port_is_available};
+use site::CheckScope;
+use utils::net::resolve_port;
 
 use clap::{CommandFactory, Parser};
 use time::UtcOffset;
@@ -57,10 +58,34 @@ fn main() {
                 std::process::exit(1);
             }
         }
-        Command::Build { base_url, output_dir, force, drafts } => {
+        Command::Build {
+            base_url,
+            output_dir,
+            force,
+            drafts,
+            watch,
+            relative_urls,
+            template_variants,
+        } => {
             console::info("Building site...");
             let start = Instant::now();
             let (root_dir, config_file) = get_config_file_path(&cli_dir, &cli.config);
+            if watch {
+                if let Err(e) = cmd::build_and_watch(
+                    &root_dir,
+                    &config_file,
+                    base_url.as_deref(),
+                    output_dir.as_deref(),
+                    force,
+                    drafts,
+                    relative_urls,
+                    UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC),
+                ) {
+                    messages::unravel_errors("Failed to build the site", &e);
+                    std::process::exit(1);
+                }
+                return;
+            }
             match cmd::build(
                 &root_dir,
                 &config_file,
@@ -68,6 +93,8 @@ fn main() {
                 output_dir.as_deref(),
                 force,
                 drafts,
+                relative_urls,
+                &template_variants,
             ) {
                 Ok(()) => messages::report_elapsed_time(start),
                 Err(e) => {
@@ -78,7 +105,7 @@ fn main() {
         }
         Command::Serve {
             interface,
-            mut port,
+            port,
             output_dir,
             force,
             base_url,
@@ -86,18 +113,15 @@ fn main() {
             open,
             fast,
             no_port_append,
+            no_port_scan,
         } => {
-            if port != 1111 && !port_is_available(port) {
-                console::error("The requested port is not available");
-                std::process::exit(1);
-            }
-
-            if !port_is_available(port) {
-                port = get_available_port(1111).unwrap_or_else(|| {
-                    console::error("No port available");
+            let port = match resolve_port(port, 1111, !no_port_scan) {
+                Ok(p) => p,
+                Err(e) => {
+                    console::error(&e.to_string());
                     std::process::exit(1);
-                });
-            }
+                }
+            };
 
             let (root_dir, config_file) = get_config_file_path(&cli_dir, &cli.config);
             console::info("Building site...");
@@ -119,11 +143,26 @@ fn main() {
                 std::process::exit(1);
             }
         }
-        Command::Check { drafts } => {
+        Command::Check { drafts, internal_only, external_only, external_links_report } => {
             console::info("Checking site...");
             let start = Instant::now();
             let (root_dir, config_file) = get_config_file_path(&cli_dir, &cli.config);
-            match cmd::check(&root_dir, &config_file, None, None, drafts) {
+            let scope = if internal_only {
+                CheckScope::InternalOnly
+            } else if external_only {
+                CheckScope::ExternalOnly
+            } else {
+                CheckScope::All
+            };
+            match cmd::check(
+                &root_dir,
+                &config_file,
+                None,
+                None,
+                drafts,
+                scope,
+                external_links_report.as_deref(),
+            ) {
                 Ok(()) => messages::report_elapsed_time(start),
                 Err(e) => {
                     messages::unravel_errors("Failed to check the site", &e);
@@ -131,6 +170,13 @@ fn main() {
                 }
             }
         }
+        Command::Import { from, source, force } => {
+            console::info("Importing the content...");
+            if let Err(e) = cmd::import(from, &source, &cli_dir, force) {
+                messages::unravel_errors("Failed to import the content", &e);
+                std::process::exit(1);
+            }
+        }
         Command::Completion { shell } => {
             let cmd = &mut Cli::command();
             clap_complete::generate(shell, cmd, cmd.get_name().to_string(), &mut std::io::stdout());
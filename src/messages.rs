@@ -52,6 +52,17 @@ pub fn warn_about_ignored_pages(site: &Site) {
     }
 }
 
+/// Display a warning in the console for every code block language that can't be highlighted
+pub fn warn_about_unknown_code_languages(site: &Site) {
+    for (path, lang) in site.find_unknown_code_languages() {
+        console::warn(&format!(
+            "Unknown language `{}` in a code block of {}",
+            lang,
+            path.display()
+        ));
+    }
+}
+
 /// Print the time elapsed rounded to 1 decimal
 pub fn report_elapsed_time(instant: Instant) {
     let duration: Duration = instant.elapsed().try_into().unwrap();